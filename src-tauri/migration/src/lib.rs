@@ -14,6 +14,31 @@ mod m20230728_092102_create_scores;
 mod m20230731_044608_create_setlists;
 mod m20230731_044622_create_pieces_setlists;
 mod m20230803_094322_create_ensemble_parts_instruments;
+mod m20261019_120000_create_tag_groups;
+mod m20261019_120100_add_tags_hierarchy;
+mod m20261019_130000_add_pieces_publication;
+mod m20261019_140000_add_musicians_details;
+mod m20261019_140100_create_musician_aliases;
+mod m20261019_150000_create_roles;
+mod m20261019_160000_add_instrument_metadata;
+mod m20261019_170000_create_players;
+mod m20261019_170100_create_ensemble_parts_players;
+mod m20261019_170200_create_part_assignments;
+mod m20261019_180000_add_physical_copies;
+mod m20261019_180100_create_loans;
+mod m20261019_190000_create_acquisitions;
+mod m20261019_200000_create_performances;
+mod m20261019_200100_create_pieces_performances;
+mod m20261019_210000_create_events;
+mod m20261019_220000_add_pieces_copyright;
+mod m20261019_230000_add_pieces_ratings;
+mod m20261019_230100_create_rehearsal_notes;
+mod m20261019_240000_add_pdf_metadata;
+mod m20261019_250000_add_setlist_order;
+mod m20261019_260000_add_file_hashes;
+mod m20261019_270000_create_drafts;
+mod m20261019_270100_create_draft_files;
+mod m20261019_280000_create_part_files;
 
 pub struct Migrator;
 
@@ -35,6 +60,31 @@ impl MigratorTrait for Migrator {
             Box::new(m20230731_044608_create_setlists::Migration),
             Box::new(m20230731_044622_create_pieces_setlists::Migration),
             Box::new(m20230803_094322_create_ensemble_parts_instruments::Migration),
+            Box::new(m20261019_120000_create_tag_groups::Migration),
+            Box::new(m20261019_120100_add_tags_hierarchy::Migration),
+            Box::new(m20261019_130000_add_pieces_publication::Migration),
+            Box::new(m20261019_140000_add_musicians_details::Migration),
+            Box::new(m20261019_140100_create_musician_aliases::Migration),
+            Box::new(m20261019_150000_create_roles::Migration),
+            Box::new(m20261019_160000_add_instrument_metadata::Migration),
+            Box::new(m20261019_170000_create_players::Migration),
+            Box::new(m20261019_170100_create_ensemble_parts_players::Migration),
+            Box::new(m20261019_170200_create_part_assignments::Migration),
+            Box::new(m20261019_180000_add_physical_copies::Migration),
+            Box::new(m20261019_180100_create_loans::Migration),
+            Box::new(m20261019_190000_create_acquisitions::Migration),
+            Box::new(m20261019_200000_create_performances::Migration),
+            Box::new(m20261019_200100_create_pieces_performances::Migration),
+            Box::new(m20261019_210000_create_events::Migration),
+            Box::new(m20261019_220000_add_pieces_copyright::Migration),
+            Box::new(m20261019_230000_add_pieces_ratings::Migration),
+            Box::new(m20261019_230100_create_rehearsal_notes::Migration),
+            Box::new(m20261019_240000_add_pdf_metadata::Migration),
+            Box::new(m20261019_250000_add_setlist_order::Migration),
+            Box::new(m20261019_260000_add_file_hashes::Migration),
+            Box::new(m20261019_270000_create_drafts::Migration),
+            Box::new(m20261019_270100_create_draft_files::Migration),
+            Box::new(m20261019_280000_create_part_files::Migration),
         ]
     }
 }
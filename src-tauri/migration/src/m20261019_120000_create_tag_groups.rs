@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TagGroups::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TagGroups::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TagGroups::Name).string().not_null())
+                    .col(ColumnDef::new(TagGroups::Color).string())
+                    .col(
+                        ColumnDef::new(TagGroups::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .col(
+                        ColumnDef::new(TagGroups::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TagGroups::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum TagGroups {
+    Table,
+    Id,
+    Name,
+    Color,
+    CreatedAt,
+    UpdatedAt,
+}
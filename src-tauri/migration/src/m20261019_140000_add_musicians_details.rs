@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only allows one column per ALTER TABLE statement
+        let columns = vec![
+            ColumnDef::new(Musicians::BirthYear).integer().to_owned(),
+            ColumnDef::new(Musicians::DeathYear).integer().to_owned(),
+            ColumnDef::new(Musicians::Nationality).string().to_owned(),
+            ColumnDef::new(Musicians::SortName).string().to_owned(),
+            ColumnDef::new(Musicians::DisplayName).string().to_owned(),
+            ColumnDef::new(Musicians::IsAnonymous)
+                .boolean()
+                .not_null()
+                .default(false)
+                .to_owned(),
+        ];
+
+        for column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Musicians::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = vec![
+            Musicians::BirthYear,
+            Musicians::DeathYear,
+            Musicians::Nationality,
+            Musicians::SortName,
+            Musicians::DisplayName,
+            Musicians::IsAnonymous,
+        ];
+
+        for column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Musicians::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Musicians {
+    Table,
+    BirthYear,
+    DeathYear,
+    Nationality,
+    SortName,
+    DisplayName,
+    IsAnonymous,
+}
@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230724_024754_create_musicians::Musicians;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MusicianAliases::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MusicianAliases::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MusicianAliases::MusicianId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MusicianAliases::Name).string().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_musician_alias_musician")
+                            .from(MusicianAliases::Table, MusicianAliases::MusicianId)
+                            .to(Musicians::Table, Musicians::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MusicianAliases::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum MusicianAliases {
+    Table,
+    Id,
+    MusicianId,
+    Name,
+}
@@ -0,0 +1,81 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Roles::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Roles::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Roles::Name).string().not_null().unique_key())
+                    .col(ColumnDef::new(Roles::Order).integer().not_null())
+                    .col(
+                        ColumnDef::new(Roles::IsDefault)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(Roles::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .col(
+                        ColumnDef::new(Roles::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // the roles that used to be hard-coded, so existing credits stay valid
+        let default_roles = [
+            "composer",
+            "arranger",
+            "orchestrator",
+            "lyricist",
+            "transcriber",
+        ];
+        let mut insert = Query::insert()
+            .into_table(Roles::Table)
+            .columns([Roles::Name, Roles::Order, Roles::IsDefault])
+            .to_owned();
+        for (index, role) in default_roles.iter().enumerate() {
+            insert.values_panic([(*role).into(), (index as i32 + 1).into(), true.into()]);
+        }
+
+        manager.exec_stmt(insert).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Roles::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum Roles {
+    Table,
+    Id,
+    Name,
+    Order,
+    IsDefault,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only allows one column per ALTER TABLE statement
+        let columns = vec![
+            ColumnDef::new(Instruments::Transposition)
+                .integer()
+                .to_owned(),
+            ColumnDef::new(Instruments::Clefs).string().to_owned(),
+            ColumnDef::new(Instruments::RangeLow).string().to_owned(),
+            ColumnDef::new(Instruments::RangeHigh).string().to_owned(),
+            ColumnDef::new(Instruments::Family).string().to_owned(),
+            ColumnDef::new(Instruments::ScoreOrder).integer().to_owned(),
+        ];
+
+        for column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Instruments::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = vec![
+            Instruments::Transposition,
+            Instruments::Clefs,
+            Instruments::RangeLow,
+            Instruments::RangeHigh,
+            Instruments::Family,
+            Instruments::ScoreOrder,
+        ];
+
+        for column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Instruments::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Instruments {
+    Table,
+    Transposition,
+    Clefs,
+    RangeLow,
+    RangeHigh,
+    Family,
+    ScoreOrder,
+}
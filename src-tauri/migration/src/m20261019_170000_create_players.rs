@@ -0,0 +1,60 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Players::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Players::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Players::Name).string().not_null())
+                    .col(ColumnDef::new(Players::Email).string())
+                    .col(ColumnDef::new(Players::Phone).string())
+                    .col(ColumnDef::new(Players::Notes).string())
+                    .col(
+                        ColumnDef::new(Players::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .col(
+                        ColumnDef::new(Players::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Players::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum Players {
+    Table,
+    Id,
+    Name,
+    Email,
+    Phone,
+    Notes,
+    CreatedAt,
+    UpdatedAt,
+}
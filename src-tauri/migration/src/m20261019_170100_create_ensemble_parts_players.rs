@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230724_042543_create_ensembles_parts::EnsemblesParts;
+use super::m20261019_170000_create_players::Players;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EnsemblePartsPlayers::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EnsemblePartsPlayers::PartId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EnsemblePartsPlayers::PlayerId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(EnsemblePartsPlayers::PartId)
+                            .col(EnsemblePartsPlayers::PlayerId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_part_player_part")
+                            .from(EnsemblePartsPlayers::Table, EnsemblePartsPlayers::PartId)
+                            .to(EnsemblesParts::Table, EnsemblesParts::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_part_player_player")
+                            .from(EnsemblePartsPlayers::Table, EnsemblePartsPlayers::PlayerId)
+                            .to(Players::Table, Players::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EnsemblePartsPlayers::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum EnsemblePartsPlayers {
+    Table,
+    PartId,
+    PlayerId,
+}
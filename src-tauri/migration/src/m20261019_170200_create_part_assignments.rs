@@ -0,0 +1,99 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230724_024751_create_parts::Parts;
+use super::m20230731_044608_create_setlists::Setlists;
+use super::m20261019_170000_create_players::Players;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PartAssignments::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PartAssignments::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PartAssignments::SetlistId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PartAssignments::PartId).integer().not_null())
+                    .col(
+                        ColumnDef::new(PartAssignments::PlayerId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PartAssignments::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .col(
+                        ColumnDef::new(PartAssignments::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_part_assignment_setlist")
+                            .from(PartAssignments::Table, PartAssignments::SetlistId)
+                            .to(Setlists::Table, Setlists::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_part_assignment_part")
+                            .from(PartAssignments::Table, PartAssignments::PartId)
+                            .to(Parts::Table, Parts::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_part_assignment_player")
+                            .from(PartAssignments::Table, PartAssignments::PlayerId)
+                            .to(Players::Table, Players::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_part_assignment_unique")
+                            .unique()
+                            .col(PartAssignments::SetlistId)
+                            .col(PartAssignments::PartId)
+                            .col(PartAssignments::PlayerId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PartAssignments::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum PartAssignments {
+    Table,
+    Id,
+    SetlistId,
+    PartId,
+    PlayerId,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,98 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230724_024751_create_parts::Parts;
+use super::m20230724_024826_create_ensembles::Ensembles;
+use super::m20261019_170000_create_players::Players;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Loans::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Loans::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Loans::PartId).integer().not_null())
+                    .col(ColumnDef::new(Loans::PlayerId).integer())
+                    .col(ColumnDef::new(Loans::EnsembleId).integer())
+                    .col(ColumnDef::new(Loans::Copies).integer().not_null())
+                    .col(
+                        ColumnDef::new(Loans::ReturnedCopies)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(Loans::DueDate).string())
+                    .col(ColumnDef::new(Loans::ReturnedAt).string())
+                    .col(ColumnDef::new(Loans::Notes).string())
+                    .col(
+                        ColumnDef::new(Loans::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .col(
+                        ColumnDef::new(Loans::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_loan_part")
+                            .from(Loans::Table, Loans::PartId)
+                            .to(Parts::Table, Parts::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_loan_player")
+                            .from(Loans::Table, Loans::PlayerId)
+                            .to(Players::Table, Players::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_loan_ensemble")
+                            .from(Loans::Table, Loans::EnsembleId)
+                            .to(Ensembles::Table, Ensembles::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Loans::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Loans {
+    Table,
+    Id,
+    PartId,
+    PlayerId,
+    EnsembleId,
+    Copies,
+    ReturnedCopies,
+    DueDate,
+    ReturnedAt,
+    Notes,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230724_024746_create_pieces::Pieces;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Acquisitions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Acquisitions::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Acquisitions::PieceId).integer().not_null())
+                    .col(
+                        ColumnDef::new(Acquisitions::AcquisitionType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Acquisitions::Vendor).string())
+                    .col(ColumnDef::new(Acquisitions::Cost).double())
+                    .col(ColumnDef::new(Acquisitions::Currency).string())
+                    .col(ColumnDef::new(Acquisitions::AcquiredDate).string())
+                    .col(ColumnDef::new(Acquisitions::RentalStart).string())
+                    .col(ColumnDef::new(Acquisitions::RentalEnd).string())
+                    .col(ColumnDef::new(Acquisitions::ReturnedDate).string())
+                    .col(ColumnDef::new(Acquisitions::PerformanceRights).string())
+                    .col(ColumnDef::new(Acquisitions::RecordingRights).string())
+                    .col(ColumnDef::new(Acquisitions::Notes).string())
+                    .col(
+                        ColumnDef::new(Acquisitions::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .col(
+                        ColumnDef::new(Acquisitions::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_acquisition_piece")
+                            .from(Acquisitions::Table, Acquisitions::PieceId)
+                            .to(Pieces::Table, Pieces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Acquisitions::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Acquisitions {
+    Table,
+    Id,
+    PieceId,
+    AcquisitionType,
+    Vendor,
+    Cost,
+    Currency,
+    AcquiredDate,
+    RentalStart,
+    RentalEnd,
+    ReturnedDate,
+    PerformanceRights,
+    RecordingRights,
+    Notes,
+    CreatedAt,
+    UpdatedAt,
+}
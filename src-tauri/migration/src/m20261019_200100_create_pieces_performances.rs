@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230724_024746_create_pieces::Pieces;
+use super::m20261019_200000_create_performances::Performances;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PiecesPerformances::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PiecesPerformances::PieceId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PiecesPerformances::PerformanceId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(PiecesPerformances::PieceId)
+                            .col(PiecesPerformances::PerformanceId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_piece_performance_piece")
+                            .from(PiecesPerformances::Table, PiecesPerformances::PieceId)
+                            .to(Pieces::Table, Pieces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_piece_performance_performance")
+                            .from(PiecesPerformances::Table, PiecesPerformances::PerformanceId)
+                            .to(Performances::Table, Performances::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PiecesPerformances::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum PiecesPerformances {
+    Table,
+    PieceId,
+    PerformanceId,
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // scores and parts both keep what was read from their PDF
+        for table in [Pdf::Scores, Pdf::Parts] {
+            // SQLite only allows one column per ALTER TABLE statement
+            let columns = vec![
+                ColumnDef::new(Pdf::PageCount).integer().to_owned(),
+                ColumnDef::new(Pdf::PageSize).string().to_owned(),
+                ColumnDef::new(Pdf::PdfTitle).string().to_owned(),
+                ColumnDef::new(Pdf::PdfAuthor).string().to_owned(),
+                ColumnDef::new(Pdf::PdfProducer).string().to_owned(),
+                ColumnDef::new(Pdf::FileSize).big_integer().to_owned(),
+            ];
+
+            for column in columns {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(table.clone())
+                            .add_column(column)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [Pdf::Scores, Pdf::Parts] {
+            let columns = vec![
+                Pdf::PageCount,
+                Pdf::PageSize,
+                Pdf::PdfTitle,
+                Pdf::PdfAuthor,
+                Pdf::PdfProducer,
+                Pdf::FileSize,
+            ];
+
+            for column in columns {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(table.clone())
+                            .drop_column(column)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden, Clone)]
+enum Pdf {
+    Scores,
+    Parts,
+    PageCount,
+    PageSize,
+    PdfTitle,
+    PdfAuthor,
+    PdfProducer,
+    FileSize,
+}
@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230724_024758_create_instruments::Instruments;
+use super::m20261019_270000_create_drafts::Drafts;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DraftFiles::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DraftFiles::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(DraftFiles::DraftId).integer().not_null())
+                    .col(ColumnDef::new(DraftFiles::Path).string().not_null())
+                    .col(ColumnDef::new(DraftFiles::Entry).string())
+                    .col(ColumnDef::new(DraftFiles::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(DraftFiles::Kind)
+                            .string()
+                            .not_null()
+                            .default("part"),
+                    )
+                    .col(ColumnDef::new(DraftFiles::InstrumentId).integer())
+                    .col(
+                        ColumnDef::new(DraftFiles::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .col(
+                        ColumnDef::new(DraftFiles::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_draft_file_draft")
+                            .from(DraftFiles::Table, DraftFiles::DraftId)
+                            .to(Drafts::Table, Drafts::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_draft_file_instrument")
+                            .from(DraftFiles::Table, DraftFiles::InstrumentId)
+                            .to(Instruments::Table, Instruments::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DraftFiles::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum DraftFiles {
+    Table,
+    Id,
+    DraftId,
+    Path,
+    Entry,
+    Name,
+    Kind,
+    InstrumentId,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,105 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+use super::m20230724_024751_create_parts::Parts;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PartFiles::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PartFiles::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PartFiles::PartId).integer().not_null())
+                    .col(ColumnDef::new(PartFiles::Path).string().not_null())
+                    .col(
+                        ColumnDef::new(PartFiles::Label)
+                            .string()
+                            .not_null()
+                            .default("original"),
+                    )
+                    .col(
+                        ColumnDef::new(PartFiles::IsPrimary)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(PartFiles::Version)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(ColumnDef::new(PartFiles::ReplacedAt).string())
+                    .col(ColumnDef::new(PartFiles::Sha256).string())
+                    .col(ColumnDef::new(PartFiles::FileSize).big_integer())
+                    .col(
+                        ColumnDef::new(PartFiles::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .col(
+                        ColumnDef::new(PartFiles::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default("DATETIME PLACEHOLDER"),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_part_file_part")
+                            .from(PartFiles::Table, PartFiles::PartId)
+                            .to(Parts::Table, Parts::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // the file each part already has becomes its primary original
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "INSERT INTO part_files (part_id, path, label, is_primary, version, sha256, file_size) \
+                 SELECT id, path, 'original', 1, 1, sha256, file_size FROM parts \
+                 WHERE path IS NOT NULL AND path != ''",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PartFiles::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum PartFiles {
+    Table,
+    Id,
+    PartId,
+    Path,
+    Label,
+    IsPrimary,
+    Version,
+    ReplacedAt,
+    Sha256,
+    FileSize,
+    CreatedAt,
+    UpdatedAt,
+}
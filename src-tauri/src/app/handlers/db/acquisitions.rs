@@ -0,0 +1,89 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::acquisitions::{self, AcquisitionDetails};
+
+#[command]
+pub fn acquisitions_get_by_piece(
+    app_handle: AppHandle,
+    piece_id: i32,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(acquisitions::get_by_piece(db, piece_id)));
+    match result {
+        Ok(acquisitions) => Ok(acquisitions),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn acquisitions_get_by_id(app_handle: AppHandle, id: i32) -> Result<serde_json::Value, String> {
+    let result = app_handle.db(|db| block_on(acquisitions::get_by_id(db, id)));
+    match result {
+        Ok(acquisition) => Ok(acquisition),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn acquisitions_add(
+    app_handle: AppHandle,
+    piece_id: i32,
+    details: AcquisitionDetails,
+) -> Result<i32, String> {
+    let result = app_handle.db(|db| block_on(acquisitions::add(db, piece_id, details)));
+    match result {
+        Ok(id) => Ok(id),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn acquisitions_update(
+    app_handle: AppHandle,
+    id: i32,
+    details: AcquisitionDetails,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(acquisitions::update(db, id, details)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn acquisitions_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(acquisitions::delete(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn acquisitions_mark_returned(
+    app_handle: AppHandle,
+    id: i32,
+    returned_date: Option<String>,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(acquisitions::mark_returned(db, id, returned_date)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn acquisitions_get_rentals_due(
+    app_handle: AppHandle,
+    days: Option<i64>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let days = days.unwrap_or(30);
+    let result = app_handle.db(|db| block_on(acquisitions::get_rentals_due(db, days)));
+    match result {
+        Ok(rentals) => Ok(rentals),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,85 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::assignments;
+use crate::services::coverage::Substitution;
+
+#[command]
+pub fn assignments_get_by_setlist(
+    app_handle: AppHandle,
+    setlist_id: i32,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(assignments::get_by_setlist(db, setlist_id)));
+    match result {
+        Ok(pieces) => Ok(pieces),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn assignments_set(
+    app_handle: AppHandle,
+    setlist_id: i32,
+    part_id: i32,
+    player_ids: Vec<i32>,
+) -> Result<(), String> {
+    let result =
+        app_handle.db(|db| block_on(assignments::set(db, setlist_id, part_id, player_ids)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn assignments_assign_from_roster(
+    app_handle: AppHandle,
+    setlist_id: i32,
+    ensemble_id: i32,
+    substitutions: Option<Vec<Substitution>>,
+) -> Result<usize, String> {
+    let substitutions = substitutions.unwrap_or_default();
+    let result = app_handle.db(|db| {
+        block_on(assignments::assign_from_roster(
+            db,
+            setlist_id,
+            ensemble_id,
+            &substitutions,
+        ))
+    });
+    match result {
+        Ok(assigned) => Ok(assigned),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn assignments_get_player_sheet(
+    app_handle: AppHandle,
+    setlist_id: i32,
+    player_id: i32,
+) -> Result<serde_json::Value, String> {
+    let result =
+        app_handle.db(|db| block_on(assignments::get_player_sheet(db, setlist_id, player_id)));
+    match result {
+        Ok(sheet) => Ok(sheet),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn assignments_get_piece_sheet(
+    app_handle: AppHandle,
+    setlist_id: i32,
+    piece_id: i32,
+) -> Result<serde_json::Value, String> {
+    let result =
+        app_handle.db(|db| block_on(assignments::get_piece_sheet(db, setlist_id, piece_id)));
+    match result {
+        Ok(sheet) => Ok(sheet),
+        Err(e) => Err(e.to_string()),
+    }
+}
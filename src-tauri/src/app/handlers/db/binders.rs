@@ -0,0 +1,30 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::binders;
+
+#[command]
+pub fn binders_build(
+    app_handle: AppHandle,
+    setlist_id: i32,
+    instrument_id: Option<i32>,
+    ensemble_part_id: Option<i32>,
+    destination: String,
+) -> Result<serde_json::Value, String> {
+    let result = app_handle.db(|db| {
+        block_on(binders::build(
+            db,
+            setlist_id,
+            instrument_id,
+            ensemble_part_id,
+            destination,
+        ))
+    });
+    match result {
+        Ok(binder) => Ok(binder),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,31 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::copyright;
+use crate::settings::AppSettings;
+
+#[command]
+pub fn copyright_evaluate(
+    app_handle: AppHandle,
+    piece_id: i32,
+) -> Result<serde_json::Value, String> {
+    let rule = AppSettings::read().copyright_rule;
+    let result = app_handle.db(|db| block_on(copyright::evaluate_piece(db, piece_id, &rule)));
+    match result {
+        Ok(copyright) => Ok(copyright),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn copyright_evaluate_all(app_handle: AppHandle) -> Result<usize, String> {
+    let rule = AppSettings::read().copyright_rule;
+    let result = app_handle.db(|db| block_on(copyright::evaluate_all(db, &rule)));
+    match result {
+        Ok(count) => Ok(count),
+        Err(e) => Err(e.to_string()),
+    }
+}
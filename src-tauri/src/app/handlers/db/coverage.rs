@@ -0,0 +1,46 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::coverage::{self, Substitution};
+
+#[command]
+pub fn coverage_check(
+    app_handle: AppHandle,
+    piece_id: i32,
+    ensemble_id: i32,
+    substitutions: Option<Vec<Substitution>>,
+) -> Result<serde_json::Value, String> {
+    let substitutions = substitutions.unwrap_or_default();
+    let result =
+        app_handle.db(|db| block_on(coverage::check(db, piece_id, ensemble_id, &substitutions)));
+    match result {
+        Ok(report) => Ok(report),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn coverage_get_playable(
+    app_handle: AppHandle,
+    ensemble_id: i32,
+    substitutions: Option<Vec<Substitution>>,
+    min_coverage: Option<f64>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let substitutions = substitutions.unwrap_or_default();
+    let min_coverage = min_coverage.unwrap_or(0.0);
+    let result = app_handle.db(|db| {
+        block_on(coverage::get_playable(
+            db,
+            ensemble_id,
+            &substitutions,
+            min_coverage,
+        ))
+    });
+    match result {
+        Ok(pieces) => Ok(pieces),
+        Err(e) => Err(e.to_string()),
+    }
+}
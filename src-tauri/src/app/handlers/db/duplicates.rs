@@ -0,0 +1,35 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::duplicates::{self, Candidate, LIKELY_DUPLICATE_SCORE};
+
+#[command]
+pub fn duplicates_get_clusters(
+    app_handle: AppHandle,
+    min_score: Option<f64>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let min_score = min_score.unwrap_or(LIKELY_DUPLICATE_SCORE);
+    let result = app_handle.db(|db| block_on(duplicates::find_clusters(db, min_score)));
+    match result {
+        Ok(clusters) => Ok(clusters),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn duplicates_find_candidates(
+    app_handle: AppHandle,
+    candidate: Candidate,
+    min_score: Option<f64>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let min_score = min_score.unwrap_or(LIKELY_DUPLICATE_SCORE);
+    let result =
+        app_handle.db(|db| block_on(duplicates::find_candidates(db, &candidate, min_score)));
+    match result {
+        Ok(pieces) => Ok(pieces),
+        Err(e) => Err(e.to_string()),
+    }
+}
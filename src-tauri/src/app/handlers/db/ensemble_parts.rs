@@ -67,3 +67,22 @@ pub fn ensemble_parts_set_instruments(
         Err(e) => Err(e.to_string()),
     }
 }
+
+#[command]
+pub fn ensemble_parts_set_players(
+    app_handle: AppHandle,
+    ensemble_part_id: i32,
+    player_ids: Vec<i32>,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| {
+        block_on(ensemble_parts::set_players(
+            db,
+            ensemble_part_id,
+            player_ids,
+        ))
+    });
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
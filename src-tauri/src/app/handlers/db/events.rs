@@ -0,0 +1,86 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::events::{self, EventDetails};
+
+use std::path::PathBuf;
+
+#[command]
+pub fn events_get_all(app_handle: AppHandle) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(events::get_all(db)));
+    match result {
+        Ok(events) => Ok(events),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn events_get_by_id(app_handle: AppHandle, id: i32) -> Result<serde_json::Value, String> {
+    let result = app_handle.db(|db| block_on(events::get_by_id(db, id)));
+    match result {
+        Ok(event) => Ok(event),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn events_get_by_setlist(
+    app_handle: AppHandle,
+    setlist_id: i32,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(events::get_by_setlist(db, setlist_id)));
+    match result {
+        Ok(events) => Ok(events),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn events_add(app_handle: AppHandle, details: EventDetails) -> Result<i32, String> {
+    let result = app_handle.db(|db| block_on(events::add(db, details)));
+    match result {
+        Ok(id) => Ok(id),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn events_update(app_handle: AppHandle, id: i32, details: EventDetails) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(events::update(db, id, details)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn events_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(events::delete(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn events_export_ics(app_handle: AppHandle, path: String) -> Result<usize, String> {
+    let path = PathBuf::from(path);
+    let result = app_handle.db(|db| block_on(events::export_ics(db, &path)));
+    match result {
+        Ok(count) => Ok(count),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn events_import_ics(app_handle: AppHandle, path: String) -> Result<serde_json::Value, String> {
+    let path = PathBuf::from(path);
+    let result = app_handle.db(|db| block_on(events::import_ics(db, &path)));
+    match result {
+        Ok(summary) => Ok(summary),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,62 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::files;
+use crate::settings::AppSettings;
+
+#[command]
+pub fn files_verify(
+    app_handle: AppHandle,
+    piece_id: Option<i32>,
+) -> Result<serde_json::Value, String> {
+    let result = app_handle.db(|db| block_on(files::verify(db, piece_id)));
+    match result {
+        Ok(report) => Ok(report),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn files_deduplicate(
+    app_handle: AppHandle,
+    piece_id: Option<i32>,
+) -> Result<serde_json::Value, String> {
+    let store_dir = files::store_dir(&AppSettings::read().working_directory);
+    let result = app_handle.db(|db| block_on(files::deduplicate(db, &store_dir, piece_id)));
+    match result {
+        Ok(report) => Ok(report),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Clones a piece's files from the store when the library is set to keep files by hash.
+/// Storing only saves space, so a file that can't be stored is left as it is.
+pub(crate) fn store_if_enabled(app_handle: &AppHandle, piece_id: i32) {
+    let settings = AppSettings::read();
+    if !settings.store_files_by_hash {
+        return;
+    }
+
+    let store_dir = files::store_dir(&settings.working_directory);
+    let result = app_handle.db(|db| block_on(files::deduplicate(db, &store_dir, Some(piece_id))));
+    if let Err(e) = result {
+        println!("store_error: {}", e);
+    }
+}
+
+/// Like `store_if_enabled`, for a single score or part that was just added.
+pub(crate) fn store_file_if_enabled(app_handle: &AppHandle, kind: &str, id: i32) {
+    let settings = AppSettings::read();
+    if !settings.store_files_by_hash {
+        return;
+    }
+
+    let store_dir = files::store_dir(&settings.working_directory);
+    let result = app_handle.db(|db| block_on(files::store(db, &store_dir, kind, id)));
+    if let Err(e) = result {
+        println!("store_error: {}", e);
+    }
+}
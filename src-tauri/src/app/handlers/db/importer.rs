@@ -0,0 +1,46 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::importer;
+use crate::settings::AppSettings;
+use std::path::Path;
+
+use super::files::store_if_enabled;
+
+#[command]
+pub fn importer_import_folder(
+    app_handle: AppHandle,
+    source: String,
+    pattern: Option<String>,
+    dry_run: bool,
+) -> Result<serde_json::Value, String> {
+    let settings = AppSettings::read();
+    let pattern = pattern.unwrap_or_else(|| String::from(importer::DEFAULT_PATTERN));
+    let result = app_handle.db(|db| {
+        block_on(importer::import_folder(
+            db,
+            Path::new(&source),
+            &pattern,
+            Path::new(&settings.working_directory),
+            &settings.templates(),
+            dry_run,
+        ))
+    });
+    match result {
+        Ok(report) => {
+            let piece_ids = report["pieces"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|piece| piece["piece_id"].as_i64());
+            for piece_id in piece_ids {
+                store_if_enabled(&app_handle, piece_id as i32);
+            }
+            Ok(report)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,110 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::inbox;
+use crate::settings::AppSettings;
+use std::path::Path;
+
+use super::files::store_if_enabled;
+
+#[command]
+pub fn inbox_get_drafts(
+    app_handle: AppHandle,
+    include_dismissed: Option<bool>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result =
+        app_handle.db(|db| block_on(inbox::get_drafts(db, include_dismissed.unwrap_or(false))));
+    match result {
+        Ok(drafts) => Ok(drafts),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn inbox_scan(app_handle: AppHandle) -> Result<serde_json::Value, String> {
+    let inbox_directory = AppSettings::read().inbox_directory;
+    if inbox_directory.is_empty() {
+        return Err(String::from("No inbox folder is set"));
+    }
+
+    let result = app_handle.db(|db| block_on(inbox::scan(db, Path::new(&inbox_directory))));
+    match result {
+        Ok(scan) => Ok(scan),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn inbox_update_draft(app_handle: AppHandle, id: i32, title: String) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(inbox::update(db, id, title)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn inbox_update_file(
+    app_handle: AppHandle,
+    id: i32,
+    name: String,
+    kind: String,
+    instrument_id: Option<i32>,
+) -> Result<(), String> {
+    let result =
+        app_handle.db(|db| block_on(inbox::update_file(db, id, name, kind, instrument_id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn inbox_move_file(
+    app_handle: AppHandle,
+    id: i32,
+    draft_id: Option<i32>,
+) -> Result<i32, String> {
+    let result = app_handle.db(|db| block_on(inbox::move_file(db, id, draft_id)));
+    match result {
+        Ok(draft_id) => Ok(draft_id),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn inbox_dismiss_draft(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(inbox::dismiss(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn inbox_file_draft(
+    app_handle: AppHandle,
+    id: i32,
+    composer_id: Option<i32>,
+) -> Result<i32, String> {
+    let settings = AppSettings::read();
+    let result = app_handle.db(|db| {
+        block_on(inbox::file(
+            db,
+            id,
+            Path::new(&settings.working_directory),
+            &settings.templates(),
+            composer_id,
+        ))
+    });
+    match result {
+        Ok(piece_id) => {
+            store_if_enabled(&app_handle, piece_id);
+            Ok(piece_id)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -4,7 +4,7 @@ use futures::executor::block_on;
 
 use crate::state::ServiceAccess;
 
-use crate::services::instruments;
+use crate::services::instruments::{self, InstrumentMetadata};
 
 #[command]
 pub fn instruments_get_all(app_handle: AppHandle) -> Result<Vec<serde_json::Value>, String> {
@@ -52,6 +52,31 @@ pub fn instruments_update(
     }
 }
 
+#[command]
+pub fn instruments_set_metadata(
+    app_handle: AppHandle,
+    id: i32,
+    metadata: InstrumentMetadata,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(instruments::set_metadata(db, id, metadata)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn instruments_get_substitutes(
+    app_handle: AppHandle,
+    id: i32,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(instruments::get_substitutes(db, id)));
+    match result {
+        Ok(substitutes) => Ok(substitutes),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 #[command]
 pub fn instruments_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
     let result = app_handle.db(|db| block_on(instruments::delete(db, id)));
@@ -60,3 +85,16 @@ pub fn instruments_delete(app_handle: AppHandle, id: i32) -> Result<(), String>
         Err(e) => Err(e.to_string()),
     }
 }
+
+#[command]
+pub fn instruments_merge(
+    app_handle: AppHandle,
+    survivor_id: i32,
+    duplicate_ids: Vec<i32>,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(instruments::merge(db, survivor_id, duplicate_ids)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,59 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::naming::Templates;
+use crate::services::library;
+use crate::settings::AppSettings;
+use std::path::Path;
+
+/// Renames the library to match the templates, either the ones given or those in the
+/// settings. Given templates are saved once the library was renamed to match them.
+#[command]
+pub fn library_reorganize(
+    app_handle: AppHandle,
+    dry_run: bool,
+    folder_template: Option<String>,
+    file_template: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let settings = AppSettings::read();
+    let templates = Templates {
+        folder: folder_template.unwrap_or_else(|| settings.folder_template.clone()),
+        file: file_template.unwrap_or_else(|| settings.file_template.clone()),
+    };
+
+    let result = app_handle.db(|db| {
+        block_on(library::reorganize(
+            db,
+            Path::new(&settings.working_directory),
+            &templates,
+            dry_run,
+        ))
+    });
+    match result {
+        Ok(report) => {
+            if !dry_run && templates != settings.templates() {
+                settings
+                    .amend(serde_json::json!({
+                        "folder_template": templates.folder,
+                        "file_template": templates.file
+                    }))
+                    .write();
+            }
+            Ok(report)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn library_rename_files(app_handle: AppHandle, piece_id: i32) -> Result<(), String> {
+    let templates = AppSettings::read().templates();
+    let result = app_handle.db(|db| block_on(library::rename_files(db, &templates, piece_id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,101 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::loans;
+
+#[command]
+pub fn loans_get_all(
+    app_handle: AppHandle,
+    include_returned: Option<bool>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let include_returned = include_returned.unwrap_or(false);
+    let result = app_handle.db(|db| block_on(loans::get_all(db, include_returned)));
+    match result {
+        Ok(loans) => Ok(loans),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn loans_get_by_part(
+    app_handle: AppHandle,
+    part_id: i32,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(loans::get_by_part(db, part_id)));
+    match result {
+        Ok(loans) => Ok(loans),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn loans_get_by_player(
+    app_handle: AppHandle,
+    player_id: i32,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(loans::get_by_player(db, player_id)));
+    match result {
+        Ok(loans) => Ok(loans),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn loans_check_out(
+    app_handle: AppHandle,
+    part_id: i32,
+    copies: i32,
+    player_id: Option<i32>,
+    ensemble_id: Option<i32>,
+    due_date: Option<String>,
+    notes: Option<String>,
+) -> Result<i32, String> {
+    let result = app_handle.db(|db| {
+        block_on(loans::check_out(
+            db,
+            part_id,
+            copies,
+            player_id,
+            ensemble_id,
+            due_date,
+            notes,
+        ))
+    });
+    match result {
+        Ok(id) => Ok(id),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn loans_check_in(app_handle: AppHandle, id: i32, copies: Option<i32>) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(loans::check_in(db, id, copies)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn loans_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(loans::delete(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn loans_get_missing(
+    app_handle: AppHandle,
+    piece_id: i32,
+) -> Result<serde_json::Value, String> {
+    let result = app_handle.db(|db| block_on(loans::get_missing(db, piece_id)));
+    match result {
+        Ok(report) => Ok(report),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -1,9 +1,28 @@
+pub mod acquisitions;
+pub mod assignments;
+pub mod binders;
+pub mod copyright;
+pub mod coverage;
+pub mod duplicates;
 pub mod ensemble_parts;
 pub mod ensembles;
+pub mod events;
+pub mod files;
+pub mod importer;
+pub mod inbox;
 pub mod instruments;
+pub mod library;
+pub mod loans;
 pub mod musicians;
+pub mod part_files;
 pub mod parts;
+pub mod performances;
 pub mod pieces;
+pub mod players;
+pub mod rehearsal_notes;
+pub mod roles;
 pub mod scores;
 pub mod setlists;
+pub mod tag_groups;
 pub mod tags;
+pub mod thumbnails;
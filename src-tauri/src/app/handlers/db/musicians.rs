@@ -4,7 +4,9 @@ use futures::executor::block_on;
 
 use crate::state::ServiceAccess;
 
-use crate::services::musicians;
+use crate::services::musicians::{self, MusicianDetails};
+use crate::settings::AppSettings;
+use std::path::Path;
 
 #[command]
 pub fn musicians_get_all(app_handle: AppHandle) -> Result<Vec<serde_json::Value>, String> {
@@ -51,6 +53,44 @@ pub fn musicians_update(
     }
 }
 
+#[command]
+pub fn musicians_search(
+    app_handle: AppHandle,
+    query: String,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(musicians::search(db, query)));
+    match result {
+        Ok(musicians) => Ok(musicians),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn musicians_set_details(
+    app_handle: AppHandle,
+    id: i32,
+    details: MusicianDetails,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(musicians::set_details(db, id, details)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn musicians_set_aliases(
+    app_handle: AppHandle,
+    id: i32,
+    aliases: Vec<String>,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(musicians::set_aliases(db, id, aliases)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 #[command]
 pub fn musicians_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
     let result = app_handle.db(|db| block_on(musicians::delete(db, id)));
@@ -59,3 +99,25 @@ pub fn musicians_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
         Err(e) => Err(e.to_string()),
     }
 }
+
+#[command]
+pub fn musicians_merge(
+    app_handle: AppHandle,
+    survivor_id: i32,
+    duplicate_ids: Vec<i32>,
+) -> Result<serde_json::Value, String> {
+    let settings = AppSettings::read();
+    let result = app_handle.db(|db| {
+        block_on(musicians::merge(
+            db,
+            Path::new(&settings.working_directory),
+            &settings.templates(),
+            survivor_id,
+            duplicate_ids,
+        ))
+    });
+    match result {
+        Ok(report) => Ok(report),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,77 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::part_files;
+use std::path::Path;
+
+#[command]
+pub fn part_files_get_by_part(
+    app_handle: AppHandle,
+    part_id: i32,
+    include_history: Option<bool>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| {
+        block_on(part_files::get_by_part(
+            db,
+            part_id,
+            include_history.unwrap_or(false),
+        ))
+    });
+    match result {
+        Ok(files) => Ok(files),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn part_files_add(
+    app_handle: AppHandle,
+    part_id: i32,
+    source: String,
+    label: String,
+    make_primary: Option<bool>,
+) -> Result<i32, String> {
+    let result = app_handle.db(|db| {
+        block_on(part_files::add(
+            db,
+            part_id,
+            Path::new(&source),
+            label,
+            make_primary.unwrap_or(false),
+        ))
+    });
+    match result {
+        Ok(id) => Ok(id),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn part_files_replace(app_handle: AppHandle, id: i32, source: String) -> Result<i32, String> {
+    let result = app_handle.db(|db| block_on(part_files::replace(db, id, Path::new(&source))));
+    match result {
+        Ok(id) => Ok(id),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn part_files_set_primary(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(part_files::set_primary(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn part_files_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(part_files::delete(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -4,7 +4,9 @@ use futures::executor::block_on;
 
 use crate::state::ServiceAccess;
 
-use crate::services::parts;
+use super::files::store_file_if_enabled;
+use crate::services::parts::{self, PartPages};
+use crate::settings::AppSettings;
 
 #[command]
 pub fn parts_get_all(app_handle: AppHandle) -> Result<Vec<serde_json::Value>, String> {
@@ -33,7 +35,10 @@ pub fn parts_add(
 ) -> Result<i32, String> {
     let result = app_handle.db(|db| block_on(parts::add(db, name, path, piece_id)));
     match result {
-        Ok(id) => Ok(id),
+        Ok(id) => {
+            store_file_if_enabled(&app_handle, "part", id);
+            Ok(id)
+        }
         Err(e) => Err(e.to_string()),
     }
 }
@@ -73,3 +78,37 @@ pub fn parts_set_instruments(
         Err(e) => Err(e.to_string()),
     }
 }
+
+#[command]
+pub fn parts_set_copies(app_handle: AppHandle, part_id: i32, copies: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(parts::set_copies(db, part_id, copies)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn parts_refresh_pdf_info(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(parts::refresh_pdf_info(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn parts_split_pdf(
+    app_handle: AppHandle,
+    piece_id: i32,
+    source: String,
+    parts: Vec<PartPages>,
+) -> Result<Vec<i32>, String> {
+    let templates = AppSettings::read().templates();
+    let result =
+        app_handle.db(|db| block_on(parts::split_pdf(db, piece_id, source, parts, &templates)));
+    match result {
+        Ok(part_ids) => Ok(part_ids),
+        Err(e) => Err(e.to_string()),
+    }
+}
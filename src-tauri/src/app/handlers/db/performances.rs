@@ -0,0 +1,81 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::performances::{self, PerformanceDetails};
+
+#[command]
+pub fn performances_get_all(app_handle: AppHandle) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(performances::get_all(db)));
+    match result {
+        Ok(performances) => Ok(performances),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn performances_get_by_id(app_handle: AppHandle, id: i32) -> Result<serde_json::Value, String> {
+    let result = app_handle.db(|db| block_on(performances::get_by_id(db, id)));
+    match result {
+        Ok(performance) => Ok(performance),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn performances_get_by_piece(
+    app_handle: AppHandle,
+    piece_id: i32,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(performances::get_by_piece(db, piece_id)));
+    match result {
+        Ok(performances) => Ok(performances),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn performances_add(app_handle: AppHandle, details: PerformanceDetails) -> Result<i32, String> {
+    let result = app_handle.db(|db| block_on(performances::add(db, details)));
+    match result {
+        Ok(id) => Ok(id),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn performances_update(
+    app_handle: AppHandle,
+    id: i32,
+    details: PerformanceDetails,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(performances::update(db, id, details)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn performances_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(performances::delete(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn performances_set_pieces(
+    app_handle: AppHandle,
+    id: i32,
+    piece_ids: Vec<i32>,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(performances::set_pieces(db, id, piece_ids)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -1,6 +1,10 @@
-use crate::services::pieces;
+use crate::naming::FileNames;
+use crate::services::duplicates::{self, Candidate};
+use crate::services::pieces::{self, PieceQuery};
+use crate::settings::AppSettings;
 use crate::state::ServiceAccess;
 use futures::executor::block_on;
+use std::path::Path;
 use tauri::{command, AppHandle};
 
 #[command]
@@ -34,6 +38,80 @@ pub fn pieces_get_by_id(app_handle: AppHandle, id: i32) -> Result<serde_json::Va
 }
 
 #[command]
+pub fn pieces_get_parts_in_score_order(
+    app_handle: AppHandle,
+    piece_id: i32,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(pieces::get_parts_in_score_order(db, piece_id)));
+    match result {
+        Ok(parts) => Ok(parts),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn pieces_get_by_tag(
+    app_handle: AppHandle,
+    tag_id: i32,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(pieces::get_by_tag(db, tag_id)));
+    match result {
+        Ok(pieces) => Ok(pieces),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn pieces_get_by_tag_group(
+    app_handle: AppHandle,
+    group_id: i32,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(pieces::get_by_tag_group(db, group_id)));
+    match result {
+        Ok(pieces) => Ok(pieces),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn pieces_get_not_performed_since(
+    app_handle: AppHandle,
+    years: Option<u32>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let years = years.unwrap_or(3);
+    let result = app_handle.db(|db| block_on(pieces::get_not_performed_since(db, years)));
+    match result {
+        Ok(pieces) => Ok(pieces),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn pieces_get_by_copyright_status(
+    app_handle: AppHandle,
+    status: String,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(pieces::get_by_copyright_status(db, status)));
+    match result {
+        Ok(pieces) => Ok(pieces),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn pieces_query(
+    app_handle: AppHandle,
+    query: PieceQuery,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(pieces::query(db, query)));
+    match result {
+        Ok(pieces) => Ok(pieces),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+#[allow(clippy::too_many_arguments)]
 pub fn pieces_add(
     app_handle: AppHandle,
     title: String,
@@ -41,16 +119,23 @@ pub fn pieces_add(
     path: String,
     difficulty: Option<i32>,
     notes: String,
+    composer_ids: Option<Vec<i32>>,
+    force: Option<bool>,
 ) -> Result<i32, String> {
     let result = app_handle.db(|db| {
-        block_on(pieces::add(
-            db,
-            title,
-            year_published,
-            path,
-            difficulty,
-            notes,
-        ))
+        block_on(async {
+            // refuse likely duplicates unless the user already confirmed
+            if !force.unwrap_or(false) {
+                let candidate = Candidate {
+                    title: title.clone(),
+                    composer_ids: composer_ids.unwrap_or_default(),
+                    ..Default::default()
+                };
+                duplicates::check(db, &candidate).await?;
+            }
+
+            pieces::add(db, title, year_published, path, difficulty, notes).await
+        })
     });
     match result {
         Ok(id) => Ok(id),
@@ -68,6 +153,7 @@ pub fn pieces_update(
     difficulty: Option<i32>,
     notes: String,
 ) -> Result<(), String> {
+    let settings = AppSettings::read();
     let result = app_handle.db(|db| {
         block_on(pieces::update(
             db,
@@ -77,6 +163,32 @@ pub fn pieces_update(
             path,
             difficulty,
             notes,
+            Path::new(&settings.working_directory),
+            &settings.templates(),
+        ))
+    });
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn pieces_set_publication(
+    app_handle: AppHandle,
+    piece_id: i32,
+    publisher: Option<String>,
+    catalogue_number: Option<String>,
+) -> Result<(), String> {
+    let settings = AppSettings::read();
+    let result = app_handle.db(|db| {
+        block_on(pieces::set_publication(
+            db,
+            piece_id,
+            publisher,
+            catalogue_number,
+            Path::new(&settings.working_directory),
+            &settings.templates(),
         ))
     });
     match result {
@@ -85,6 +197,68 @@ pub fn pieces_update(
     }
 }
 
+#[command]
+pub fn pieces_set_location(
+    app_handle: AppHandle,
+    piece_id: i32,
+    location_cabinet: Option<String>,
+    location_drawer: Option<String>,
+    location_box: Option<String>,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| {
+        block_on(pieces::set_location(
+            db,
+            piece_id,
+            location_cabinet,
+            location_drawer,
+            location_box,
+        ))
+    });
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn pieces_suggest_from_pdf(
+    app_handle: AppHandle,
+    path: String,
+) -> Result<serde_json::Value, String> {
+    let result = app_handle.db(|db| block_on(pieces::suggest_from_pdf(db, path)));
+    match result {
+        Ok(suggestion) => Ok(suggestion),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn pieces_set_ratings(
+    app_handle: AppHandle,
+    id: i32,
+    rating: Option<i32>,
+    audience_rating: Option<i32>,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(pieces::set_ratings(db, id, rating, audience_rating)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn pieces_set_favorite(
+    app_handle: AppHandle,
+    id: i32,
+    is_favorite: bool,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(pieces::set_favorite(db, id, is_favorite)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 #[command]
 pub fn pieces_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
     let result = app_handle.db(|db| block_on(pieces::delete(db, id)));
@@ -101,8 +275,17 @@ pub fn pieces_set_musicians(
     musician_ids: Vec<i32>,
     role: String,
 ) -> Result<(), String> {
-    let result =
-        app_handle.db(|db| block_on(pieces::set_musicians(db, piece_id, musician_ids, role)));
+    let settings = AppSettings::read();
+    let result = app_handle.db(|db| {
+        block_on(pieces::set_musicians(
+            db,
+            piece_id,
+            musician_ids,
+            role,
+            Path::new(&settings.working_directory),
+            &settings.templates(),
+        ))
+    });
     match result {
         Ok(_) => Ok(()),
         Err(e) => Err(e.to_string()),
@@ -123,19 +306,47 @@ pub fn pieces_set_tags(
 }
 
 #[command]
-pub fn pieces_drop_scores(app_handle: AppHandle, piece_id: i32) -> Result<(), String> {
-    let result = app_handle.db(|db| block_on(pieces::drop_scores(db, piece_id)));
+pub fn pieces_move_to_library_folder(
+    app_handle: AppHandle,
+    piece_id: i32,
+) -> Result<String, String> {
+    let settings = AppSettings::read();
+    let result = app_handle.db(|db| {
+        block_on(pieces::move_to_library_folder(
+            db,
+            Path::new(&settings.working_directory),
+            &settings.templates(),
+            piece_id,
+        ))
+    });
     match result {
-        Ok(_) => Ok(()),
+        Ok(folder) => Ok(folder.to_string_lossy().to_string()),
         Err(e) => Err(e.to_string()),
     }
 }
 
+/// Name the file template gives a score or part of the piece, e.g. `1.3_Clarinet 2.pdf`.
 #[command]
-pub fn pieces_drop_parts(app_handle: AppHandle, piece_id: i32) -> Result<(), String> {
-    let result = app_handle.db(|db| block_on(pieces::drop_parts(db, piece_id)));
+pub fn pieces_get_library_file_name(
+    app_handle: AppHandle,
+    piece_id: i32,
+    is_score: bool,
+    order: usize,
+    name: String,
+    instrument: Option<String>,
+) -> Result<String, String> {
+    let templates = AppSettings::read().templates();
+    let result = app_handle.db(|db| block_on(pieces::library_names(db, piece_id)));
     match result {
-        Ok(_) => Ok(()),
+        Ok(names) => Ok(templates.file(
+            &names,
+            &FileNames {
+                is_score,
+                order,
+                name,
+                instrument,
+            },
+        )),
         Err(e) => Err(e.to_string()),
     }
 }
@@ -0,0 +1,65 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::players;
+
+#[command]
+pub fn players_get_all(app_handle: AppHandle) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(players::get_all(db)));
+    match result {
+        Ok(players) => Ok(players),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn players_get_by_id(app_handle: AppHandle, id: i32) -> Result<serde_json::Value, String> {
+    let result = app_handle.db(|db| block_on(players::get_by_id(db, id)));
+    match result {
+        Ok(player) => Ok(player),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn players_add(
+    app_handle: AppHandle,
+    name: String,
+    email: Option<String>,
+    phone: Option<String>,
+    notes: Option<String>,
+) -> Result<i32, String> {
+    let result = app_handle.db(|db| block_on(players::add(db, name, email, phone, notes)));
+    match result {
+        Ok(id) => Ok(id),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn players_update(
+    app_handle: AppHandle,
+    id: i32,
+    name: String,
+    email: Option<String>,
+    phone: Option<String>,
+    notes: Option<String>,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(players::update(db, id, name, email, phone, notes)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn players_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(players::delete(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
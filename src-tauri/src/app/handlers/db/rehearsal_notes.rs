@@ -0,0 +1,50 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::rehearsal_notes;
+
+#[command]
+pub fn rehearsal_notes_get_by_piece(
+    app_handle: AppHandle,
+    piece_id: i32,
+) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(rehearsal_notes::get_by_piece(db, piece_id)));
+    match result {
+        Ok(notes) => Ok(notes),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn rehearsal_notes_add(
+    app_handle: AppHandle,
+    piece_id: i32,
+    note: String,
+) -> Result<i32, String> {
+    let result = app_handle.db(|db| block_on(rehearsal_notes::add(db, piece_id, note)));
+    match result {
+        Ok(id) => Ok(id),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn rehearsal_notes_update(app_handle: AppHandle, id: i32, note: String) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(rehearsal_notes::update(db, id, note)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn rehearsal_notes_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(rehearsal_notes::delete(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,52 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::roles;
+
+#[command]
+pub fn roles_get_all(app_handle: AppHandle) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(roles::get_all(db)));
+    match result {
+        Ok(roles) => Ok(roles),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn roles_get_by_id(app_handle: AppHandle, id: i32) -> Result<serde_json::Value, String> {
+    let result = app_handle.db(|db| block_on(roles::get_by_id(db, id)));
+    match result {
+        Ok(role) => Ok(role),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn roles_add(app_handle: AppHandle, name: String) -> Result<i32, String> {
+    let result = app_handle.db(|db| block_on(roles::add(db, name)));
+    match result {
+        Ok(id) => Ok(id),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn roles_update(app_handle: AppHandle, id: i32, name: String) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(roles::update(db, id, name)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn roles_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(roles::delete(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
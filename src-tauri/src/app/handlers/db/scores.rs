@@ -4,6 +4,7 @@ use futures::executor::block_on;
 
 use crate::state::ServiceAccess;
 
+use super::files::store_file_if_enabled;
 use crate::services::scores;
 
 #[command]
@@ -33,7 +34,10 @@ pub fn scores_add(
 ) -> Result<i32, String> {
     let result = app_handle.db(|db| block_on(scores::add(db, name, path, piece_id)));
     match result {
-        Ok(id) => Ok(id),
+        Ok(id) => {
+            store_file_if_enabled(&app_handle, "score", id);
+            Ok(id)
+        }
         Err(e) => Err(e.to_string()),
     }
 }
@@ -60,3 +64,12 @@ pub fn scores_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
         Err(e) => Err(e.to_string()),
     }
 }
+
+#[command]
+pub fn scores_refresh_pdf_info(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(scores::refresh_pdf_info(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
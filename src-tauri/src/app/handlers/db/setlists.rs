@@ -76,3 +76,16 @@ pub fn setlists_remove_piece(
         Err(e) => Err(e.to_string()),
     }
 }
+
+#[command]
+pub fn setlists_set_order(
+    app_handle: AppHandle,
+    setlist_id: i32,
+    piece_ids: Vec<i32>,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(setlists::set_order(db, setlist_id, piece_ids)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,61 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::services::tag_groups;
+
+#[command]
+pub fn tag_groups_get_all(app_handle: AppHandle) -> Result<Vec<serde_json::Value>, String> {
+    let result = app_handle.db(|db| block_on(tag_groups::get_all(db)));
+    match result {
+        Ok(groups) => Ok(groups),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn tag_groups_get_by_id(app_handle: AppHandle, id: i32) -> Result<serde_json::Value, String> {
+    let result = app_handle.db(|db| block_on(tag_groups::get_by_id(db, id)));
+    match result {
+        Ok(group) => Ok(group),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn tag_groups_add(
+    app_handle: AppHandle,
+    name: String,
+    color: Option<String>,
+) -> Result<i32, String> {
+    let result = app_handle.db(|db| block_on(tag_groups::add(db, name, color)));
+    match result {
+        Ok(id) => Ok(id),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn tag_groups_update(
+    app_handle: AppHandle,
+    id: i32,
+    name: String,
+    color: Option<String>,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(tag_groups::update(db, id, name, color)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn tag_groups_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(tag_groups::delete(db, id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
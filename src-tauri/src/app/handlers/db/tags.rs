@@ -25,8 +25,14 @@ pub fn tags_get_by_id(app_handle: AppHandle, id: i32) -> Result<serde_json::Valu
 }
 
 #[command]
-pub fn tags_add(app_handle: AppHandle, name: String, color: String) -> Result<i32, String> {
-    let result = app_handle.db(|db| block_on(tags::add(db, name, color)));
+pub fn tags_add(
+    app_handle: AppHandle,
+    name: String,
+    color: Option<String>,
+    parent_id: Option<i32>,
+    group_id: Option<i32>,
+) -> Result<i32, String> {
+    let result = app_handle.db(|db| block_on(tags::add(db, name, color, parent_id, group_id)));
     match result {
         Ok(id) => Ok(id),
         Err(e) => Err(e.to_string()),
@@ -47,6 +53,28 @@ pub fn tags_update(
     }
 }
 
+#[command]
+pub fn tags_set_parent(
+    app_handle: AppHandle,
+    id: i32,
+    parent_id: Option<i32>,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(tags::set_parent(db, id, parent_id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn tags_set_group(app_handle: AppHandle, id: i32, group_id: Option<i32>) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(tags::set_group(db, id, group_id)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 #[command]
 pub fn tags_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
     let result = app_handle.db(|db| block_on(tags::delete(db, id)));
@@ -56,3 +84,16 @@ pub fn tags_delete(app_handle: AppHandle, id: i32) -> Result<(), String> {
         Err(e) => Err(e.to_string()),
     }
 }
+
+#[command]
+pub fn tags_merge(
+    app_handle: AppHandle,
+    survivor_id: i32,
+    duplicate_ids: Vec<i32>,
+) -> Result<(), String> {
+    let result = app_handle.db(|db| block_on(tags::merge(db, survivor_id, duplicate_ids)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
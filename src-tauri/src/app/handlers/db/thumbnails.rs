@@ -0,0 +1,65 @@
+use tauri::{command, AppHandle};
+
+use futures::executor::block_on;
+
+use crate::state::ServiceAccess;
+
+use crate::render;
+use crate::services::thumbnails;
+use crate::settings::AppSettings;
+
+#[command]
+pub fn thumbnails_get_score(
+    app_handle: AppHandle,
+    score_id: i32,
+    include_data: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let cache_dir = thumbnails::cache_dir(&AppSettings::read().working_directory);
+    let result = app_handle.db(|db| {
+        block_on(thumbnails::get_by_score(
+            db,
+            &cache_dir,
+            score_id,
+            include_data.unwrap_or(false),
+        ))
+    });
+    match result {
+        Ok(thumbnail) => Ok(thumbnail),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn thumbnails_get_part(
+    app_handle: AppHandle,
+    part_id: i32,
+    include_data: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let cache_dir = thumbnails::cache_dir(&AppSettings::read().working_directory);
+    let result = app_handle.db(|db| {
+        block_on(thumbnails::get_by_part(
+            db,
+            &cache_dir,
+            part_id,
+            include_data.unwrap_or(false),
+        ))
+    });
+    match result {
+        Ok(thumbnail) => Ok(thumbnail),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn thumbnails_clear() -> Result<(), String> {
+    let cache_dir = thumbnails::cache_dir(&AppSettings::read().working_directory);
+    match thumbnails::clear(&cache_dir) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[command]
+pub fn thumbnails_get_renderer() -> Option<String> {
+    render::pdf_renderer().map(|renderer| renderer.to_string())
+}
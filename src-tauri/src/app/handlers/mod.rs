@@ -6,47 +6,142 @@ mod settings;
 
 pub fn init() -> impl Fn(Invoke) {
     generate_handler![
+        db::acquisitions::acquisitions_get_by_piece,
+        db::acquisitions::acquisitions_get_by_id,
+        db::acquisitions::acquisitions_add,
+        db::acquisitions::acquisitions_update,
+        db::acquisitions::acquisitions_delete,
+        db::acquisitions::acquisitions_mark_returned,
+        db::acquisitions::acquisitions_get_rentals_due,
+        db::assignments::assignments_get_by_setlist,
+        db::assignments::assignments_set,
+        db::assignments::assignments_assign_from_roster,
+        db::assignments::assignments_get_player_sheet,
+        db::assignments::assignments_get_piece_sheet,
+        db::binders::binders_build,
+        db::copyright::copyright_evaluate,
+        db::copyright::copyright_evaluate_all,
+        db::coverage::coverage_check,
+        db::coverage::coverage_get_playable,
+        db::duplicates::duplicates_get_clusters,
+        db::duplicates::duplicates_find_candidates,
         db::ensemble_parts::ensemble_parts_get_by_id,
         db::ensemble_parts::ensemble_parts_add,
         db::ensemble_parts::ensemble_parts_update,
         db::ensemble_parts::ensemble_parts_delete,
         db::ensemble_parts::ensemble_parts_set_instruments,
+        db::ensemble_parts::ensemble_parts_set_players,
         db::ensembles::ensembles_get_all,
         db::ensembles::ensembles_get_by_id,
         db::ensembles::ensembles_add,
         db::ensembles::ensembles_update,
         db::ensembles::ensembles_delete,
+        db::events::events_get_all,
+        db::events::events_get_by_id,
+        db::events::events_get_by_setlist,
+        db::events::events_add,
+        db::events::events_update,
+        db::events::events_delete,
+        db::events::events_export_ics,
+        db::events::events_import_ics,
+        db::files::files_verify,
+        db::files::files_deduplicate,
+        db::importer::importer_import_folder,
+        db::inbox::inbox_get_drafts,
+        db::inbox::inbox_scan,
+        db::inbox::inbox_update_draft,
+        db::inbox::inbox_update_file,
+        db::inbox::inbox_move_file,
+        db::inbox::inbox_dismiss_draft,
+        db::inbox::inbox_file_draft,
         db::instruments::instruments_get_all,
         db::instruments::instruments_get_by_id,
         db::instruments::instruments_add,
         db::instruments::instruments_update,
+        db::instruments::instruments_set_metadata,
+        db::instruments::instruments_get_substitutes,
         db::instruments::instruments_delete,
+        db::instruments::instruments_merge,
+        db::library::library_reorganize,
+        db::library::library_rename_files,
+        db::loans::loans_get_all,
+        db::loans::loans_get_by_part,
+        db::loans::loans_get_by_player,
+        db::loans::loans_check_out,
+        db::loans::loans_check_in,
+        db::loans::loans_delete,
+        db::loans::loans_get_missing,
         db::musicians::musicians_get_all,
         db::musicians::musicians_get_by_id,
         db::musicians::musicians_add,
         db::musicians::musicians_update,
+        db::musicians::musicians_search,
+        db::musicians::musicians_set_details,
+        db::musicians::musicians_set_aliases,
         db::musicians::musicians_delete,
+        db::musicians::musicians_merge,
+        db::part_files::part_files_get_by_part,
+        db::part_files::part_files_add,
+        db::part_files::part_files_replace,
+        db::part_files::part_files_set_primary,
+        db::part_files::part_files_delete,
         db::parts::parts_get_all,
         db::parts::parts_get_by_id,
         db::parts::parts_add,
         db::parts::parts_update,
         db::parts::parts_delete,
         db::parts::parts_set_instruments,
+        db::parts::parts_set_copies,
+        db::parts::parts_refresh_pdf_info,
+        db::parts::parts_split_pdf,
+        db::performances::performances_get_all,
+        db::performances::performances_get_by_id,
+        db::performances::performances_get_by_piece,
+        db::performances::performances_add,
+        db::performances::performances_update,
+        db::performances::performances_delete,
+        db::performances::performances_set_pieces,
         db::pieces::pieces_get_all,
         db::pieces::pieces_get_by_setlist,
         db::pieces::pieces_get_by_id,
+        db::pieces::pieces_get_parts_in_score_order,
+        db::pieces::pieces_get_by_tag,
+        db::pieces::pieces_get_by_tag_group,
+        db::pieces::pieces_get_not_performed_since,
+        db::pieces::pieces_get_by_copyright_status,
+        db::pieces::pieces_query,
         db::pieces::pieces_add,
         db::pieces::pieces_update,
+        db::pieces::pieces_set_publication,
+        db::pieces::pieces_set_location,
+        db::pieces::pieces_suggest_from_pdf,
+        db::pieces::pieces_set_ratings,
+        db::pieces::pieces_set_favorite,
         db::pieces::pieces_delete,
         db::pieces::pieces_set_musicians,
         db::pieces::pieces_set_tags,
-        db::pieces::pieces_drop_scores,
-        db::pieces::pieces_drop_parts,
+        db::pieces::pieces_move_to_library_folder,
+        db::pieces::pieces_get_library_file_name,
+        db::players::players_get_all,
+        db::players::players_get_by_id,
+        db::players::players_add,
+        db::players::players_update,
+        db::players::players_delete,
+        db::rehearsal_notes::rehearsal_notes_get_by_piece,
+        db::rehearsal_notes::rehearsal_notes_add,
+        db::rehearsal_notes::rehearsal_notes_update,
+        db::rehearsal_notes::rehearsal_notes_delete,
+        db::roles::roles_get_all,
+        db::roles::roles_get_by_id,
+        db::roles::roles_add,
+        db::roles::roles_update,
+        db::roles::roles_delete,
         db::scores::scores_get_all,
         db::scores::scores_get_by_id,
         db::scores::scores_add,
         db::scores::scores_update,
         db::scores::scores_delete,
+        db::scores::scores_refresh_pdf_info,
         db::setlists::setlists_get_all,
         db::setlists::setlists_get_by_id,
         db::setlists::setlists_add,
@@ -54,16 +149,37 @@ pub fn init() -> impl Fn(Invoke) {
         db::setlists::setlists_delete,
         db::setlists::setlists_add_piece,
         db::setlists::setlists_remove_piece,
+        db::setlists::setlists_set_order,
         db::tags::tags_get_all,
         db::tags::tags_get_by_id,
         db::tags::tags_add,
         db::tags::tags_update,
+        db::tags::tags_set_parent,
+        db::tags::tags_set_group,
         db::tags::tags_delete,
+        db::tags::tags_merge,
+        db::tag_groups::tag_groups_get_all,
+        db::tag_groups::tag_groups_get_by_id,
+        db::tag_groups::tag_groups_add,
+        db::tag_groups::tag_groups_update,
+        db::tag_groups::tag_groups_delete,
+        db::thumbnails::thumbnails_get_score,
+        db::thumbnails::thumbnails_get_part,
+        db::thumbnails::thumbnails_clear,
+        db::thumbnails::thumbnails_get_renderer,
         fs::get_database_exists,
         fs::get_dir_empty,
         fs::open,
         fs::delete_dir,
         settings::get_working_directory,
         settings::set_working_directory,
+        settings::get_copyright_rule,
+        settings::set_copyright_rule,
+        settings::get_store_files_by_hash,
+        settings::set_store_files_by_hash,
+        settings::get_inbox_directory,
+        settings::set_inbox_directory,
+        settings::get_naming_templates,
+        settings::set_naming_templates,
     ]
 }
@@ -1,3 +1,5 @@
+use crate::naming::Templates;
+use crate::services::copyright::COPYRIGHT_RULES;
 use crate::settings::AppSettings;
 
 use tauri::command;
@@ -15,3 +17,82 @@ pub async fn set_working_directory(path: String) {
         }))
         .write();
 }
+
+#[command]
+pub async fn get_copyright_rule() -> String {
+    AppSettings::read().copyright_rule
+}
+
+#[command]
+pub async fn set_copyright_rule(rule: String) -> Result<(), String> {
+    if !COPYRIGHT_RULES.contains(&rule.as_str()) {
+        return Err(format!(
+            "Copyright rule {} is not one of {}",
+            rule,
+            COPYRIGHT_RULES.join(", ")
+        ));
+    }
+
+    AppSettings::read()
+        .amend(serde_json::json!({
+            "copyright_rule": rule
+        }))
+        .write();
+
+    Ok(())
+}
+
+#[command]
+pub async fn get_store_files_by_hash() -> bool {
+    AppSettings::read().store_files_by_hash
+}
+
+#[command]
+pub async fn set_store_files_by_hash(enabled: bool) {
+    AppSettings::read()
+        .amend(serde_json::json!({
+            "store_files_by_hash": enabled
+        }))
+        .write();
+}
+
+#[command]
+pub async fn get_inbox_directory() -> String {
+    AppSettings::read().inbox_directory
+}
+
+#[command]
+pub async fn set_inbox_directory(path: String) {
+    AppSettings::read()
+        .amend(serde_json::json!({
+            "inbox_directory": path
+        }))
+        .write();
+}
+
+#[command]
+pub async fn get_naming_templates() -> Templates {
+    AppSettings::read().templates()
+}
+
+/// Only changes how new pieces are named; `library_reorganize` renames what is already there.
+#[command]
+pub async fn set_naming_templates(
+    folder_template: String,
+    file_template: String,
+) -> Result<(), String> {
+    Templates {
+        folder: folder_template.clone(),
+        file: file_template.clone(),
+    }
+    .validate()?;
+
+    AppSettings::read()
+        .amend(serde_json::json!({
+            "folder_template": folder_template,
+            "file_template": file_template
+        }))
+        .write();
+
+    Ok(())
+}
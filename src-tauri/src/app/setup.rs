@@ -1,7 +1,13 @@
 use crate::db;
-use crate::state::AppState;
+use crate::services::{files, inbox};
+use crate::settings::AppSettings;
+use crate::state::{AppState, ServiceAccess};
 use futures::executor::block_on;
-use tauri::{App, Manager, State};
+use std::{path::Path, thread, time::Duration};
+use tauri::{App, AppHandle, Manager, State};
+
+/// How often the inbox folder is looked at for new downloads.
+const INBOX_INTERVAL: Duration = Duration::from_secs(30);
 
 pub fn init(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
     let handle = app.handle();
@@ -9,5 +15,42 @@ pub fn init(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
     let db = block_on(db::init()).unwrap();
 
     *app_state.db.lock().unwrap() = Some(db);
+
+    record_hashes(handle.clone());
+    watch_inbox(handle);
     Ok(())
 }
+
+/// Records the hashes of files attached before hashes were stored, once, so that
+/// duplicate checks never have to read the library from disk.
+fn record_hashes(handle: AppHandle) {
+    thread::spawn(move || {
+        let result = handle.db(|db| block_on(files::record_missing_hashes(db)));
+        if let Err(e) = result {
+            println!("files_record_hashes_error: {}", e);
+        }
+    });
+}
+
+/// Queues what's dropped into the inbox folder as drafts and lets the window
+/// know with an `inbox-changed` event.
+fn watch_inbox(handle: AppHandle) {
+    thread::spawn(move || loop {
+        let inbox_directory = AppSettings::read().inbox_directory;
+        if !inbox_directory.is_empty() && Path::new(&inbox_directory).is_dir() {
+            let result = handle.db(|db| block_on(inbox::scan(db, Path::new(&inbox_directory))));
+            match result {
+                Ok(scan) => {
+                    let changed =
+                        scan["files"].as_i64() != Some(0) || scan["removed"].as_i64() != Some(0);
+                    if changed {
+                        let _ = handle.emit_all("inbox-changed", scan);
+                    }
+                }
+                Err(e) => println!("inbox_scan_error: {}", e),
+            }
+        }
+
+        thread::sleep(INBOX_INTERVAL);
+    });
+}
@@ -35,11 +35,24 @@ async fn establish_connection() -> Result<DatabaseConnection, DbErr> {
 async fn run_migrations(db: &sea_orm::DatabaseConnection, creating: bool) -> Result<(), DbErr> {
     let schema_manager = SchemaManager::new(db);
 
+    // libraries created before instruments had metadata need the defaults filled in
+    let backfill_instrument_metadata = !creating
+        && Migrator::get_pending_migrations(db)
+            .await?
+            .iter()
+            .any(|migration| migration.name() == "m20261019_160000_add_instrument_metadata");
+
+    // apply any migrations added since the library was created
+    Migrator::up(db, None).await?;
+
     if creating {
-        Migrator::up(db, None).await?;
         seed::seed(db).await?;
     }
 
+    if backfill_instrument_metadata {
+        seed::seed_instrument_metadata(db).await?;
+    }
+
     assert!(schema_manager.has_table("pieces").await?);
     assert!(schema_manager.has_table("parts").await?);
     assert!(schema_manager.has_table("musicians").await?);
@@ -55,6 +68,21 @@ async fn run_migrations(db: &sea_orm::DatabaseConnection, creating: bool) -> Res
             .has_table("ensemble_parts_instruments")
             .await?
     );
+    assert!(schema_manager.has_table("tag_groups").await?);
+    assert!(schema_manager.has_table("musician_aliases").await?);
+    assert!(schema_manager.has_table("roles").await?);
+    assert!(schema_manager.has_table("players").await?);
+    assert!(schema_manager.has_table("ensemble_parts_players").await?);
+    assert!(schema_manager.has_table("part_assignments").await?);
+    assert!(schema_manager.has_table("loans").await?);
+    assert!(schema_manager.has_table("acquisitions").await?);
+    assert!(schema_manager.has_table("performances").await?);
+    assert!(schema_manager.has_table("pieces_performances").await?);
+    assert!(schema_manager.has_table("events").await?);
+    assert!(schema_manager.has_table("rehearsal_notes").await?);
+    assert!(schema_manager.has_table("drafts").await?);
+    assert!(schema_manager.has_table("draft_files").await?);
+    assert!(schema_manager.has_table("part_files").await?);
     Ok(())
 }
 
@@ -1,9 +1,162 @@
 use indexmap::IndexMap;
 use std::collections::HashMap;
 
+use crate::entities;
 use crate::services::{ensemble_parts, ensembles, instruments};
 use indexmap::indexmap;
-use sea_orm::{DatabaseConnection, DbErr};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+
+/// Family, transposition, clefs and sounding range of the default instruments,
+/// listed in standard score order.
+#[rustfmt::skip]
+const INSTRUMENT_METADATA: &[(&str, &str, Option<i32>, Option<&str>, Option<&str>, Option<&str>)] = &[
+    ("Piccolo", "Flute", Some(12), Some("treble"), Some("D5"), Some("C8")),
+    ("Flute", "Flute", Some(0), Some("treble"), Some("C4"), Some("D7")),
+    ("Alto Flute", "Flute", Some(-5), Some("treble"), Some("G3"), Some("G6")),
+    ("Bass Flute", "Flute", Some(-12), Some("treble"), Some("C3"), Some("C6")),
+    ("Contra-alto Flute", "Flute", Some(-17), Some("treble"), Some("G2"), Some("G5")),
+    ("Contrabass Flute", "Flute", Some(-24), Some("treble"), Some("C2"), Some("C5")),
+    ("Oboe", "Oboe", Some(0), Some("treble"), Some("Bb3"), Some("A6")),
+    ("Oboe D'Amore", "Oboe", Some(-3), Some("treble"), Some("G#3"), Some("C#6")),
+    ("Cor Anglais", "Oboe", Some(-7), Some("treble"), Some("E3"), Some("C6")),
+    ("Bass Oboe", "Oboe", Some(-12), Some("treble"), Some("B2"), Some("G5")),
+    ("Heckelphone", "Oboe", Some(-12), Some("treble"), Some("A2"), Some("G5")),
+    ("Piccolo Clarinet in Ab", "Clarinet", Some(8), Some("treble"), Some("C4"), Some("C7")),
+    ("Clarinet in Eb", "Clarinet", Some(3), Some("treble"), Some("G3"), Some("Bb6")),
+    ("Clarinet in Bb", "Clarinet", Some(-2), Some("treble"), Some("D3"), Some("Bb6")),
+    ("Clarinet in A", "Clarinet", Some(-3), Some("treble"), Some("C#3"), Some("A6")),
+    ("Basset Horn", "Clarinet", Some(-7), Some("treble"), Some("F2"), Some("F5")),
+    ("Alto Clarinet", "Clarinet", Some(-9), Some("treble"), Some("Gb2"), Some("Bb5")),
+    ("Bass Clarinet", "Clarinet", Some(-14), Some("treble,bass"), Some("Bb1"), Some("F5")),
+    ("Contra-alto Clarinet", "Clarinet", Some(-21), Some("treble"), Some("Gb1"), Some("Bb4")),
+    ("Contrabass Clarinet", "Clarinet", Some(-26), Some("treble"), Some("Bb0"), Some("F4")),
+    ("Bassoon", "Bassoon", Some(0), Some("bass,tenor"), Some("Bb1"), Some("E5")),
+    ("Contrabassoon", "Bassoon", Some(-12), Some("bass"), Some("Bb0"), Some("G4")),
+    ("Sopranino Saxophone", "Saxophone", Some(3), Some("treble"), Some("Db4"), Some("Ab6")),
+    ("Soprano Saxophone", "Saxophone", Some(-2), Some("treble"), Some("Ab3"), Some("E6")),
+    ("Alto Saxophone", "Saxophone", Some(-9), Some("treble"), Some("Db3"), Some("Ab5")),
+    ("Tenor Saxophone", "Saxophone", Some(-14), Some("treble"), Some("Ab2"), Some("E5")),
+    ("Baritone Saxophone", "Saxophone", Some(-21), Some("treble"), Some("C2"), Some("Ab4")),
+    ("Bass Saxophone", "Saxophone", Some(-26), Some("treble"), Some("Ab1"), Some("E4")),
+    ("Contrabass Saxophone", "Saxophone", Some(-33), Some("treble"), Some("C1"), Some("Ab3")),
+    ("Horn in F", "Horn", Some(-7), Some("treble,bass"), Some("B1"), Some("F5")),
+    ("Wagner Tuba", "Horn", Some(-7), Some("treble,bass"), Some("E2"), Some("F4")),
+    ("Mellophone", "Mellophone", Some(-7), Some("treble"), Some("F#3"), Some("C6")),
+    ("Piccolo Trumpet in Bb", "Trumpet", Some(10), Some("treble"), Some("F#4"), Some("G6")),
+    ("Piccolo Trumpet in A", "Trumpet", Some(9), Some("treble"), Some("F4"), Some("F#6")),
+    ("Trumpet in Eb", "Trumpet", Some(3), Some("treble"), Some("A3"), Some("Bb5")),
+    ("Trumpet in D", "Trumpet", Some(2), Some("treble"), Some("G#3"), Some("A5")),
+    ("Trumpet in C", "Trumpet", Some(0), Some("treble"), Some("F#3"), Some("D6")),
+    ("Trumpet in Bb", "Trumpet", Some(-2), Some("treble"), Some("E3"), Some("C6")),
+    ("Cornet in Eb", "Cornet", Some(3), Some("treble"), Some("A3"), Some("Bb5")),
+    ("Cornet in Bb", "Cornet", Some(-2), Some("treble"), Some("E3"), Some("Bb5")),
+    ("Flugelhorn", "Flugelhorn", Some(-2), Some("treble"), Some("E3"), Some("F5")),
+    ("Bass Trumpet", "Trumpet", Some(-14), Some("treble,bass"), Some("F#2"), Some("Bb4")),
+    ("Soprano Bugle", "Bugle", None, Some("treble"), None, None),
+    ("Alto Bugle", "Bugle", None, Some("treble"), None, None),
+    ("Baritone Bugle", "Bugle", None, Some("treble,bass"), None, None),
+    ("Contrabass Bugle", "Bugle", None, Some("bass"), None, None),
+    ("Alto Horn", "Alto Horn", Some(-9), Some("treble"), Some("A2"), Some("Eb5")),
+    ("Tenor Horn", "Alto Horn", Some(-9), Some("treble"), Some("A2"), Some("Eb5")),
+    ("Soprano Trombone", "Trombone", Some(0), Some("treble"), Some("E4"), Some("Bb5")),
+    ("Alto Trombone", "Trombone", Some(0), Some("alto"), Some("A2"), Some("Eb5")),
+    ("Tenor Trombone", "Trombone", Some(0), Some("bass,tenor"), Some("E2"), Some("F5")),
+    ("Bass Trombone", "Trombone", Some(0), Some("bass"), Some("Bb0"), Some("Bb4")),
+    ("Contrabass Trombone", "Trombone", Some(0), Some("bass"), Some("E1"), Some("G4")),
+    ("Baritone Horn", "Euphonium", Some(0), Some("bass"), Some("E2"), Some("Bb4")),
+    ("Euphonium", "Euphonium", Some(0), Some("bass,tenor"), Some("Bb1"), Some("Bb4")),
+    ("Cimbasso", "Tuba", Some(0), Some("bass"), Some("A0"), Some("F4")),
+    ("Tuba", "Tuba", Some(0), Some("bass"), Some("D1"), Some("F4")),
+    ("Sousaphone", "Tuba", Some(0), Some("bass"), Some("D1"), Some("F4")),
+    ("Helicon", "Tuba", Some(0), Some("bass"), Some("D1"), Some("F4")),
+    ("Timpani", "Timpani", Some(0), Some("bass"), Some("D2"), Some("C4")),
+    ("Glockenspiel", "Glockenspiel", Some(24), Some("treble"), Some("G5"), Some("C8")),
+    ("Crotales", "Crotales", Some(24), Some("treble"), Some("C6"), Some("C8")),
+    ("Xylophone", "Xylophone", Some(12), Some("treble"), Some("F4"), Some("C8")),
+    ("Vibraphone", "Vibraphone", Some(0), Some("treble"), Some("F3"), Some("F6")),
+    ("Marimba", "Marimba", Some(0), Some("treble,bass"), Some("C2"), Some("C7")),
+    ("Tubular Bells", "Tubular Bells", Some(0), Some("treble"), Some("C4"), Some("F5")),
+    ("Anvil", "Anvil", None, Some("percussion"), None, None),
+    ("Bass Drum", "Bass Drum", None, Some("percussion"), None, None),
+    ("Bass Drums", "Bass Drums", None, Some("percussion"), None, None),
+    ("Bongos", "Bongos", None, Some("percussion"), None, None),
+    ("Castanets", "Castanets", None, Some("percussion"), None, None),
+    ("China Cymbal", "Cymbal", None, Some("percussion"), None, None),
+    ("Claves", "Claves", None, Some("percussion"), None, None),
+    ("Congas", "Congas", None, Some("percussion"), None, None),
+    ("Cowbell", "Cowbell", None, Some("percussion"), None, None),
+    ("Crash Cymbal", "Cymbal", None, Some("percussion"), None, None),
+    ("Crash Cymbals", "Crash Cymbals", None, Some("percussion"), None, None),
+    ("Djembe", "Djembe", None, Some("percussion"), None, None),
+    ("Doumbek", "Doumbek", None, Some("percussion"), None, None),
+    ("Finger Cymbals", "Finger Cymbals", None, Some("percussion"), None, None),
+    ("Floor Tom", "Tom", None, Some("percussion"), None, None),
+    ("Guiro", "Guiro", None, Some("percussion"), None, None),
+    ("High Tom", "Tom", None, Some("percussion"), None, None),
+    ("Hi-hat", "Hi-hat", None, Some("percussion"), None, None),
+    ("Low Tom", "Tom", None, Some("percussion"), None, None),
+    ("Maracas", "Maracas", None, Some("percussion"), None, None),
+    ("Mark Tree", "Mark Tree", None, Some("percussion"), None, None),
+    ("Ratchet", "Ratchet", None, Some("percussion"), None, None),
+    ("Ride Cymbal", "Cymbal", None, Some("percussion"), None, None),
+    ("Shaker", "Shaker", None, Some("percussion"), None, None),
+    ("Sizzle Cymbal", "Cymbal", None, Some("percussion"), None, None),
+    ("Slap Stick", "Slap Stick", None, Some("percussion"), None, None),
+    ("Snare Drum", "Snare Drum", None, Some("percussion"), None, None),
+    ("Suspended Cymbal", "Cymbal", None, Some("percussion"), None, None),
+    ("Tabla", "Tabla", None, Some("percussion"), None, None),
+    ("Tam-tam", "Tam-tam", None, Some("percussion"), None, None),
+    ("Tambourine", "Tambourine", None, Some("percussion"), None, None),
+    ("Temple Block", "Temple Block", None, Some("percussion"), None, None),
+    ("Tenor Drum", "Tenor Drum", None, Some("percussion"), None, None),
+    ("Tenor Drums", "Tenor Drums", None, Some("percussion"), None, None),
+    ("Triangle", "Triangle", None, Some("percussion"), None, None),
+    ("Whip", "Whip", None, Some("percussion"), None, None),
+    ("Wood Block", "Wood Block", None, Some("percussion"), None, None),
+    ("Drum Set", "Drum Set", None, Some("percussion"), None, None),
+    ("Harp", "Harp", Some(0), Some("treble,bass"), Some("Cb1"), Some("G#7")),
+    ("Piano", "Piano", Some(0), Some("treble,bass"), Some("A0"), Some("C8")),
+    ("Celesta", "Celesta", Some(12), Some("treble,bass"), Some("C4"), Some("C8")),
+    ("Harpsichord", "Harpsichord", Some(0), Some("treble,bass"), Some("F1"), Some("F6")),
+    ("Organ", "Organ", Some(0), Some("treble,bass"), Some("C2"), Some("C7")),
+    ("Synthesizer", "Synthesizer", Some(0), Some("treble,bass"), None, None),
+    ("Accordion", "Accordion", Some(0), Some("treble,bass"), None, None),
+    ("Bandoneon", "Bandoneon", Some(0), Some("treble,bass"), Some("A1"), Some("A6")),
+    ("Melodica", "Melodica", Some(0), Some("treble"), Some("F3"), Some("E6")),
+    ("Acoustic Guitar", "Guitar", Some(-12), Some("treble"), Some("E2"), Some("B5")),
+    ("Classical Guitar", "Guitar", Some(-12), Some("treble"), Some("E2"), Some("B5")),
+    ("Electric Guitar", "Guitar", Some(-12), Some("treble"), Some("E2"), Some("E6")),
+    ("Baritone Guitar", "Baritone Guitar", Some(-12), Some("treble"), Some("B1"), Some("B4")),
+    ("Bass Guitar", "Bass Guitar", Some(-12), Some("bass"), Some("E1"), Some("G4")),
+    ("Ukulele", "Ukulele", Some(0), Some("treble"), Some("C4"), Some("A5")),
+    ("Banjo", "Banjo", Some(-12), Some("treble"), Some("C3"), Some("D5")),
+    ("Mandolin", "Mandolin", Some(0), Some("treble"), Some("G3"), Some("E6")),
+    ("Dobro", "Dobro", Some(-12), Some("treble"), Some("G2"), Some("D5")),
+    ("Lute", "Lute", Some(0), Some("treble"), None, None),
+    ("Sitar", "Sitar", None, None, None, None),
+    ("Shamisen", "Shamisen", None, None, None, None),
+    ("Pipa", "Pipa", Some(0), Some("treble"), Some("A2"), Some("D6")),
+    ("Guzheng", "Guzheng", Some(0), Some("treble,bass"), Some("D2"), Some("D6")),
+    ("Zhongruan", "Zhongruan", Some(0), Some("treble,bass"), None, None),
+    ("Sanxian", "Sanxian", None, None, None, None),
+    ("Daruan", "Daruan", Some(0), Some("bass"), None, None),
+    ("Liuqin", "Liuqin", Some(0), Some("treble"), None, None),
+    ("Soprano Voice", "Soprano Voice", Some(0), Some("treble"), Some("C4"), Some("C6")),
+    ("Mezzo-soprano Voice", "Mezzo-soprano Voice", Some(0), Some("treble"), Some("A3"), Some("A5")),
+    ("Alto Voice", "Alto Voice", Some(0), Some("treble"), Some("F3"), Some("F5")),
+    ("Countertenor Voice", "Countertenor Voice", Some(0), Some("treble"), Some("G3"), Some("E5")),
+    ("Tenor Voice", "Tenor Voice", Some(-12), Some("treble"), Some("C3"), Some("C5")),
+    ("Baritone Voice", "Baritone Voice", Some(0), Some("bass"), Some("A2"), Some("A4")),
+    ("Bass Voice", "Bass Voice", Some(0), Some("bass"), Some("E2"), Some("E4")),
+    ("Violin", "Violin", Some(0), Some("treble"), Some("G3"), Some("A7")),
+    ("Viola", "Viola", Some(0), Some("alto,treble"), Some("C3"), Some("E6")),
+    ("Cello", "Cello", Some(0), Some("bass,tenor,treble"), Some("C2"), Some("A5")),
+    ("Double Bass", "Double Bass", Some(-12), Some("bass"), Some("E1"), Some("G4")),
+    ("Viol", "Viol", Some(0), Some("alto,bass"), None, None),
+    ("Hurdy-gurdy", "Hurdy-gurdy", None, Some("treble"), None, None),
+    ("Erhu", "Erhu", Some(0), Some("treble"), Some("D4"), Some("D7")),
+    ("Saranghi", "Saranghi", None, None, None, None),
+];
 
 pub async fn seed_instruments(db: &DatabaseConnection) -> Result<HashMap<&str, i32>, DbErr> {
     let woodwinds = vec![
@@ -761,8 +914,37 @@ pub async fn seed_ensembles(
     Ok(())
 }
 
+/// Fills in the metadata of default instruments that don't have any yet.
+pub async fn seed_instrument_metadata(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let defaults = entities::instruments::Entity::find()
+        .filter(entities::instruments::Column::IsDefault.eq(true))
+        .filter(entities::instruments::Column::Family.is_null())
+        .all(db)
+        .await?;
+
+    for (score_order, (name, family, transposition, clefs, range_low, range_high)) in
+        INSTRUMENT_METADATA.iter().enumerate()
+    {
+        let instrument = defaults.iter().find(|instrument| instrument.name == *name);
+        if let Some(instrument) = instrument {
+            let metadata = instruments::InstrumentMetadata {
+                transposition: *transposition,
+                clefs: clefs.map(String::from),
+                range_low: range_low.map(String::from),
+                range_high: range_high.map(String::from),
+                family: Some(String::from(*family)),
+                score_order: Some(score_order as i32 + 1),
+            };
+            instruments::set_metadata(db, instrument.id, metadata).await?;
+        }
+    }
+
+    Ok(())
+}
+
 pub async fn seed(db: &DatabaseConnection) -> Result<(), DbErr> {
     let instrument_map = seed_instruments(&db).await?;
+    seed_instrument_metadata(db).await?;
     seed_ensembles(&db, instrument_map).await?;
 
     Ok(())
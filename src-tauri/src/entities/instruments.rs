@@ -10,6 +10,12 @@ pub struct Model {
     pub name: String,
     pub category: Option<String>,
     pub is_default: bool,
+    pub transposition: Option<i32>,
+    pub clefs: Option<String>,
+    pub range_low: Option<String>,
+    pub range_high: Option<String>,
+    pub family: Option<String>,
+    pub score_order: Option<i32>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -2,17 +2,32 @@
 
 pub mod prelude;
 
+pub mod acquisitions;
+pub mod draft_files;
+pub mod drafts;
 pub mod ensemble_parts_instruments;
+pub mod ensemble_parts_players;
 pub mod ensembles;
 pub mod ensembles_parts;
+pub mod events;
 pub mod instruments;
+pub mod loans;
+pub mod musician_aliases;
 pub mod musicians;
+pub mod part_assignments;
+pub mod part_files;
 pub mod parts;
 pub mod parts_instruments;
+pub mod performances;
 pub mod pieces;
 pub mod pieces_musicians;
+pub mod pieces_performances;
 pub mod pieces_setlists;
 pub mod pieces_tags;
+pub mod players;
+pub mod rehearsal_notes;
+pub mod roles;
 pub mod scores;
 pub mod setlists;
+pub mod tag_groups;
 pub mod tags;
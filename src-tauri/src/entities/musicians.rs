@@ -11,14 +11,28 @@ pub struct Model {
     pub last_name: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    pub birth_year: Option<i32>,
+    pub death_year: Option<i32>,
+    pub nationality: Option<String>,
+    pub sort_name: Option<String>,
+    pub display_name: Option<String>,
+    pub is_anonymous: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::musician_aliases::Entity")]
+    MusicianAliases,
     #[sea_orm(has_many = "super::pieces_musicians::Entity")]
     PiecesMusicians,
 }
 
+impl Related<super::musician_aliases::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MusicianAliases.def()
+    }
+}
+
 impl Related<super::pieces_musicians::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::PiecesMusicians.def()
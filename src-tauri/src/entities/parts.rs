@@ -12,6 +12,14 @@ pub struct Model {
     pub created_at: String,
     pub updated_at: String,
     pub piece_id: i32,
+    pub copies: i32,
+    pub page_count: Option<i32>,
+    pub page_size: Option<String>,
+    pub pdf_title: Option<String>,
+    pub pdf_author: Option<String>,
+    pub pdf_producer: Option<String>,
+    pub file_size: Option<i64>,
+    pub sha256: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
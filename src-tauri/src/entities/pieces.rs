@@ -14,6 +14,17 @@ pub struct Model {
     pub notes: String,
     pub created_at: String,
     pub updated_at: String,
+    pub publisher: Option<String>,
+    pub catalogue_number: Option<String>,
+    pub location_cabinet: Option<String>,
+    pub location_drawer: Option<String>,
+    pub location_box: Option<String>,
+    pub copyright_status: Option<String>,
+    pub copyright_reason: Option<String>,
+    pub copyright_rule: Option<String>,
+    pub rating: Option<i32>,
+    pub audience_rating: Option<i32>,
+    pub is_favorite: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -9,6 +9,7 @@ pub struct Model {
     pub piece_id: i32,
     #[sea_orm(primary_key, auto_increment = false)]
     pub setlist_id: i32,
+    pub order: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
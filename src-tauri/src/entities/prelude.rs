@@ -1,16 +1,31 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.11.3
 
+pub use super::acquisitions::Entity as Acquisitions;
+pub use super::draft_files::Entity as DraftFiles;
+pub use super::drafts::Entity as Drafts;
 pub use super::ensemble_parts_instruments::Entity as EnsemblePartsInstruments;
+pub use super::ensemble_parts_players::Entity as EnsemblePartsPlayers;
 pub use super::ensembles::Entity as Ensembles;
 pub use super::ensembles_parts::Entity as EnsemblesParts;
+pub use super::events::Entity as Events;
 pub use super::instruments::Entity as Instruments;
+pub use super::loans::Entity as Loans;
+pub use super::musician_aliases::Entity as MusicianAliases;
 pub use super::musicians::Entity as Musicians;
+pub use super::part_assignments::Entity as PartAssignments;
+pub use super::part_files::Entity as PartFiles;
 pub use super::parts::Entity as Parts;
 pub use super::parts_instruments::Entity as PartsInstruments;
+pub use super::performances::Entity as Performances;
 pub use super::pieces::Entity as Pieces;
 pub use super::pieces_musicians::Entity as PiecesMusicians;
+pub use super::pieces_performances::Entity as PiecesPerformances;
 pub use super::pieces_setlists::Entity as PiecesSetlists;
 pub use super::pieces_tags::Entity as PiecesTags;
+pub use super::players::Entity as Players;
+pub use super::rehearsal_notes::Entity as RehearsalNotes;
+pub use super::roles::Entity as Roles;
 pub use super::scores::Entity as Scores;
 pub use super::setlists::Entity as Setlists;
+pub use super::tag_groups::Entity as TagGroups;
 pub use super::tags::Entity as Tags;
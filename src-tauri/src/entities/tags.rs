@@ -11,10 +11,35 @@ pub struct Model {
     pub color: String,
     pub created_at: String,
     pub updated_at: String,
+    pub parent_id: Option<i32>,
+    pub group_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::ParentId",
+        to = "Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    SelfRef,
+    #[sea_orm(
+        belongs_to = "super::tag_groups::Entity",
+        from = "Column::GroupId",
+        to = "super::tag_groups::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    TagGroups,
+}
+
+impl Related<super::tag_groups::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TagGroups.def()
+    }
+}
 
 impl Related<super::pieces::Entity> for Entity {
     fn to() -> RelationDef {
@@ -9,6 +9,9 @@ mod app;
 mod db;
 mod entities;
 mod migrator;
+mod naming;
+mod pdf;
+mod render;
 mod services;
 mod settings;
 mod state;
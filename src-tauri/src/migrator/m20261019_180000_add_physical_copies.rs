@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Parts::Table)
+                    .add_column(
+                        ColumnDef::new(Parts::Copies)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // SQLite only allows one column per ALTER TABLE statement
+        let columns = vec![
+            Pieces::LocationCabinet,
+            Pieces::LocationDrawer,
+            Pieces::LocationBox,
+        ];
+
+        for column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Pieces::Table)
+                        .add_column(ColumnDef::new(column).string())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Parts::Table)
+                    .drop_column(Parts::Copies)
+                    .to_owned(),
+            )
+            .await?;
+
+        let columns = vec![
+            Pieces::LocationCabinet,
+            Pieces::LocationDrawer,
+            Pieces::LocationBox,
+        ];
+
+        for column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Pieces::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Parts {
+    Table,
+    Copies,
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Pieces {
+    Table,
+    LocationCabinet,
+    LocationDrawer,
+    LocationBox,
+}
@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230724_024826_create_ensembles::Ensembles;
+use super::m20230731_044608_create_setlists::Setlists;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let current_timestamp = chrono::Local::now().naive_local();
+        manager
+            .create_table(
+                Table::create()
+                    .table(Performances::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Performances::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Performances::Date).string().not_null())
+                    .col(ColumnDef::new(Performances::Venue).string())
+                    .col(ColumnDef::new(Performances::EnsembleId).integer())
+                    .col(ColumnDef::new(Performances::Conductor).string())
+                    .col(ColumnDef::new(Performances::SetlistId).integer())
+                    .col(ColumnDef::new(Performances::Notes).string())
+                    .col(
+                        ColumnDef::new(Performances::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(current_timestamp.to_string()),
+                    )
+                    .col(
+                        ColumnDef::new(Performances::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(current_timestamp.to_string()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_performance_ensemble")
+                            .from(Performances::Table, Performances::EnsembleId)
+                            .to(Ensembles::Table, Ensembles::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_performance_setlist")
+                            .from(Performances::Table, Performances::SetlistId)
+                            .to(Setlists::Table, Setlists::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Performances::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum Performances {
+    Table,
+    Id,
+    Date,
+    Venue,
+    EnsembleId,
+    Conductor,
+    SetlistId,
+    Notes,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,80 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230731_044608_create_setlists::Setlists;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let current_timestamp = chrono::Local::now().naive_local();
+        manager
+            .create_table(
+                Table::create()
+                    .table(Events::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Events::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Events::EventType).string().not_null())
+                    .col(ColumnDef::new(Events::Title).string().not_null())
+                    .col(ColumnDef::new(Events::StartsAt).string().not_null())
+                    .col(ColumnDef::new(Events::EndsAt).string())
+                    .col(ColumnDef::new(Events::CallTime).string())
+                    .col(ColumnDef::new(Events::Location).string())
+                    .col(ColumnDef::new(Events::SetlistId).integer())
+                    .col(ColumnDef::new(Events::Notes).string())
+                    .col(ColumnDef::new(Events::Uid).string().not_null().unique_key())
+                    .col(
+                        ColumnDef::new(Events::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(current_timestamp.to_string()),
+                    )
+                    .col(
+                        ColumnDef::new(Events::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(current_timestamp.to_string()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_event_setlist")
+                            .from(Events::Table, Events::SetlistId)
+                            .to(Setlists::Table, Setlists::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Events::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Events {
+    Table,
+    Id,
+    EventType,
+    Title,
+    StartsAt,
+    EndsAt,
+    CallTime,
+    Location,
+    SetlistId,
+    Notes,
+    Uid,
+    CreatedAt,
+    UpdatedAt,
+}
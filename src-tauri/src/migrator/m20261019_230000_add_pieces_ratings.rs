@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only allows one column per ALTER TABLE statement
+        let columns = vec![
+            ColumnDef::new(Pieces::Rating).integer().to_owned(),
+            ColumnDef::new(Pieces::AudienceRating).integer().to_owned(),
+            ColumnDef::new(Pieces::IsFavorite)
+                .boolean()
+                .not_null()
+                .default(false)
+                .to_owned(),
+        ];
+
+        for column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Pieces::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = vec![Pieces::Rating, Pieces::AudienceRating, Pieces::IsFavorite];
+
+        for column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Pieces::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Pieces {
+    Table,
+    Rating,
+    AudienceRating,
+    IsFavorite,
+}
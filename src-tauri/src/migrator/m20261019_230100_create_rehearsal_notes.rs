@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230724_024746_create_pieces::Pieces;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let current_timestamp = chrono::Local::now().naive_local();
+        manager
+            .create_table(
+                Table::create()
+                    .table(RehearsalNotes::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RehearsalNotes::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RehearsalNotes::PieceId).integer().not_null())
+                    .col(ColumnDef::new(RehearsalNotes::Note).string().not_null())
+                    .col(
+                        ColumnDef::new(RehearsalNotes::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(current_timestamp.to_string()),
+                    )
+                    .col(
+                        ColumnDef::new(RehearsalNotes::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(current_timestamp.to_string()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_rehearsal_note_piece")
+                            .from(RehearsalNotes::Table, RehearsalNotes::PieceId)
+                            .to(Pieces::Table, Pieces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RehearsalNotes::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum RehearsalNotes {
+    Table,
+    Id,
+    PieceId,
+    Note,
+    CreatedAt,
+    UpdatedAt,
+}
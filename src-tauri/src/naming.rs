@@ -0,0 +1,318 @@
+use std::path::PathBuf;
+
+/// The layout the edit wizard has always used, e.g. `12_Holst, Gustav/34_First Suite in Eb`.
+pub const DEFAULT_FOLDER_TEMPLATE: &str = "{composer_id}_{composer}/{piece_id}_{title}";
+/// Scores first (`0.1_Full Score`), then parts (`1.3_Clarinet 2`).
+pub const DEFAULT_FILE_TEMPLATE: &str = "{kind}.{order}_{name}";
+
+/// Placeholders describing the piece, usable in both templates.
+pub const PIECE_FIELDS: [&str; 7] = [
+    "composer",
+    "composer_id",
+    "arranger",
+    "title",
+    "year",
+    "catalogue_number",
+    "piece_id",
+];
+/// Placeholders describing one score or part file.
+/// `kind` is 0 for scores and 1 for parts so scores sort first.
+pub const FILE_FIELDS: [&str; 4] = ["kind", "order", "name", "instrument"];
+
+// names Windows keeps for devices, whatever the extension
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+// bytes, leaving room for the extension and a collision suffix under the usual 255
+const MAX_NAME_LENGTH: usize = 200;
+
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct Templates {
+    pub folder: String,
+    pub file: String,
+}
+
+impl Default for Templates {
+    fn default() -> Self {
+        Self {
+            folder: String::from(DEFAULT_FOLDER_TEMPLATE),
+            file: String::from(DEFAULT_FILE_TEMPLATE),
+        }
+    }
+}
+
+/// What a piece is called, for filling in templates.
+/// Musicians go by their sort name, e.g. "Holst, Gustav".
+#[derive(Debug, Clone, Default)]
+pub struct PieceNames {
+    pub piece_id: i32,
+    pub title: String,
+    pub composer: Option<String>,
+    pub composer_id: Option<i32>,
+    pub arranger: Option<String>,
+    pub year: Option<i32>,
+    pub catalogue_number: Option<String>,
+}
+
+/// What a score or part file is called, for filling in the file template.
+#[derive(Debug, Clone, Default)]
+pub struct FileNames {
+    pub is_score: bool,
+    /// Counts from 1 among the scores or the parts of the piece.
+    pub order: usize,
+    pub name: String,
+    pub instrument: Option<String>,
+}
+
+impl Templates {
+    /// Checks both templates only use known placeholders, and that the file
+    /// template names a file rather than folders.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.folder.trim_matches('/').is_empty() {
+            return Err(String::from("The folder template is empty"));
+        }
+        check_fields(&self.folder, &PIECE_FIELDS)?;
+        let folder_fields = fields(&self.folder)?;
+        if !folder_fields.contains(&"title") && !folder_fields.contains(&"piece_id") {
+            return Err(format!(
+                "The folder template {} needs {{title}} or {{piece_id}} to give each piece a folder",
+                self.folder
+            ));
+        }
+
+        if self.file.contains('/') || self.file.contains('\\') {
+            return Err(format!(
+                "The file template {} cannot contain folders",
+                self.file
+            ));
+        }
+        let file_fields = fields(&self.file)?;
+        if !file_fields.contains(&"order") && !file_fields.contains(&"name") {
+            return Err(format!(
+                "The file template {} needs {{order}} or {{name}} to tell the files apart",
+                self.file
+            ));
+        }
+        let all_fields: Vec<&str> = PIECE_FIELDS.iter().chain(&FILE_FIELDS).copied().collect();
+        check_fields(&self.file, &all_fields)
+    }
+
+    /// The piece folder relative to the library. Folders whose placeholders are
+    /// all empty are left out, so a piece without a composer sits at the top.
+    pub fn folder(&self, piece: &PieceNames) -> PathBuf {
+        let values = piece_values(piece);
+
+        self.folder
+            .split('/')
+            .filter_map(|segment| render(segment, &values))
+            .map(|segment| file_name(&segment))
+            .filter(|segment| !segment.is_empty())
+            .collect()
+    }
+
+    /// The file name of a score or part, with the `.pdf` extension.
+    pub fn file(&self, piece: &PieceNames, file: &FileNames) -> String {
+        let mut values = piece_values(piece);
+        values.extend([
+            (
+                "kind",
+                Some(String::from(if file.is_score { "0" } else { "1" })),
+            ),
+            ("order", Some(file.order.to_string())),
+            ("name", Some(file.name.clone())),
+            ("instrument", file.instrument.clone()),
+        ]);
+
+        let name = render(&self.file, &values).unwrap_or_default();
+        format!("{}.pdf", file_name(&name))
+    }
+}
+
+/// Turns a name into something that can be used as a file name on every platform:
+/// no path separators or characters Windows refuses, no control characters,
+/// no trailing dots or spaces, no device names, and not too long.
+pub fn file_name(name: &str) -> String {
+    let mut name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    if name.len() > MAX_NAME_LENGTH {
+        let mut end = MAX_NAME_LENGTH;
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        name.truncate(end);
+    }
+
+    let name = name.trim().trim_end_matches('.').trim_end();
+    let stem = name.split('.').next().unwrap_or_default();
+    match RESERVED_NAMES.contains(&stem.to_uppercase().as_str()) {
+        true => format!("_{}", name),
+        false => name.to_string(),
+    }
+}
+
+fn piece_values(piece: &PieceNames) -> Vec<(&'static str, Option<String>)> {
+    vec![
+        ("composer", piece.composer.clone()),
+        ("composer_id", piece.composer_id.map(|id| id.to_string())),
+        ("arranger", piece.arranger.clone()),
+        ("title", Some(piece.title.clone())),
+        ("year", piece.year.map(|year| year.to_string())),
+        ("catalogue_number", piece.catalogue_number.clone()),
+        ("piece_id", Some(piece.piece_id.to_string())),
+    ]
+}
+
+/// Fills in the placeholders of a template, or gives `None` when it has
+/// placeholders and every one of them is empty.
+fn render(template: &str, values: &[(&str, Option<String>)]) -> Option<String> {
+    let mut rendered = String::new();
+    let mut placeholders = 0;
+    let mut filled = 0;
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+        rendered.push_str(&rest[..start]);
+
+        let field = &rest[start + 1..end];
+        let value = values
+            .iter()
+            .find(|(name, _)| *name == field)
+            .and_then(|(_, value)| value.clone())
+            .filter(|value| !value.trim().is_empty());
+        placeholders += 1;
+        if let Some(value) = value {
+            filled += 1;
+            // a value can't start new folders
+            rendered.push_str(&value.replace(['/', '\\'], "_"));
+        }
+
+        rest = &rest[end + 1..];
+    }
+    rendered.push_str(rest);
+
+    match placeholders > 0 && filled == 0 {
+        true => None,
+        false => Some(rendered),
+    }
+}
+
+fn fields(template: &str) -> Result<Vec<&str>, String> {
+    let mut fields = vec![];
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("{} has a {{ without a }}", template))?;
+        fields.push(&rest[start + 1..start + end]);
+        rest = &rest[start + end + 1..];
+    }
+
+    Ok(fields)
+}
+
+fn check_fields(template: &str, known: &[&str]) -> Result<(), String> {
+    for field in fields(template)? {
+        if !known.contains(&field) {
+            return Err(format!(
+                "{{{}}} is not one of {{{}}}",
+                field,
+                known.join("}, {")
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("Horn in F 1/2"), "Horn in F 1_2");
+        assert_eq!(file_name(" Trumpet: Bb "), "Trumpet_ Bb");
+        assert_eq!(file_name("Finale..."), "Finale");
+        assert_eq!(file_name("con"), "_con");
+        assert_eq!(file_name("Aux. Percussion"), "_Aux. Percussion");
+        assert_eq!(file_name(&"é".repeat(150)).len(), MAX_NAME_LENGTH);
+    }
+
+    #[test]
+    fn test_templates() {
+        let piece = PieceNames {
+            piece_id: 34,
+            title: String::from("First Suite in Eb"),
+            composer: Some(String::from("Holst, Gustav")),
+            composer_id: Some(12),
+            ..Default::default()
+        };
+        let part = FileNames {
+            is_score: false,
+            order: 3,
+            name: String::from("Clarinet 2"),
+            instrument: Some(String::from("Clarinet in Bb")),
+        };
+
+        let templates = Templates::default();
+        assert!(templates.validate().is_ok());
+        assert_eq!(
+            templates.folder(&piece),
+            PathBuf::from("12_Holst, Gustav").join("34_First Suite in Eb")
+        );
+        assert_eq!(templates.file(&piece, &part), "1.3_Clarinet 2.pdf");
+
+        let anonymous = PieceNames {
+            composer: None,
+            composer_id: None,
+            ..piece.clone()
+        };
+        assert_eq!(
+            templates.folder(&anonymous),
+            PathBuf::from("34_First Suite in Eb")
+        );
+
+        let templates = Templates {
+            folder: String::from("{arranger}/{composer}/{title} ({year})"),
+            file: String::from("{name} - {instrument}"),
+        };
+        let dated = PieceNames {
+            year: Some(1909),
+            ..piece.clone()
+        };
+        assert_eq!(
+            templates.folder(&dated),
+            PathBuf::from("Holst, Gustav").join("First Suite in Eb (1909)")
+        );
+        assert_eq!(
+            templates.file(&piece, &part),
+            "Clarinet 2 - Clarinet in Bb.pdf"
+        );
+
+        assert!(Templates {
+            folder: String::from("{composer}/{arranger}"),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(Templates {
+            file: String::from("{title}/{name}"),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,590 @@
+use eyre::{eyre, Result};
+use lopdf::{
+    content::{Content, Operation},
+    dictionary, Dictionary, Document, Object, ObjectId, Stream,
+};
+use serde::Serialize;
+use std::{
+    fs,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+/// PDF page sizes in points, portrait. Sheet music mostly comes in one of these.
+const PAGE_SIZES: [(&str, f32, f32); 4] = [
+    ("letter", 612.0, 792.0),
+    ("a4", 595.0, 842.0),
+    ("concert", 648.0, 864.0),
+    ("march_card", 360.0, 504.0),
+];
+
+/// How far (in points, about a quarter inch) a page may be off and still count as a known size.
+const PAGE_SIZE_TOLERANCE: f32 = 18.0;
+
+/// Margin around the text of the pages a binder adds, in points.
+const MARGIN: f32 = 72.0;
+
+/// Distance between lines of text, as a multiple of the font size.
+const LINE_SPACING: f32 = 1.6;
+
+const HEADING_SIZE: f32 = 18.0;
+const CONTENTS_SIZE: f32 = 12.0;
+
+/// Page attributes a page may take from its parents in the page tree.
+const INHERITED: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct PdfInfo {
+    pub page_count: i32,
+    pub page_size: Option<String>,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub producer: Option<String>,
+    pub file_size: i64,
+}
+
+/// Reads the page count, first page size and document info of a PDF.
+pub fn read_info<P: AsRef<Path>>(path: P) -> Result<PdfInfo> {
+    let path = path.as_ref();
+    let file_size = fs::metadata(path)?.len() as i64;
+    let document = Document::load(path).map_err(|e| eyre!("{}: {}", path.display(), e))?;
+
+    let pages = document.get_pages();
+    let page_size = pages
+        .values()
+        .next()
+        .and_then(|page_id| media_box(&document, *page_id))
+        .map(|(width, height)| page_size_name(width, height));
+
+    let info = document
+        .trailer
+        .get(b"Info")
+        .ok()
+        .and_then(|info| resolve(&document, info).as_dict().ok());
+
+    Ok(PdfInfo {
+        page_count: pages.len() as i32,
+        page_size,
+        title: info.and_then(|info| text(&document, info, b"Title")),
+        author: info.and_then(|info| text(&document, info, b"Author")),
+        producer: info.and_then(|info| text(&document, info, b"Producer")),
+        file_size,
+    })
+}
+
+/// Like `read_info`, but for paths stored on scores and parts: anything that
+/// isn't a readable PDF has no info.
+pub fn try_read_info(path: &Option<String>) -> Option<PdfInfo> {
+    let path = Path::new(path.as_deref()?);
+    let is_pdf = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"));
+    if !is_pdf {
+        return None;
+    }
+
+    read_info(path).ok()
+}
+
+/// Writes the given page ranges (counted from 1) of a PDF to separate files.
+/// When one can't be written, the ones written before it are removed again.
+pub fn split<P: AsRef<Path>>(source: P, ranges: &[(RangeInclusive<u32>, PathBuf)]) -> Result<()> {
+    let source = source.as_ref();
+    let document = Document::load(source).map_err(|e| eyre!("{}: {}", source.display(), e))?;
+    let page_count = document.get_pages().len() as u32;
+
+    for (range, _) in ranges {
+        if range.is_empty() || *range.start() < 1 || *range.end() > page_count {
+            return Err(eyre!(
+                "Pages {}-{} are not within the {} pages of {}",
+                range.start(),
+                range.end(),
+                page_count,
+                source.display()
+            ));
+        }
+    }
+
+    for (index, (range, destination)) in ranges.iter().enumerate() {
+        let other_pages: Vec<u32> = (1..=page_count)
+            .filter(|page| !range.contains(page))
+            .collect();
+
+        let mut part = document.clone();
+        part.delete_pages(&other_pages);
+        part.prune_objects();
+        part.renumber_objects();
+        part.compress();
+        if let Err(e) = part.save(destination) {
+            for (_, written) in &ranges[..index] {
+                let _ = fs::remove_file(written);
+            }
+            return Err(eyre!("{}: {}", destination.display(), e));
+        }
+    }
+
+    Ok(())
+}
+
+/// Merges the PDFs of each section, in order, behind a generated cover page and
+/// table of contents. Sections without files are listed as missing.
+///
+/// Returns the page each section starts on.
+pub fn binder<P: AsRef<Path>>(
+    title: &str,
+    subtitle: &str,
+    sections: &[(String, Vec<PathBuf>)],
+    destination: P,
+) -> Result<Vec<Option<u32>>> {
+    let destination = destination.as_ref();
+    if sections.is_empty() {
+        return Err(eyre!("A binder needs at least one section"));
+    }
+
+    let mut documents: Vec<Vec<Document>> = vec![];
+    for (_, files) in sections {
+        let mut section = vec![];
+        for file in files {
+            section.push(Document::load(file).map_err(|e| eyre!("{}: {}", file.display(), e))?);
+        }
+        documents.push(section);
+    }
+
+    // the generated pages take the size of the first part, so the binder stays even
+    let (width, height) = documents
+        .iter()
+        .flatten()
+        .find_map(|document| {
+            let page_id = *document.get_pages().values().next()?;
+            media_box(document, page_id)
+        })
+        .unwrap_or((612.0, 792.0));
+
+    // the cover comes first, then the contents
+    let (first_lines, page_lines) = contents_lines(height);
+    let contents_pages = 1 + sections
+        .len()
+        .saturating_sub(first_lines)
+        .div_ceil(page_lines) as u32;
+    let mut first_pages = vec![];
+    let mut next_page = contents_pages + 2;
+    for section in &documents {
+        if section.is_empty() {
+            first_pages.push(None);
+            continue;
+        }
+        first_pages.push(Some(next_page));
+        next_page += section
+            .iter()
+            .map(|document| document.get_pages().len() as u32)
+            .sum::<u32>();
+    }
+
+    let mut binder = Document::with_version("1.5");
+    let pages_id = binder.new_object_id();
+    let font_id = binder.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    });
+    let resources_id = binder.add_object(dictionary! {
+        "Font" => dictionary! {
+            "F1" => font_id,
+        },
+    });
+
+    let mut page_ids: Vec<ObjectId> = vec![];
+
+    let cover = vec![
+        (28.0, latin1(title), None),
+        (18.0, latin1(subtitle), None),
+        (12.0, latin1(&format!("{} pieces", sections.len())), None),
+    ];
+    page_ids.push(text_page(
+        &mut binder,
+        (pages_id, resources_id),
+        (width, height),
+        &cover,
+    )?);
+
+    let contents: Vec<(f32, Vec<u8>, Option<Vec<u8>>)> = sections
+        .iter()
+        .zip(&first_pages)
+        .map(|((name, _), first_page)| {
+            let page = match first_page {
+                Some(page) => page.to_string(),
+                None => String::from("missing"),
+            };
+            // leave room for the page number on narrow pages like march cards
+            let name: String = name.chars().take(title_length(width)).collect();
+            (CONTENTS_SIZE, latin1(&name), Some(latin1(&page)))
+        })
+        .collect();
+    let (first, rest) = contents.split_at(first_lines.min(contents.len()));
+    for (index, lines) in std::iter::once(first)
+        .chain(rest.chunks(page_lines))
+        .enumerate()
+    {
+        let mut page = vec![];
+        if index == 0 {
+            page.push((HEADING_SIZE, latin1("Contents"), None));
+        }
+        page.extend_from_slice(lines);
+        page_ids.push(text_page(
+            &mut binder,
+            (pages_id, resources_id),
+            (width, height),
+            &page,
+        )?);
+    }
+
+    for mut document in documents.into_iter().flatten() {
+        document.renumber_objects_with(binder.max_id + 1);
+        binder.max_id = document.max_id;
+
+        let pages = document.get_pages();
+        for page_id in pages.values() {
+            let mut page = inherit(&document, *page_id)
+                .ok_or_else(|| eyre!("Page {:?} is missing from its document", page_id))?;
+            page.set("Parent", pages_id);
+            binder.objects.insert(*page_id, Object::Dictionary(page));
+            page_ids.push(*page_id);
+        }
+
+        for (id, object) in document.objects {
+            let kind = object
+                .as_dict()
+                .ok()
+                .and_then(|dictionary| dictionary.get(b"Type").ok())
+                .and_then(|kind| kind.as_name().ok());
+            let is_tree = matches!(
+                kind,
+                Some(b"Catalog") | Some(b"Pages") | Some(b"Outlines") | Some(b"Outline")
+            );
+            if is_tree || binder.objects.contains_key(&id) {
+                continue;
+            }
+            binder.objects.insert(id, object);
+        }
+    }
+
+    let pages = dictionary! {
+        "Type" => "Pages",
+        "Kids" => page_ids.iter().map(|id| Object::Reference(*id)).collect::<Vec<Object>>(),
+        "Count" => page_ids.len() as i64,
+    };
+    binder.objects.insert(pages_id, Object::Dictionary(pages));
+    let catalog_id = binder.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    binder.trailer.set("Root", catalog_id);
+
+    binder.prune_objects();
+    binder.renumber_objects();
+    binder.compress();
+    binder
+        .save(destination)
+        .map_err(|e| eyre!("{}: {}", destination.display(), e))?;
+
+    Ok(first_pages)
+}
+
+/// Names the page size regardless of orientation, or gives the size in inches.
+pub fn page_size_name(width: f32, height: f32) -> String {
+    let (short, long) = match width < height {
+        true => (width, height),
+        false => (height, width),
+    };
+
+    for (name, size_short, size_long) in PAGE_SIZES {
+        if (short - size_short).abs() <= PAGE_SIZE_TOLERANCE
+            && (long - size_long).abs() <= PAGE_SIZE_TOLERANCE
+        {
+            return name.to_string();
+        }
+    }
+
+    format!("{:.2}x{:.2}in", width / 72.0, height / 72.0)
+}
+
+/// Returns the width and height of a page, following the page tree up for inherited boxes.
+fn media_box(document: &Document, page_id: ObjectId) -> Option<(f32, f32)> {
+    let mut page = document.get_dictionary(page_id).ok()?;
+
+    loop {
+        if let Ok(media_box) = page.get(b"MediaBox") {
+            let corners: Vec<f32> = resolve(document, media_box)
+                .as_array()
+                .ok()?
+                .iter()
+                .filter_map(|corner| resolve(document, corner).as_float().ok())
+                .collect();
+            if corners.len() != 4 {
+                return None;
+            }
+
+            let width = (corners[2] - corners[0]).abs();
+            let height = (corners[3] - corners[1]).abs();
+            // pages turned a quarter are shown the other way round
+            let rotate = page
+                .get(b"Rotate")
+                .ok()
+                .and_then(|rotate| rotate.as_i64().ok())
+                .unwrap_or_default();
+            return match rotate.rem_euclid(180) == 90 {
+                true => Some((height, width)),
+                false => Some((width, height)),
+            };
+        }
+
+        let parent = page.get(b"Parent").ok()?.as_reference().ok()?;
+        page = document.get_dictionary(parent).ok()?;
+    }
+}
+
+/// How many table of contents entries fit on the first page, below the heading,
+/// and on the pages after it.
+fn contents_lines(height: f32) -> (usize, usize) {
+    let line = CONTENTS_SIZE * LINE_SPACING;
+    let space = height - 2.0 * MARGIN;
+    let first = ((space - HEADING_SIZE * LINE_SPACING) / line)
+        .floor()
+        .max(1.0);
+    let rest = (space / line).floor().max(1.0);
+    (first as usize, rest as usize)
+}
+
+/// How many characters of a title fit left of the page numbers, at about half
+/// the font size per character.
+fn title_length(width: f32) -> usize {
+    let space = width - 2.0 * MARGIN - 54.0;
+    ((space / (CONTENTS_SIZE / 2.0)) as usize).clamp(10, 60)
+}
+
+/// Adds a page of text to a document, one line after the other, with anything
+/// in the second column set to the right like page numbers in a table of contents.
+fn text_page(
+    document: &mut Document,
+    (pages_id, resources_id): (ObjectId, ObjectId),
+    (width, height): (f32, f32),
+    lines: &[(f32, Vec<u8>, Option<Vec<u8>>)],
+) -> Result<ObjectId> {
+    let mut operations = vec![];
+    let mut y = height - MARGIN;
+
+    for (size, text, right) in lines {
+        y -= size * LINE_SPACING;
+        let columns = std::iter::once((MARGIN, text))
+            .chain(right.iter().map(|right| (width - MARGIN - 54.0, right)));
+        for (x, text) in columns {
+            operations.push(Operation::new("BT", vec![]));
+            operations.push(Operation::new("Tf", vec!["F1".into(), (*size).into()]));
+            operations.push(Operation::new("Td", vec![x.into(), y.into()]));
+            operations.push(Operation::new(
+                "Tj",
+                vec![Object::string_literal(text.clone())],
+            ));
+            operations.push(Operation::new("ET", vec![]));
+        }
+    }
+
+    let content = Content { operations };
+    let content_id = document.add_object(Stream::new(dictionary! {}, content.encode()?));
+
+    Ok(document.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "MediaBox" => vec![0.into(), 0.into(), width.into(), height.into()],
+        "Contents" => content_id,
+        "Resources" => resources_id,
+    }))
+}
+
+/// Returns a copy of a page with the attributes it inherits set on the page itself,
+/// so it can be moved to another page tree.
+fn inherit(document: &Document, page_id: ObjectId) -> Option<Dictionary> {
+    let mut page = document.get_dictionary(page_id).ok()?.clone();
+    let mut parent = page.get(b"Parent").and_then(Object::as_reference).ok();
+
+    while let Some(parent_id) = parent {
+        let node = document.get_dictionary(parent_id).ok()?;
+        for key in INHERITED {
+            if !page.has(key) {
+                if let Ok(value) = node.get(key) {
+                    page.set(key, value.clone());
+                }
+            }
+        }
+        parent = node.get(b"Parent").and_then(Object::as_reference).ok();
+    }
+
+    Some(page)
+}
+
+/// Encodes text for the standard PDF fonts, which only know (about) Latin-1.
+fn latin1(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| u8::try_from(c).unwrap_or(b'?'))
+        .collect()
+}
+
+fn resolve<'a>(document: &'a Document, object: &'a Object) -> &'a Object {
+    match object {
+        Object::Reference(id) => document.get_object(*id).unwrap_or(object),
+        _ => object,
+    }
+}
+
+fn text(document: &Document, dictionary: &Dictionary, key: &[u8]) -> Option<String> {
+    let bytes = resolve(document, dictionary.get(key).ok()?).as_str().ok()?;
+    let text = decode_text(bytes).trim().to_string();
+
+    match text.is_empty() {
+        true => None,
+        false => Some(text),
+    }
+}
+
+/// Decodes a PDF text string, which is either UTF-16 with a byte order mark
+/// or (close enough to) Latin-1.
+fn decode_text(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xfe, 0xff]) {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    let utf8 = bytes.strip_prefix(&[0xef, 0xbb, 0xbf]).unwrap_or(bytes);
+    if let Ok(text) = std::str::from_utf8(utf8) {
+        return text.to_string();
+    }
+
+    bytes.iter().map(|byte| *byte as char).collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_size_name() {
+        assert_eq!(page_size_name(612.0, 792.0), "letter");
+        assert_eq!(page_size_name(841.89, 595.28), "a4");
+        assert_eq!(page_size_name(648.0, 864.0), "concert");
+        assert_eq!(page_size_name(504.0, 360.0), "march_card");
+        assert_eq!(page_size_name(612.0, 1008.0), "8.50x14.00in");
+    }
+
+    #[test]
+    fn test_binder() {
+        let directory = std::env::temp_dir().join("blackbird test binder");
+        fs::create_dir_all(&directory).unwrap();
+        let one_page = directory.join("one page.pdf");
+        let two_pages = directory.join("two pages.pdf");
+        write_test_pdf(&one_page, 1);
+        write_test_pdf(&two_pages, 2);
+
+        let sections = vec![
+            (
+                String::from("Suite in F"),
+                vec![one_page.clone(), two_pages],
+            ),
+            (String::from("Missing March"), vec![]),
+            (String::from("Finale"), vec![one_page]),
+        ];
+        let destination = directory.join("binder.pdf");
+        let first_pages = binder("Spring Concert", "Horn 1", &sections, &destination).unwrap();
+
+        // the cover and contents come first
+        assert_eq!(first_pages, vec![Some(3), None, Some(6)]);
+        let info = read_info(&destination).unwrap();
+        assert_eq!(info.page_count, 6);
+        assert_eq!(info.page_size, Some(String::from("letter")));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_split() {
+        let directory = std::env::temp_dir().join("blackbird test split");
+        fs::create_dir_all(&directory).unwrap();
+        let source = directory.join("parts.pdf");
+        write_test_pdf(&source, 5);
+
+        let ranges = vec![
+            (1..=2, directory.join("Flute.pdf")),
+            (3..=3, directory.join("Oboe.pdf")),
+            (4..=5, directory.join("Tuba.pdf")),
+        ];
+        split(&source, &ranges).unwrap();
+        let page_counts: Vec<i32> = ranges
+            .iter()
+            .map(|(_, path)| read_info(path).unwrap().page_count)
+            .collect();
+        assert_eq!(page_counts, vec![2, 1, 2]);
+
+        // ranges past the end write nothing
+        let past_end = vec![(5..=6, directory.join("Horn.pdf"))];
+        assert!(split(&source, &past_end).is_err());
+        assert!(!directory.join("Horn.pdf").exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_contents_lines() {
+        assert_eq!(contents_lines(792.0), (32, 33));
+        // march cards are short, so their contents run over sooner
+        assert_eq!(contents_lines(504.0), (17, 18));
+        assert_eq!(title_length(612.0), 60);
+        assert_eq!(title_length(360.0), 27);
+    }
+
+    #[test]
+    fn test_latin1() {
+        assert_eq!(latin1("Saint-Saëns"), b"Saint-Sa\xebns");
+        assert_eq!(latin1("Dvořák"), b"Dvo?\xe1k");
+    }
+
+    /// Writes a PDF of blank pages that inherit their size from the page tree.
+    pub(crate) fn write_test_pdf(path: &Path, page_count: usize) {
+        let mut document = Document::with_version("1.5");
+        let pages_id = document.new_object_id();
+        let kids: Vec<Object> = (0..page_count)
+            .map(|_| {
+                document
+                    .add_object(dictionary! {
+                        "Type" => "Page",
+                        "Parent" => pages_id,
+                    })
+                    .into()
+            })
+            .collect();
+        let pages = dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => page_count as i64,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+        };
+        document.objects.insert(pages_id, Object::Dictionary(pages));
+        let catalog_id = document.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        document.trailer.set("Root", catalog_id);
+        document.save(path).unwrap();
+    }
+
+    #[test]
+    fn test_decode_text() {
+        assert_eq!(decode_text(b"Suite in F"), "Suite in F");
+        assert_eq!(
+            decode_text(&[0xfe, 0xff, 0x00, 0x48, 0x00, 0x6f, 0x00, 0x6c, 0x00, 0x73, 0x00, 0x74]),
+            "Holst"
+        );
+        assert_eq!(decode_text(b"Saint-Sa\xebns"), "Saint-Saëns");
+    }
+}
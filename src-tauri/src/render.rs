@@ -0,0 +1,145 @@
+use eyre::{eyre, Result};
+use std::{fs, path::Path, process::Command};
+
+/// Pixels on the longest side of a rendered page.
+pub const SIZE: u32 = 512;
+
+/// Renders the first page of a PDF (or an image) to a PNG.
+///
+/// Quick Look does this on macOS. Everywhere else, and for anything Quick Look
+/// can't read, poppler's `pdftoppm` has to be installed; [`pdf_renderer`] tells
+/// whether it is.
+pub fn first_page<P: AsRef<Path>, Q: AsRef<Path>>(source: P, destination: Q) -> Result<()> {
+    let source = source.as_ref();
+    let destination = destination.as_ref();
+
+    if !source.exists() {
+        return Err(eyre!("{} does not exist", source.display()));
+    }
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if quick_look(source, destination).is_ok() {
+            return Ok(());
+        }
+    }
+
+    match extension(source).as_str() {
+        "pdf" => pdftoppm(source, destination),
+        "png" => {
+            image::open(source)?
+                .thumbnail(SIZE, SIZE)
+                .save_with_format(destination, image::ImageFormat::Png)?;
+            Ok(())
+        }
+        _ => Err(eyre!("Cannot render {}", source.display())),
+    }
+}
+
+/// Returns the name of what renders PDFs on this computer, or `None` when
+/// nothing can and previews of PDFs will fail.
+pub fn pdf_renderer() -> Option<&'static str> {
+    if cfg!(target_os = "macos") {
+        return Some("Quick Look");
+    }
+
+    // pdftoppm prints its version and exits, successfully or not depending on the version
+    match Command::new("pdftoppm").arg("-v").output() {
+        Ok(_) => Some("pdftoppm"),
+        Err(_) => None,
+    }
+}
+
+/// Renders with `pdftoppm`, which adds the extension to the name it's given.
+fn pdftoppm(source: &Path, destination: &Path) -> Result<()> {
+    let output = Command::new("pdftoppm")
+        .args(["-png", "-singlefile", "-f", "1", "-l", "1", "-scale-to"])
+        .arg(SIZE.to_string())
+        .arg(source)
+        .arg(destination.with_extension(""))
+        .output()
+        .map_err(|e| eyre!("Could not run pdftoppm, is poppler installed? {}", e))?;
+
+    if !output.status.success() || !destination.exists() {
+        return Err(eyre!(
+            "pdftoppm could not render {}: {}",
+            source.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Renders with Quick Look, which names the PNG after the source file in the output folder.
+#[cfg(target_os = "macos")]
+fn quick_look(source: &Path, destination: &Path) -> Result<()> {
+    let directory = destination.with_extension("quicklook");
+    fs::create_dir_all(&directory)?;
+
+    let output = Command::new("qlmanage")
+        .arg("-t")
+        .arg("-s")
+        .arg(SIZE.to_string())
+        .arg("-o")
+        .arg(&directory)
+        .arg(source)
+        .output();
+
+    let file_name = format!(
+        "{}.png",
+        source.file_name().unwrap_or_default().to_string_lossy()
+    );
+    let rendered = directory.join(file_name);
+    let result = match output {
+        Ok(output) if output.status.success() && rendered.exists() => {
+            fs::rename(&rendered, destination).map_err(|e| eyre!(e))
+        }
+        Ok(_) => Err(eyre!("Quick Look could not render {}", source.display())),
+        Err(e) => Err(eyre!(e)),
+    };
+
+    let _ = fs::remove_dir_all(&directory);
+    result
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension() {
+        assert_eq!(extension(Path::new("/music/Suite in F/0_Score.PDF")), "pdf");
+        assert_eq!(extension(Path::new("/music/Suite in F/notes")), "");
+    }
+
+    #[test]
+    fn test_png_thumbnail() {
+        let source = std::env::temp_dir().join("blackbird test render source.png");
+        let destination = std::env::temp_dir().join("blackbird test render thumbnail.png");
+        image::RgbImage::new(SIZE * 2, SIZE).save(&source).unwrap();
+
+        first_page(&source, &destination).unwrap();
+        let thumbnail = image::open(&destination).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (SIZE, SIZE / 2));
+
+        let _ = fs::remove_file(&source);
+        let _ = fs::remove_file(&destination);
+    }
+
+    #[test]
+    fn test_missing_source() {
+        let destination = std::env::temp_dir().join("blackbird test render.png");
+        assert!(first_page("/no/such/part.pdf", &destination).is_err());
+        assert!(!destination.exists());
+    }
+}
@@ -0,0 +1,274 @@
+use sea_orm::{
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::entities::{acquisitions, pieces};
+use crate::services::loans::parse_date;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+pub const ACQUISITION_TYPES: [&str; 5] = [
+    "purchased",
+    "rental",
+    "borrowed",
+    "public_domain",
+    "commissioned",
+];
+
+/// How a piece came into the library. Dates are in the form YYYY-MM-DD.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct AcquisitionDetails {
+    pub acquisition_type: String,
+    pub vendor: Option<String>,
+    pub cost: Option<f64>,
+    pub currency: Option<String>,
+    pub acquired_date: Option<String>,
+    pub rental_start: Option<String>,
+    /// The date a rental has to be back with the vendor.
+    pub rental_end: Option<String>,
+    pub returned_date: Option<String>,
+    pub performance_rights: Option<String>,
+    pub recording_rights: Option<String>,
+    pub notes: Option<String>,
+}
+
+pub async fn get_by_piece(db: &DatabaseConnection, piece_id: i32) -> Result<Vec<Value>, DbErr> {
+    let acquisitions = acquisitions::Entity::find()
+        .filter(acquisitions::Column::PieceId.eq(piece_id))
+        .order_by_asc(acquisitions::Column::CreatedAt)
+        .into_json()
+        .all(db)
+        .await?;
+
+    Ok(acquisitions)
+}
+
+pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr> {
+    let acquisition = acquisitions::Entity::find_by_id(id)
+        .into_json()
+        .one(db)
+        .await?;
+    match acquisition {
+        Some(acquisition) => Ok(acquisition),
+        None => Err(DbErr::RecordNotFound(format!(
+            "Acquisition with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn add(
+    db: &DatabaseConnection,
+    piece_id: i32,
+    details: AcquisitionDetails,
+) -> Result<i32, DbErr> {
+    validate(&details)?;
+
+    let piece = pieces::Entity::find_by_id(piece_id).one(db).await?;
+    if piece.is_none() {
+        return Err(DbErr::RecordNotFound(format!(
+            "Piece with id {} not found",
+            piece_id
+        )));
+    }
+
+    let active_acquisition = acquisitions::ActiveModel {
+        piece_id: ActiveValue::Set(piece_id),
+        acquisition_type: ActiveValue::Set(details.acquisition_type),
+        vendor: ActiveValue::Set(details.vendor),
+        cost: ActiveValue::Set(details.cost),
+        currency: ActiveValue::Set(details.currency),
+        acquired_date: ActiveValue::Set(details.acquired_date),
+        rental_start: ActiveValue::Set(details.rental_start),
+        rental_end: ActiveValue::Set(details.rental_end),
+        returned_date: ActiveValue::Set(details.returned_date),
+        performance_rights: ActiveValue::Set(details.performance_rights),
+        recording_rights: ActiveValue::Set(details.recording_rights),
+        notes: ActiveValue::Set(details.notes),
+        ..Default::default()
+    };
+
+    let acquisition = acquisitions::Entity::insert(active_acquisition)
+        .exec(db)
+        .await?;
+    Ok(acquisition.last_insert_id)
+}
+
+pub async fn update(
+    db: &DatabaseConnection,
+    id: i32,
+    details: AcquisitionDetails,
+) -> Result<(), DbErr> {
+    validate(&details)?;
+
+    let acquisition = acquisitions::Entity::find_by_id(id).one(db).await?;
+    match acquisition {
+        Some(acquisition) => {
+            let mut acquisition: acquisitions::ActiveModel = acquisition.into();
+
+            acquisition.acquisition_type = ActiveValue::Set(details.acquisition_type);
+            acquisition.vendor = ActiveValue::Set(details.vendor);
+            acquisition.cost = ActiveValue::Set(details.cost);
+            acquisition.currency = ActiveValue::Set(details.currency);
+            acquisition.acquired_date = ActiveValue::Set(details.acquired_date);
+            acquisition.rental_start = ActiveValue::Set(details.rental_start);
+            acquisition.rental_end = ActiveValue::Set(details.rental_end);
+            acquisition.returned_date = ActiveValue::Set(details.returned_date);
+            acquisition.performance_rights = ActiveValue::Set(details.performance_rights);
+            acquisition.recording_rights = ActiveValue::Set(details.recording_rights);
+            acquisition.notes = ActiveValue::Set(details.notes);
+            acquisition.updated_at =
+                ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            acquisitions::Entity::update(acquisition).exec(db).await?;
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Acquisition with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    acquisitions::Entity::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
+/// Marks a rental as sent back to the vendor, today unless a date is given.
+pub async fn mark_returned(
+    db: &DatabaseConnection,
+    id: i32,
+    returned_date: Option<String>,
+) -> Result<(), DbErr> {
+    let returned_date = match returned_date {
+        Some(returned_date) => {
+            parse_date(&returned_date)?;
+            returned_date
+        }
+        None => chrono::Local::now().date_naive().to_string(),
+    };
+
+    let acquisition = acquisitions::Entity::find_by_id(id).one(db).await?;
+    match acquisition {
+        Some(acquisition) => {
+            let mut acquisition: acquisitions::ActiveModel = acquisition.into();
+
+            acquisition.returned_date = ActiveValue::Set(Some(returned_date));
+            acquisition.updated_at =
+                ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            acquisitions::Entity::update(acquisition).exec(db).await?;
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Acquisition with id {} not found",
+            id
+        ))),
+    }
+}
+
+/// Lists rentals that have to go back within `days` days, overdue ones included, soonest first.
+pub async fn get_rentals_due(db: &DatabaseConnection, days: i64) -> Result<Vec<Value>, DbErr> {
+    let today = chrono::Local::now().date_naive();
+    let deadline = today + chrono::Duration::days(days);
+
+    let rentals = acquisitions::Entity::find()
+        .filter(acquisitions::Column::AcquisitionType.eq("rental"))
+        .filter(acquisitions::Column::RentalEnd.is_not_null())
+        .filter(acquisitions::Column::ReturnedDate.is_null())
+        .order_by_asc(acquisitions::Column::RentalEnd)
+        .all(db)
+        .await?;
+
+    let pieces = pieces::Entity::find()
+        .filter(pieces::Column::Id.is_in(rentals.iter().map(|rental| rental.piece_id)))
+        .all(db)
+        .await?;
+
+    let mut due: Vec<Value> = vec![];
+
+    for rental in rentals {
+        let rental_end = match rental.rental_end.as_deref().map(parse_date) {
+            Some(Ok(rental_end)) => rental_end,
+            _ => continue,
+        };
+        if rental_end > deadline {
+            continue;
+        }
+
+        let piece = pieces.iter().find(|piece| piece.id == rental.piece_id);
+
+        due.push(serde_json::json!({
+            "id": rental.id,
+            "piece_id": rental.piece_id,
+            "piece_title": piece.map(|piece| piece.title.clone()),
+            "vendor": rental.vendor,
+            "rental_end": rental.rental_end,
+            "days_left": (rental_end - today).num_days(),
+            "overdue": rental_end < today,
+            "performance_rights": rental.performance_rights,
+            "recording_rights": rental.recording_rights,
+        }));
+    }
+
+    Ok(due)
+}
+
+fn validate(details: &AcquisitionDetails) -> Result<(), DbErr> {
+    if !ACQUISITION_TYPES.contains(&details.acquisition_type.as_str()) {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Acquisition type {} is not one of {}",
+            details.acquisition_type,
+            ACQUISITION_TYPES.join(", ")
+        ))));
+    }
+
+    let dates = [
+        &details.acquired_date,
+        &details.rental_start,
+        &details.rental_end,
+        &details.returned_date,
+    ];
+    for date in dates.into_iter().flatten() {
+        parse_date(date)?;
+    }
+
+    if let (Some(rental_start), Some(rental_end)) = (&details.rental_start, &details.rental_end) {
+        if parse_date(rental_end)? < parse_date(rental_start)? {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "Rental end {} is before rental start {}",
+                rental_end, rental_start
+            ))));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let mut details = AcquisitionDetails {
+            acquisition_type: String::from("rental"),
+            rental_start: Some(String::from("2026-09-01")),
+            rental_end: Some(String::from("2026-11-30")),
+            ..Default::default()
+        };
+        assert!(validate(&details).is_ok());
+
+        details.rental_end = Some(String::from("2026-08-01"));
+        assert!(validate(&details).is_err());
+
+        details.rental_end = Some(String::from("30/11/2026"));
+        assert!(validate(&details).is_err());
+
+        details.rental_end = None;
+        details.acquisition_type = String::from("stolen");
+        assert!(validate(&details).is_err());
+    }
+}
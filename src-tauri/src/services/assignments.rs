@@ -0,0 +1,446 @@
+use sea_orm::{
+    ActiveValue, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+
+use crate::entities::{
+    ensemble_parts_players, part_assignments, parts, pieces, pieces_setlists, players, setlists,
+};
+use crate::services::coverage::{self, Substitution};
+
+use serde_json::Value;
+
+/// Returns every piece on the setlist with its parts and the players assigned to them.
+pub async fn get_by_setlist(db: &DatabaseConnection, setlist_id: i32) -> Result<Vec<Value>, DbErr> {
+    find_setlist(db, setlist_id).await?;
+
+    let pieces = get_pieces(db, setlist_id).await?;
+    let assignments = get_assignments(db, setlist_id).await?;
+    let players = players::Entity::find().all(db).await?;
+
+    let mut pieces_with_assignments: Vec<Value> = vec![];
+
+    for piece in pieces {
+        let parts = get_parts(db, piece.id).await?;
+
+        let parts: Vec<Value> = parts
+            .iter()
+            .map(|part| {
+                let assigned: Vec<Value> = assignments
+                    .iter()
+                    .filter(|assignment| assignment.part_id == part.id)
+                    .filter_map(|assignment| {
+                        players
+                            .iter()
+                            .find(|player| player.id == assignment.player_id)
+                    })
+                    .map(|player| {
+                        serde_json::json!({
+                            "id": player.id,
+                            "name": player.name,
+                        })
+                    })
+                    .collect();
+
+                serde_json::json!({
+                    "id": part.id,
+                    "name": part.name,
+                    "path": part.path,
+                    "players": assigned,
+                })
+            })
+            .collect();
+
+        pieces_with_assignments.push(serde_json::json!({
+            "id": piece.id,
+            "title": piece.title,
+            "parts": parts,
+        }));
+    }
+
+    Ok(pieces_with_assignments)
+}
+
+/// Hands a part of a piece on the setlist to the players, replacing earlier assignments.
+pub async fn set(
+    db: &DatabaseConnection,
+    setlist_id: i32,
+    part_id: i32,
+    player_ids: Vec<i32>,
+) -> Result<(), DbErr> {
+    find_setlist(db, setlist_id).await?;
+
+    let part = parts::Entity::find_by_id(part_id).one(db).await?;
+    let part = match part {
+        Some(part) => part,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Part with id {} not found",
+                part_id
+            )))
+        }
+    };
+
+    let on_setlist = pieces_setlists::Entity::find_by_id((part.piece_id, setlist_id))
+        .one(db)
+        .await?;
+    if on_setlist.is_none() {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Piece with id {} is not on setlist {}",
+            part.piece_id, setlist_id
+        ))));
+    }
+
+    part_assignments::Entity::delete_many()
+        .filter(
+            Condition::all()
+                .add(part_assignments::Column::SetlistId.eq(setlist_id))
+                .add(part_assignments::Column::PartId.eq(part_id)),
+        )
+        .exec(db)
+        .await?;
+
+    for player_id in player_ids {
+        insert(db, setlist_id, part_id, player_id).await?;
+    }
+
+    Ok(())
+}
+
+/// Fills in unassigned parts from the ensemble roster, giving each part to the
+/// players seated in the chair that covers it. Returns the number of parts assigned.
+pub async fn assign_from_roster(
+    db: &DatabaseConnection,
+    setlist_id: i32,
+    ensemble_id: i32,
+    substitutions: &[Substitution],
+) -> Result<usize, DbErr> {
+    find_setlist(db, setlist_id).await?;
+
+    let assignments = get_assignments(db, setlist_id).await?;
+    let roster = ensemble_parts_players::Entity::find().all(db).await?;
+
+    let mut assigned = 0;
+
+    for piece in get_pieces(db, setlist_id).await? {
+        let report = coverage::check(db, piece.id, ensemble_id, substitutions).await?;
+
+        for assignment in report["assignments"].as_array().into_iter().flatten() {
+            let part_id = assignment["part"]["id"].as_i64().unwrap_or_default() as i32;
+            let chair_id = assignment["ensemble_part"]["id"]
+                .as_i64()
+                .unwrap_or_default() as i32;
+
+            if assignments
+                .iter()
+                .any(|assignment| assignment.part_id == part_id)
+            {
+                continue;
+            }
+
+            let seated: Vec<i32> = roster
+                .iter()
+                .filter(|seat| seat.part_id == chair_id)
+                .map(|seat| seat.player_id)
+                .collect();
+            if seated.is_empty() {
+                continue;
+            }
+
+            for player_id in seated {
+                insert(db, setlist_id, part_id, player_id).await?;
+            }
+            assigned += 1;
+        }
+    }
+
+    Ok(assigned)
+}
+
+/// Lists the parts a player has to prepare for the setlist, with a printable text version.
+pub async fn get_player_sheet(
+    db: &DatabaseConnection,
+    setlist_id: i32,
+    player_id: i32,
+) -> Result<Value, DbErr> {
+    let setlist = find_setlist(db, setlist_id).await?;
+
+    let player = players::Entity::find_by_id(player_id).one(db).await?;
+    let player = match player {
+        Some(player) => player,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Player with id {} not found",
+                player_id
+            )))
+        }
+    };
+
+    let assignments: Vec<part_assignments::Model> = get_assignments(db, setlist_id)
+        .await?
+        .into_iter()
+        .filter(|assignment| assignment.player_id == player_id)
+        .collect();
+
+    let mut text = format!("{}\n{}\n", setlist.name, player.name);
+    let mut pieces_with_parts: Vec<Value> = vec![];
+
+    for piece in get_pieces(db, setlist_id).await? {
+        let parts: Vec<parts::Model> = get_parts(db, piece.id)
+            .await?
+            .into_iter()
+            .filter(|part| {
+                assignments
+                    .iter()
+                    .any(|assignment| assignment.part_id == part.id)
+            })
+            .collect();
+        if parts.is_empty() {
+            continue;
+        }
+
+        text.push_str(&format!("\n{}\n", piece.title));
+        for part in &parts {
+            text.push_str(&format!("  {}\n", part.name));
+        }
+
+        pieces_with_parts.push(serde_json::json!({
+            "id": piece.id,
+            "title": piece.title,
+            "parts": parts
+                .iter()
+                .map(|part| serde_json::json!({
+                    "id": part.id,
+                    "name": part.name,
+                    "path": part.path,
+                }))
+                .collect::<Vec<Value>>(),
+        }));
+    }
+
+    Ok(serde_json::json!({
+        "setlist": {
+            "id": setlist.id,
+            "name": setlist.name,
+        },
+        "player": {
+            "id": player.id,
+            "name": player.name,
+        },
+        "pieces": pieces_with_parts,
+        "text": text,
+    }))
+}
+
+/// Lists who plays each part of a piece on the setlist, with a printable text version.
+pub async fn get_piece_sheet(
+    db: &DatabaseConnection,
+    setlist_id: i32,
+    piece_id: i32,
+) -> Result<Value, DbErr> {
+    let setlist = find_setlist(db, setlist_id).await?;
+
+    let piece = get_pieces(db, setlist_id)
+        .await?
+        .into_iter()
+        .find(|piece| piece.id == piece_id);
+    let piece = match piece {
+        Some(piece) => piece,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Piece with id {} not found on setlist {}",
+                piece_id, setlist_id
+            )))
+        }
+    };
+
+    let assignments = get_assignments(db, setlist_id).await?;
+    let players = players::Entity::find().all(db).await?;
+
+    let mut text = format!("{}\n{}\n\n", setlist.name, piece.title);
+    let mut parts_with_players: Vec<Value> = vec![];
+
+    for part in get_parts(db, piece_id).await? {
+        let assigned: Vec<&players::Model> = assignments
+            .iter()
+            .filter(|assignment| assignment.part_id == part.id)
+            .filter_map(|assignment| {
+                players
+                    .iter()
+                    .find(|player| player.id == assignment.player_id)
+            })
+            .collect();
+
+        let names: Vec<String> = assigned.iter().map(|player| player.name.clone()).collect();
+        if names.is_empty() {
+            text.push_str(&format!("{}: (unassigned)\n", part.name));
+        } else {
+            text.push_str(&format!("{}: {}\n", part.name, names.join(", ")));
+        }
+
+        parts_with_players.push(serde_json::json!({
+            "id": part.id,
+            "name": part.name,
+            "players": assigned
+                .iter()
+                .map(|player| serde_json::json!({
+                    "id": player.id,
+                    "name": player.name,
+                }))
+                .collect::<Vec<Value>>(),
+        }));
+    }
+
+    Ok(serde_json::json!({
+        "setlist": {
+            "id": setlist.id,
+            "name": setlist.name,
+        },
+        "piece": {
+            "id": piece.id,
+            "title": piece.title,
+        },
+        "parts": parts_with_players,
+        "text": text,
+    }))
+}
+
+async fn insert(
+    db: &DatabaseConnection,
+    setlist_id: i32,
+    part_id: i32,
+    player_id: i32,
+) -> Result<(), DbErr> {
+    let active_assignment = part_assignments::ActiveModel {
+        setlist_id: ActiveValue::Set(setlist_id),
+        part_id: ActiveValue::Set(part_id),
+        player_id: ActiveValue::Set(player_id),
+        ..Default::default()
+    };
+
+    part_assignments::Entity::insert(active_assignment)
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+async fn find_setlist(db: &DatabaseConnection, id: i32) -> Result<setlists::Model, DbErr> {
+    let setlist = setlists::Entity::find_by_id(id).one(db).await?;
+    match setlist {
+        Some(setlist) => Ok(setlist),
+        None => Err(DbErr::RecordNotFound(format!(
+            "Setlist with id {} not found",
+            id
+        ))),
+    }
+}
+
+async fn get_pieces(db: &DatabaseConnection, setlist_id: i32) -> Result<Vec<pieces::Model>, DbErr> {
+    let piece_ids: Vec<i32> = pieces_setlists::Entity::find()
+        .filter(pieces_setlists::Column::SetlistId.eq(setlist_id))
+        .order_by_asc(pieces_setlists::Column::Order)
+        .all(db)
+        .await?
+        .iter()
+        .map(|piece_setlist| piece_setlist.piece_id)
+        .collect();
+
+    let mut pieces = pieces::Entity::find()
+        .filter(pieces::Column::Id.is_in(piece_ids.clone()))
+        .all(db)
+        .await?;
+    pieces.sort_by_key(|piece| piece_ids.iter().position(|id| *id == piece.id));
+
+    Ok(pieces)
+}
+
+async fn get_parts(db: &DatabaseConnection, piece_id: i32) -> Result<Vec<parts::Model>, DbErr> {
+    parts::Entity::find()
+        .filter(parts::Column::PieceId.eq(piece_id))
+        .order_by_asc(parts::Column::Id)
+        .all(db)
+        .await
+}
+
+async fn get_assignments(
+    db: &DatabaseConnection,
+    setlist_id: i32,
+) -> Result<Vec<part_assignments::Model>, DbErr> {
+    part_assignments::Entity::find()
+        .filter(part_assignments::Column::SetlistId.eq(setlist_id))
+        .all(db)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+    use crate::services::{
+        parts as parts_service, pieces as pieces_service, players as players_service,
+        setlists as setlists_service,
+    };
+
+    #[tokio::test]
+    async fn test_sheets() {
+        let db = init().await.unwrap();
+
+        let setlist_id = setlists_service::add(&db, String::from("test assignments"))
+            .await
+            .unwrap();
+        let piece_id = pieces_service::add(
+            &db,
+            String::from("test assigned piece"),
+            None,
+            String::from("test path"),
+            None,
+            String::new(),
+        )
+        .await
+        .unwrap();
+        let part_id = parts_service::add(&db, String::from("Flute 1"), None, piece_id)
+            .await
+            .unwrap();
+        let player_id = players_service::add(&db, String::from("Jo Flautist"), None, None, None)
+            .await
+            .unwrap();
+
+        // only pieces on the setlist can be handed out
+        assert!(set(&db, setlist_id, part_id, vec![player_id])
+            .await
+            .is_err());
+
+        setlists_service::add_piece(&db, setlist_id, piece_id)
+            .await
+            .unwrap();
+        set(&db, setlist_id, part_id, vec![player_id])
+            .await
+            .unwrap();
+
+        let piece_sheet = get_piece_sheet(&db, setlist_id, piece_id).await.unwrap();
+        assert!(piece_sheet["text"]
+            .as_str()
+            .unwrap()
+            .contains("Flute 1: Jo Flautist"));
+
+        let player_sheet = get_player_sheet(&db, setlist_id, player_id).await.unwrap();
+        assert_eq!(player_sheet["pieces"][0]["parts"][0]["name"], "Flute 1");
+
+        // editing the part keeps who plays it
+        parts_service::update(&db, part_id, String::from("Flute I"), None)
+            .await
+            .unwrap();
+        let player_sheet = get_player_sheet(&db, setlist_id, player_id).await.unwrap();
+        assert_eq!(player_sheet["pieces"][0]["parts"][0]["name"], "Flute I");
+
+        setlists_service::delete(&db, setlist_id).await.unwrap();
+        players_service::delete(&db, player_id).await.unwrap();
+        pieces::Entity::delete_by_id(piece_id)
+            .exec(&db)
+            .await
+            .unwrap();
+
+        let _ = db.close().await;
+    }
+}
@@ -0,0 +1,226 @@
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, JoinType, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait,
+};
+
+use crate::entities::{
+    ensemble_parts_players, ensembles_parts, instruments, part_assignments, parts,
+    parts_instruments, pieces, pieces_setlists, setlists,
+};
+use crate::pdf;
+use crate::services::coverage;
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Who a binder is put together for.
+enum Target {
+    Instrument(i32),
+    /// An ensemble and one of its chairs.
+    Chair(i32, i32),
+}
+
+/// Merges the parts for one instrument, or one ensemble chair, across the setlist
+/// into a single PDF with a cover page and table of contents. A chair gets the
+/// parts handed out to its players for the setlist, or for pieces nobody was
+/// handed parts of yet, the parts its ensemble's coverage gives it.
+///
+/// Pieces without a matching part file are listed as `missing` and get no pages.
+pub async fn build(
+    db: &DatabaseConnection,
+    setlist_id: i32,
+    instrument_id: Option<i32>,
+    ensemble_part_id: Option<i32>,
+    destination: String,
+) -> Result<Value, DbErr> {
+    let setlist = setlists::Entity::find_by_id(setlist_id).one(db).await?;
+    let setlist = match setlist {
+        Some(setlist) => setlist,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Setlist with id {} not found",
+                setlist_id
+            )))
+        }
+    };
+
+    let (name, target) = match (instrument_id, ensemble_part_id) {
+        (Some(instrument_id), None) => {
+            let instrument = instruments::Entity::find_by_id(instrument_id)
+                .one(db)
+                .await?;
+            match instrument {
+                Some(instrument) => (instrument.name, Target::Instrument(instrument_id)),
+                None => {
+                    return Err(DbErr::RecordNotFound(format!(
+                        "Instrument with id {} not found",
+                        instrument_id
+                    )))
+                }
+            }
+        }
+        (None, Some(ensemble_part_id)) => {
+            let chair = ensembles_parts::Entity::find_by_id(ensemble_part_id)
+                .one(db)
+                .await?;
+            match chair {
+                Some(chair) => (chair.name, Target::Chair(chair.ensemble_id, chair.id)),
+                None => {
+                    return Err(DbErr::RecordNotFound(format!(
+                        "Ensemble part with id {} not found",
+                        ensemble_part_id
+                    )))
+                }
+            }
+        }
+        _ => {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(String::from(
+                "A binder is for either an instrument or an ensemble part",
+            ))))
+        }
+    };
+
+    let pieces = pieces::Entity::find()
+        .join_rev(JoinType::InnerJoin, pieces_setlists::Relation::Pieces.def())
+        .filter(pieces_setlists::Column::SetlistId.eq(setlist_id))
+        .order_by_asc(pieces_setlists::Column::Order)
+        .all(db)
+        .await?;
+    if pieces.is_empty() {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Setlist {} has no pieces",
+            setlist.name
+        ))));
+    }
+
+    let mut sections: Vec<(String, Vec<PathBuf>)> = vec![];
+    let mut piece_parts: Vec<Vec<parts::Model>> = vec![];
+
+    for piece in &pieces {
+        let parts: Vec<parts::Model> = get_parts(db, setlist_id, piece.id, &target)
+            .await?
+            .into_iter()
+            .filter(|part| part.path.as_deref().is_some_and(is_pdf))
+            .collect();
+
+        sections.push((
+            piece.title.clone(),
+            parts
+                .iter()
+                .filter_map(|part| part.path.as_ref().map(PathBuf::from))
+                .collect(),
+        ));
+        piece_parts.push(parts);
+    }
+
+    let first_pages = pdf::binder(&setlist.name, &name, &sections, &destination)
+        .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string())))?;
+
+    let mut included: Vec<Value> = vec![];
+    let mut missing: Vec<Value> = vec![];
+
+    for ((piece, parts), first_page) in pieces.iter().zip(piece_parts).zip(first_pages) {
+        match first_page {
+            Some(first_page) => included.push(serde_json::json!({
+                "id": piece.id,
+                "title": piece.title,
+                "first_page": first_page,
+                "parts": parts
+                    .iter()
+                    .map(|part| serde_json::json!({
+                        "id": part.id,
+                        "name": part.name,
+                    }))
+                    .collect::<Vec<Value>>(),
+            })),
+            None => missing.push(serde_json::json!({
+                "id": piece.id,
+                "title": piece.title,
+            })),
+        }
+    }
+
+    Ok(serde_json::json!({
+        "path": destination,
+        "name": name,
+        "pieces": included,
+        "missing": missing,
+    }))
+}
+
+/// Returns the parts of a piece for the instrument, or the parts the chair is
+/// given when the piece is handed out to its ensemble.
+async fn get_parts(
+    db: &DatabaseConnection,
+    setlist_id: i32,
+    piece_id: i32,
+    target: &Target,
+) -> Result<Vec<parts::Model>, DbErr> {
+    let parts = parts::Entity::find()
+        .filter(parts::Column::PieceId.eq(piece_id))
+        .order_by_asc(parts::Column::Id)
+        .all(db)
+        .await?;
+
+    let part_ids: Vec<i32> = match target {
+        Target::Instrument(instrument_id) => parts_instruments::Entity::find()
+            .filter(parts_instruments::Column::PartId.is_in(parts.iter().map(|part| part.id)))
+            .filter(parts_instruments::Column::InstrumentId.eq(*instrument_id))
+            .all(db)
+            .await?
+            .iter()
+            .map(|part_instrument| part_instrument.part_id)
+            .collect(),
+        Target::Chair(ensemble_id, chair_id) => {
+            let assignments = part_assignments::Entity::find()
+                .filter(part_assignments::Column::SetlistId.eq(setlist_id))
+                .filter(part_assignments::Column::PartId.is_in(parts.iter().map(|part| part.id)))
+                .all(db)
+                .await?;
+
+            match assignments.is_empty() {
+                // the parts the chair's players were handed win over coverage
+                false => {
+                    let seated: Vec<i32> = ensemble_parts_players::Entity::find()
+                        .filter(ensemble_parts_players::Column::PartId.eq(*chair_id))
+                        .all(db)
+                        .await?
+                        .iter()
+                        .map(|seat| seat.player_id)
+                        .collect();
+
+                    assignments
+                        .iter()
+                        .filter(|assignment| seated.contains(&assignment.player_id))
+                        .map(|assignment| assignment.part_id)
+                        .collect()
+                }
+                true => {
+                    let report = coverage::check(db, piece_id, *ensemble_id, &[]).await?;
+
+                    report["assignments"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter(|assignment| assignment["ensemble_part"]["id"] == *chair_id)
+                        .filter_map(|assignment| assignment["part"]["id"].as_i64())
+                        .map(|part_id| part_id as i32)
+                        .collect()
+                }
+            }
+        }
+    };
+
+    Ok(parts
+        .into_iter()
+        .filter(|part| part_ids.contains(&part.id))
+        .collect())
+}
+
+fn is_pdf(path: &str) -> bool {
+    let path = Path::new(path);
+    path.exists()
+        && path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"))
+}
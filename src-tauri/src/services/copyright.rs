@@ -0,0 +1,324 @@
+use sea_orm::{
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, JoinType, QueryFilter,
+    QuerySelect, RelationTrait,
+};
+
+use crate::entities::{musicians, pieces, pieces_musicians};
+
+use chrono::Datelike;
+use serde_json::Value;
+
+/// Rules for working out when a work enters the public domain.
+/// `life_plus_50` and `life_plus_70` count from the end of the year the last creator died,
+/// `us` follows the United States publication-date rules.
+pub const COPYRIGHT_RULES: [&str; 3] = ["life_plus_50", "life_plus_70", "us"];
+
+pub const PUBLIC_DOMAIN: &str = "public_domain";
+pub const PROTECTED: &str = "protected";
+pub const UNKNOWN: &str = "unknown";
+
+/// Roles whose rights decide whether a piece is still protected.
+const CREATOR_ROLES: [&str; 3] = ["composer", "arranger", "lyricist"];
+
+/// Works published in the US before 1978 are protected for 95 years from publication.
+const US_PUBLICATION_TERM: i32 = 95;
+const US_LIFE_TERM_FROM: i32 = 1978;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Creator {
+    pub name: String,
+    pub death_year: Option<i32>,
+    pub is_anonymous: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyrightStatus {
+    pub status: &'static str,
+    pub reason: String,
+}
+
+/// Works out the copyright status of a piece under the rule and stores it with the piece.
+pub async fn evaluate_piece(
+    db: &DatabaseConnection,
+    piece_id: i32,
+    rule: &str,
+) -> Result<Value, DbErr> {
+    check_rule(rule)?;
+
+    let piece = pieces::Entity::find_by_id(piece_id).one(db).await?;
+    let piece = match piece {
+        Some(piece) => piece,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Piece with id {} not found",
+                piece_id
+            )))
+        }
+    };
+
+    let creators = get_creators(db, piece_id).await?;
+    let current_year = chrono::Local::now().year();
+    let copyright = evaluate(rule, current_year, piece.year_published, &creators);
+
+    let mut piece: pieces::ActiveModel = piece.into();
+    piece.copyright_status = ActiveValue::Set(Some(copyright.status.to_string()));
+    piece.copyright_reason = ActiveValue::Set(Some(copyright.reason.clone()));
+    piece.copyright_rule = ActiveValue::Set(Some(rule.to_string()));
+    pieces::Entity::update(piece).exec(db).await?;
+
+    Ok(serde_json::json!({
+        "piece_id": piece_id,
+        "status": copyright.status,
+        "reason": copyright.reason,
+        "rule": rule,
+    }))
+}
+
+/// Re-evaluates every piece, e.g. after the rule changed or death years were filled in.
+/// Returns the number of pieces evaluated.
+pub async fn evaluate_all(db: &DatabaseConnection, rule: &str) -> Result<usize, DbErr> {
+    check_rule(rule)?;
+
+    let pieces = pieces::Entity::find().all(db).await?;
+    for piece in &pieces {
+        evaluate_piece(db, piece.id, rule).await?;
+    }
+
+    Ok(pieces.len())
+}
+
+/// Decides whether a work is in the public domain in `current_year`.
+pub fn evaluate(
+    rule: &str,
+    current_year: i32,
+    year_published: Option<i32>,
+    creators: &[Creator],
+) -> CopyrightStatus {
+    match rule {
+        "life_plus_50" => life_plus(50, 50, current_year, year_published, creators),
+        "life_plus_70" => life_plus(70, 70, current_year, year_published, creators),
+        "us" => match year_published {
+            None => status(UNKNOWN, String::from("The publication year is unknown")),
+            Some(year) if year + US_PUBLICATION_TERM < current_year => status(
+                PUBLIC_DOMAIN,
+                format!(
+                    "Published in {}, protection ended at the end of {}",
+                    year,
+                    year + US_PUBLICATION_TERM
+                ),
+            ),
+            Some(year) if year < US_LIFE_TERM_FROM => status(
+                PROTECTED,
+                format!(
+                    "Published in {}, protected until the end of {}",
+                    year,
+                    year + US_PUBLICATION_TERM
+                ),
+            ),
+            Some(_) => life_plus(
+                70,
+                US_PUBLICATION_TERM,
+                current_year,
+                year_published,
+                creators,
+            ),
+        },
+        _ => status(UNKNOWN, format!("Unknown copyright rule {}", rule)),
+    }
+}
+
+/// Protects a work for `term` years after the last named creator died. Works without
+/// a named creator are protected for `anonymous_term` years after publication.
+fn life_plus(
+    term: i32,
+    anonymous_term: i32,
+    current_year: i32,
+    year_published: Option<i32>,
+    creators: &[Creator],
+) -> CopyrightStatus {
+    let named: Vec<&Creator> = creators
+        .iter()
+        .filter(|creator| !creator.is_anonymous)
+        .collect();
+
+    if named.is_empty() {
+        return match year_published {
+            Some(year) if year + anonymous_term < current_year => status(
+                PUBLIC_DOMAIN,
+                format!(
+                    "Anonymous work published in {}, protection ended at the end of {}",
+                    year,
+                    year + anonymous_term
+                ),
+            ),
+            Some(year) => status(
+                PROTECTED,
+                format!(
+                    "Anonymous work published in {}, protected until the end of {}",
+                    year,
+                    year + anonymous_term
+                ),
+            ),
+            None => status(
+                UNKNOWN,
+                String::from("No named creator and the publication year is unknown"),
+            ),
+        };
+    }
+
+    let last_death = named
+        .iter()
+        .filter_map(|creator| creator.death_year.map(|death_year| (creator, death_year)))
+        .max_by_key(|(_, death_year)| *death_year);
+
+    if let Some((creator, death_year)) = last_death {
+        if death_year + term >= current_year {
+            return status(
+                PROTECTED,
+                format!(
+                    "{} died in {}, protected until the end of {}",
+                    creator.name,
+                    death_year,
+                    death_year + term
+                ),
+            );
+        }
+    }
+
+    let unknown: Vec<&str> = named
+        .iter()
+        .filter(|creator| creator.death_year.is_none())
+        .map(|creator| creator.name.as_str())
+        .collect();
+    if !unknown.is_empty() {
+        return status(
+            UNKNOWN,
+            format!("Death year unknown for {}", unknown.join(", ")),
+        );
+    }
+
+    match last_death {
+        Some((creator, death_year)) => status(
+            PUBLIC_DOMAIN,
+            format!(
+                "{} died in {}, protection ended at the end of {}",
+                creator.name,
+                death_year,
+                death_year + term
+            ),
+        ),
+        None => status(UNKNOWN, String::from("No death years known")),
+    }
+}
+
+fn status(status: &'static str, reason: String) -> CopyrightStatus {
+    CopyrightStatus { status, reason }
+}
+
+fn check_rule(rule: &str) -> Result<(), DbErr> {
+    if !COPYRIGHT_RULES.contains(&rule) {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Copyright rule {} is not one of {}",
+            rule,
+            COPYRIGHT_RULES.join(", ")
+        ))));
+    }
+
+    Ok(())
+}
+
+async fn get_creators(db: &DatabaseConnection, piece_id: i32) -> Result<Vec<Creator>, DbErr> {
+    let musicians = musicians::Entity::find()
+        .join_rev(
+            JoinType::InnerJoin,
+            pieces_musicians::Relation::Musicians.def(),
+        )
+        .filter(pieces_musicians::Column::PieceId.eq(piece_id))
+        .filter(pieces_musicians::Column::Role.is_in(CREATOR_ROLES))
+        .distinct()
+        .all(db)
+        .await?;
+
+    Ok(musicians
+        .into_iter()
+        .map(|musician| Creator {
+            name: crate::services::musicians::display_name(&musician),
+            death_year: musician.death_year,
+            is_anonymous: musician.is_anonymous,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn creator(name: &str, death_year: Option<i32>) -> Creator {
+        Creator {
+            name: String::from(name),
+            death_year,
+            is_anonymous: false,
+        }
+    }
+
+    #[test]
+    fn test_life_plus() {
+        let holst = [creator("Gustav Holst", Some(1934))];
+        assert_eq!(
+            evaluate("life_plus_70", 2026, Some(1921), &holst).status,
+            PUBLIC_DOMAIN
+        );
+
+        // the arranger's rights outlive the composer's
+        let arranged = [
+            creator("Gustav Holst", Some(1934)),
+            creator("Arranger", Some(1990)),
+        ];
+        let copyright = evaluate("life_plus_70", 2026, Some(1960), &arranged);
+        assert_eq!(copyright.status, PROTECTED);
+        assert!(copyright.reason.contains("2060"));
+
+        let living = [
+            creator("Gustav Holst", Some(1934)),
+            creator("Lyricist", None),
+        ];
+        assert_eq!(
+            evaluate("life_plus_50", 2026, None, &living).status,
+            UNKNOWN
+        );
+
+        let vaughan_williams = [creator("Ralph Vaughan Williams", Some(1958))];
+        assert_eq!(
+            evaluate("life_plus_50", 2026, None, &vaughan_williams).status,
+            PUBLIC_DOMAIN
+        );
+        assert_eq!(
+            evaluate("life_plus_70", 2026, None, &vaughan_williams).status,
+            PROTECTED
+        );
+    }
+
+    #[test]
+    fn test_us() {
+        let gershwin = [creator("George Gershwin", Some(1937))];
+        assert_eq!(
+            evaluate("us", 2026, Some(1924), &gershwin).status,
+            PUBLIC_DOMAIN
+        );
+        assert_eq!(
+            evaluate("us", 2026, Some(1935), &gershwin).status,
+            PROTECTED
+        );
+        assert_eq!(evaluate("us", 2026, None, &gershwin).status, UNKNOWN);
+
+        let anonymous = [Creator {
+            name: String::from("Anonymous"),
+            death_year: None,
+            is_anonymous: true,
+        }];
+        assert_eq!(
+            evaluate("us", 2026, Some(1990), &anonymous).status,
+            PROTECTED
+        );
+    }
+}
@@ -0,0 +1,543 @@
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+
+use crate::entities::{
+    ensemble_parts_instruments, ensembles, ensembles_parts, instruments, parts, parts_instruments,
+    pieces,
+};
+use crate::services::instruments::transpose_by;
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Lets parts written for one instrument be played on another outside its family,
+/// e.g. Alto Horn parts on Horn in F.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Substitution {
+    pub instrument_id: i32,
+    pub substitute_id: i32,
+}
+
+/// A piece part or an ensemble chair, with the instruments it calls for.
+struct Slot {
+    id: i32,
+    name: String,
+    instruments: Vec<instruments::Model>,
+}
+
+/// How a chair plays one of a part's instruments.
+struct Cover<'a> {
+    needed: &'a instruments::Model,
+    played_on: &'a instruments::Model,
+    transpose_by: Option<i32>,
+}
+
+/// Compares a piece's parts against an ensemble's chairs.
+///
+/// `missing` lists chairs the piece has no part for, `extra` lists parts no
+/// chair can play, and `doubling` lists parts that need more than one instrument.
+pub async fn check(
+    db: &DatabaseConnection,
+    piece_id: i32,
+    ensemble_id: i32,
+    substitutions: &[Substitution],
+) -> Result<Value, DbErr> {
+    let piece = pieces::Entity::find_by_id(piece_id).one(db).await?;
+    if piece.is_none() {
+        return Err(DbErr::RecordNotFound(format!(
+            "Piece with id {} not found",
+            piece_id
+        )));
+    }
+
+    let ensemble = ensembles::Entity::find_by_id(ensemble_id).one(db).await?;
+    if ensemble.is_none() {
+        return Err(DbErr::RecordNotFound(format!(
+            "Ensemble with id {} not found",
+            ensemble_id
+        )));
+    }
+
+    let all_instruments = instruments::Entity::find().all(db).await?;
+    let parts = parts::Entity::find()
+        .filter(parts::Column::PieceId.eq(piece_id))
+        .all(db)
+        .await?;
+    let parts = get_part_slots(db, parts, &all_instruments).await?;
+    let chairs = get_ensemble_slots(db, ensemble_id, &all_instruments).await?;
+
+    let mut report = report(&parts, &chairs, substitutions);
+    report["piece_id"] = Value::from(piece_id);
+    report["ensemble_id"] = Value::from(ensemble_id);
+
+    Ok(report)
+}
+
+/// Ranks every piece by how much of it the ensemble can play, best first.
+/// Pieces whose parts have no instruments set can't be judged and are left out.
+pub async fn get_playable(
+    db: &DatabaseConnection,
+    ensemble_id: i32,
+    substitutions: &[Substitution],
+    min_coverage: f64,
+) -> Result<Vec<Value>, DbErr> {
+    let ensemble = ensembles::Entity::find_by_id(ensemble_id).one(db).await?;
+    if ensemble.is_none() {
+        return Err(DbErr::RecordNotFound(format!(
+            "Ensemble with id {} not found",
+            ensemble_id
+        )));
+    }
+
+    let all_instruments = instruments::Entity::find().all(db).await?;
+    let chairs = get_ensemble_slots(db, ensemble_id, &all_instruments).await?;
+
+    let pieces = pieces::Entity::find().all(db).await?;
+    let all_parts = parts::Entity::find().all(db).await?;
+    let piece_ids: Vec<i32> = all_parts.iter().map(|part| part.piece_id).collect();
+    let all_parts = get_part_slots(db, all_parts, &all_instruments).await?;
+
+    let mut parts_by_piece: HashMap<i32, Vec<Slot>> = HashMap::new();
+    for (part, piece_id) in all_parts.into_iter().zip(piece_ids) {
+        parts_by_piece.entry(piece_id).or_default().push(part);
+    }
+
+    let mut playable: Vec<(f64, Value)> = vec![];
+
+    for piece in pieces {
+        let parts = match parts_by_piece.get(&piece.id) {
+            Some(parts) if parts.iter().any(|part| !part.instruments.is_empty()) => parts,
+            _ => continue,
+        };
+
+        let report = report(parts, &chairs, substitutions);
+
+        let coverage = report["coverage"].as_f64().unwrap_or(0.0);
+        if coverage < min_coverage {
+            continue;
+        }
+
+        // instruments no chair can play at all, as opposed to running out of chairs
+        let mut missing_instruments: Vec<String> = vec![];
+        for part in parts {
+            for needed in &part.instruments {
+                let available = chairs.iter().any(|chair| {
+                    chair
+                        .instruments
+                        .iter()
+                        .any(|played_on| can_play(needed, played_on, substitutions).is_some())
+                });
+                if !available && !missing_instruments.contains(&needed.name) {
+                    missing_instruments.push(needed.name.clone());
+                }
+            }
+        }
+
+        let substituted_parts = report["assignments"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|assignment| {
+                assignment["substitutions"]
+                    .as_array()
+                    .is_some_and(|substitutions| !substitutions.is_empty())
+            })
+            .count();
+
+        playable.push((
+            coverage,
+            serde_json::json!({
+                "piece": {
+                    "id": piece.id,
+                    "title": piece.title,
+                },
+                "coverage": coverage,
+                "covered": report["covered"],
+                "instruments_covered": missing_instruments.is_empty(),
+                "missing_instruments": missing_instruments,
+                "extra": report["extra"],
+                "substituted_parts": substituted_parts,
+            }),
+        ));
+    }
+
+    playable.sort_by(|(a, a_piece), (b, b_piece)| {
+        b.total_cmp(a).then_with(|| {
+            let a_title = a_piece["piece"]["title"].as_str().unwrap_or_default();
+            let b_title = b_piece["piece"]["title"].as_str().unwrap_or_default();
+            a_title.to_lowercase().cmp(&b_title.to_lowercase())
+        })
+    });
+
+    Ok(playable.into_iter().map(|(_, piece)| piece).collect())
+}
+
+async fn get_part_slots(
+    db: &DatabaseConnection,
+    parts: Vec<parts::Model>,
+    all_instruments: &[instruments::Model],
+) -> Result<Vec<Slot>, DbErr> {
+    let parts_instruments = parts_instruments::Entity::find()
+        .filter(parts_instruments::Column::PartId.is_in(parts.iter().map(|part| part.id)))
+        .all(db)
+        .await?;
+
+    let slots = parts
+        .into_iter()
+        .map(|part| Slot {
+            id: part.id,
+            name: part.name,
+            instruments: parts_instruments
+                .iter()
+                .filter(|part_instrument| part_instrument.part_id == part.id)
+                .filter_map(|part_instrument| {
+                    all_instruments
+                        .iter()
+                        .find(|instrument| instrument.id == part_instrument.instrument_id)
+                        .cloned()
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(slots)
+}
+
+async fn get_ensemble_slots(
+    db: &DatabaseConnection,
+    ensemble_id: i32,
+    all_instruments: &[instruments::Model],
+) -> Result<Vec<Slot>, DbErr> {
+    let chairs = ensembles_parts::Entity::find()
+        .filter(ensembles_parts::Column::EnsembleId.eq(ensemble_id))
+        .all(db)
+        .await?;
+
+    let chairs_instruments = ensemble_parts_instruments::Entity::find()
+        .filter(
+            ensemble_parts_instruments::Column::PartId.is_in(chairs.iter().map(|chair| chair.id)),
+        )
+        .all(db)
+        .await?;
+
+    let slots = chairs
+        .into_iter()
+        .map(|chair| Slot {
+            id: chair.id,
+            name: chair.name,
+            instruments: chairs_instruments
+                .iter()
+                .filter(|chair_instrument| chair_instrument.part_id == chair.id)
+                .filter_map(|chair_instrument| {
+                    all_instruments
+                        .iter()
+                        .find(|instrument| instrument.id == chair_instrument.instrument_id)
+                        .cloned()
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(slots)
+}
+
+fn report(parts: &[Slot], chairs: &[Slot], substitutions: &[Substitution]) -> Value {
+    let assignments = assign(parts, chairs, substitutions);
+
+    let mut assigned: Vec<Value> = vec![];
+    let mut extra: Vec<Value> = vec![];
+    let mut unchecked: Vec<Value> = vec![];
+    let mut doubling: Vec<Value> = vec![];
+
+    for (index, part) in parts.iter().enumerate() {
+        if part.instruments.len() > 1 {
+            doubling.push(serde_json::json!({
+                "part": slot_json(part),
+                "instruments": part
+                    .instruments
+                    .iter()
+                    .map(|instrument| instrument.name.clone())
+                    .collect::<Vec<String>>(),
+            }));
+        }
+
+        if part.instruments.is_empty() {
+            unchecked.push(slot_json(part));
+            continue;
+        }
+
+        match assignments[index] {
+            Some(chair_index) => {
+                let chair = &chairs[chair_index];
+                let substituted: Vec<Value> = cover(part, chair, substitutions)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter(|cover| cover.needed.id != cover.played_on.id)
+                    .map(|cover| {
+                        serde_json::json!({
+                            "instrument": cover.needed.name,
+                            "played_on": cover.played_on.name,
+                            "transpose_by": cover.transpose_by,
+                        })
+                    })
+                    .collect();
+
+                assigned.push(serde_json::json!({
+                    "part": slot_json(part),
+                    "ensemble_part": slot_json(chair),
+                    "substitutions": substituted,
+                }));
+            }
+            None => extra.push(slot_json(part)),
+        }
+    }
+
+    let missing: Vec<Value> = chairs
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !assignments.contains(&Some(*index)))
+        .map(|(_, chair)| slot_json(chair))
+        .collect();
+
+    let checked = parts.len() - unchecked.len();
+    let coverage = if checked == 0 {
+        0.0
+    } else {
+        assigned.len() as f64 / checked as f64
+    };
+
+    serde_json::json!({
+        "covered": checked > 0 && extra.is_empty(),
+        "coverage": coverage,
+        "assignments": assigned,
+        "missing": missing,
+        "extra": extra,
+        "unchecked": unchecked,
+        "doubling": doubling,
+    })
+}
+
+/// Matches every part to a different chair, as many as possible, preferring
+/// chairs that play the written instruments over ones that need to transpose.
+fn assign(parts: &[Slot], chairs: &[Slot], substitutions: &[Substitution]) -> Vec<Option<usize>> {
+    let candidates: Vec<Vec<usize>> = parts
+        .iter()
+        .map(|part| {
+            let mut candidates: Vec<(bool, usize)> = chairs
+                .iter()
+                .enumerate()
+                .filter_map(|(index, chair)| {
+                    cover(part, chair, substitutions).map(|covers| {
+                        let substitutes = covers
+                            .iter()
+                            .any(|cover| cover.needed.id != cover.played_on.id);
+                        (substitutes, index)
+                    })
+                })
+                .collect();
+            candidates.sort();
+            candidates.into_iter().map(|(_, index)| index).collect()
+        })
+        .collect();
+
+    let mut owners: Vec<Option<usize>> = vec![None; chairs.len()];
+    for part_index in 0..parts.len() {
+        let mut visited = vec![false; chairs.len()];
+        try_assign(part_index, &candidates, &mut owners, &mut visited);
+    }
+
+    let mut assignments = vec![None; parts.len()];
+    for (chair_index, owner) in owners.iter().enumerate() {
+        if let Some(part_index) = owner {
+            assignments[*part_index] = Some(chair_index);
+        }
+    }
+
+    assignments
+}
+
+fn try_assign(
+    part_index: usize,
+    candidates: &[Vec<usize>],
+    owners: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    for &chair_index in &candidates[part_index] {
+        if visited[chair_index] {
+            continue;
+        }
+        visited[chair_index] = true;
+
+        let free = match owners[chair_index] {
+            Some(other_part_index) => try_assign(other_part_index, candidates, owners, visited),
+            None => true,
+        };
+        if free {
+            owners[chair_index] = Some(part_index);
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Returns how the chair plays each of the part's instruments, or `None` if it can't.
+fn cover<'a>(
+    part: &'a Slot,
+    chair: &'a Slot,
+    substitutions: &[Substitution],
+) -> Option<Vec<Cover<'a>>> {
+    if part.instruments.is_empty() {
+        return None;
+    }
+
+    part.instruments
+        .iter()
+        .map(|needed| {
+            chair
+                .instruments
+                .iter()
+                .filter_map(|played_on| {
+                    can_play(needed, played_on, substitutions).map(|transpose_by| Cover {
+                        needed,
+                        played_on,
+                        transpose_by,
+                    })
+                })
+                .min_by_key(|cover| {
+                    (
+                        cover.played_on.id != needed.id,
+                        cover.needed.family != cover.played_on.family,
+                        cover.transpose_by.map_or(i32::MAX, i32::abs),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Returns `Some` with the interval to transpose by, if known, when a part for
+/// `needed` can be played on `played_on`.
+fn can_play(
+    needed: &instruments::Model,
+    played_on: &instruments::Model,
+    substitutions: &[Substitution],
+) -> Option<Option<i32>> {
+    if let Some(transpose_by) = transpose_by(needed, played_on) {
+        return Some(Some(transpose_by));
+    }
+
+    substitutions
+        .iter()
+        .any(|substitution| {
+            substitution.instrument_id == needed.id && substitution.substitute_id == played_on.id
+        })
+        .then(|| {
+            needed
+                .transposition
+                .zip(played_on.transposition)
+                .map(|(needed, played_on)| needed - played_on)
+        })
+}
+
+fn slot_json(slot: &Slot) -> Value {
+    serde_json::json!({
+        "id": slot.id,
+        "name": slot.name,
+        "instruments": slot
+            .instruments
+            .iter()
+            .map(|instrument| instrument.name.clone())
+            .collect::<Vec<String>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instrument(id: i32, name: &str, family: &str, transposition: i32) -> instruments::Model {
+        instruments::Model {
+            id,
+            name: String::from(name),
+            category: None,
+            is_default: true,
+            transposition: Some(transposition),
+            clefs: None,
+            range_low: None,
+            range_high: None,
+            family: Some(String::from(family)),
+            score_order: Some(id),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn slot(id: i32, name: &str, instruments: &[&instruments::Model]) -> Slot {
+        Slot {
+            id,
+            name: String::from(name),
+            instruments: instruments
+                .iter()
+                .map(|instrument| (*instrument).clone())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let flute = instrument(1, "Flute", "Flute", 0);
+        let piccolo = instrument(2, "Piccolo", "Flute", 12);
+        let trumpet_in_c = instrument(3, "Trumpet in C", "Trumpet", 0);
+        let trumpet_in_bb = instrument(4, "Trumpet in Bb", "Trumpet", -2);
+        let tuba = instrument(5, "Tuba", "Tuba", 0);
+
+        let parts = vec![
+            slot(1, "Flute/Piccolo", &[&flute, &piccolo]),
+            slot(2, "Trumpet in C", &[&trumpet_in_c]),
+            slot(3, "Tuba", &[&tuba]),
+            slot(4, "Cues", &[]),
+        ];
+        let chairs = vec![
+            slot(1, "Flute", &[&flute]),
+            slot(2, "Trumpet 1", &[&trumpet_in_bb]),
+            slot(3, "Trumpet 2", &[&trumpet_in_bb]),
+        ];
+
+        let report = report(&parts, &chairs, &[]);
+
+        // the flute chair can pick up the piccolo double, the tuba part has nowhere to go
+        assert_eq!(report["covered"], false);
+        assert_eq!(report["assignments"].as_array().unwrap().len(), 2);
+        assert_eq!(report["extra"][0]["name"], "Tuba");
+        assert_eq!(report["missing"][0]["name"], "Trumpet 2");
+        assert_eq!(report["unchecked"][0]["name"], "Cues");
+        assert_eq!(report["doubling"][0]["part"]["name"], "Flute/Piccolo");
+
+        let trumpet = &report["assignments"][1];
+        assert_eq!(trumpet["ensemble_part"]["name"], "Trumpet 1");
+        assert_eq!(trumpet["substitutions"][0]["transpose_by"], 2);
+    }
+
+    #[test]
+    fn test_listed_substitutions() {
+        let alto_horn = instrument(1, "Alto Horn", "Alto Horn", -9);
+        let horn_in_f = instrument(2, "Horn in F", "Horn", -7);
+
+        let parts = vec![slot(1, "Alto Horn", &[&alto_horn])];
+        let chairs = vec![slot(1, "Horn 1", &[&horn_in_f])];
+
+        let report_without = report(&parts, &chairs, &[]);
+        assert_eq!(report_without["coverage"], 0.0);
+
+        let substitutions = vec![Substitution {
+            instrument_id: alto_horn.id,
+            substitute_id: horn_in_f.id,
+        }];
+        let report_with = report(&parts, &chairs, &substitutions);
+        assert_eq!(report_with["covered"], true);
+        assert_eq!(
+            report_with["assignments"][0]["substitutions"][0]["transpose_by"],
+            -2
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+
+use crate::entities::{parts, pieces, pieces_musicians, scores};
+use crate::utils;
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Score above which adding a piece is refused unless forced.
+pub const LIKELY_DUPLICATE_SCORE: f64 = 0.65;
+
+const TITLE_WEIGHT: f64 = 0.4;
+const COMPOSERS_WEIGHT: f64 = 0.3;
+const ARRANGERS_WEIGHT: f64 = 0.1;
+const ARRANGERS_MISMATCH_PENALTY: f64 = 0.2;
+const CATALOGUE_NUMBER_WEIGHT: f64 = 0.2;
+
+/// A piece as seen by duplicate detection, stored or not yet created.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Candidate {
+    pub title: String,
+    #[serde(default)]
+    pub composer_ids: Vec<i32>,
+    #[serde(default)]
+    pub arranger_ids: Vec<i32>,
+    pub catalogue_number: Option<String>,
+    #[serde(default)]
+    pub file_paths: Vec<String>,
+}
+
+struct Fingerprint {
+    id: i32,
+    title: String,
+    normalized_title: String,
+    composer_ids: HashSet<i32>,
+    arranger_ids: HashSet<i32>,
+    catalogue_number: Option<String>,
+    file_hashes: HashSet<String>,
+}
+
+/// Returns stored pieces that resemble the candidate, best match first.
+pub async fn find_candidates(
+    db: &DatabaseConnection,
+    candidate: &Candidate,
+    min_score: f64,
+) -> Result<Vec<Value>, DbErr> {
+    let candidate = Fingerprint {
+        id: 0,
+        title: candidate.title.clone(),
+        normalized_title: normalize_title(&candidate.title),
+        composer_ids: candidate.composer_ids.iter().copied().collect(),
+        arranger_ids: candidate.arranger_ids.iter().copied().collect(),
+        catalogue_number: candidate
+            .catalogue_number
+            .as_deref()
+            .and_then(normalize_catalogue_number),
+        file_hashes: candidate
+            .file_paths
+            .iter()
+            .filter_map(|path| utils::sha256_file(path).ok())
+            .collect(),
+    };
+
+    let fingerprints = get_fingerprints(db).await?;
+
+    let mut matches: Vec<(f64, Vec<&str>, &Fingerprint)> = fingerprints
+        .iter()
+        .map(|fingerprint| {
+            let (score, reasons) = compare(&candidate, fingerprint);
+            (score, reasons, fingerprint)
+        })
+        .filter(|(score, _, _)| *score >= min_score)
+        .collect();
+
+    matches.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let matches = matches
+        .into_iter()
+        .map(|(score, reasons, fingerprint)| {
+            serde_json::json!({
+                "id": fingerprint.id,
+                "title": fingerprint.title,
+                "score": score,
+                "reasons": reasons,
+            })
+        })
+        .collect();
+
+    Ok(matches)
+}
+
+/// Fails when the candidate is likely a duplicate of a stored piece.
+pub async fn check(db: &DatabaseConnection, candidate: &Candidate) -> Result<(), DbErr> {
+    let matches = find_candidates(db, candidate, LIKELY_DUPLICATE_SCORE).await?;
+
+    if matches.is_empty() {
+        return Ok(());
+    }
+
+    let titles: Vec<String> = matches
+        .iter()
+        .map(|piece| {
+            format!(
+                "\"{}\" (id {})",
+                piece["title"].as_str().unwrap(),
+                piece["id"]
+            )
+        })
+        .collect();
+
+    Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+        "Piece \"{}\" looks like a duplicate of {}",
+        candidate.title,
+        titles.join(", ")
+    ))))
+}
+
+/// Groups the library into clusters of pieces that look like duplicates of each other.
+pub async fn find_clusters(db: &DatabaseConnection, min_score: f64) -> Result<Vec<Value>, DbErr> {
+    let fingerprints = get_fingerprints(db).await?;
+
+    // union-find over every pair scoring at least `min_score`
+    let mut roots: Vec<usize> = (0..fingerprints.len()).collect();
+    fn root(roots: &mut [usize], index: usize) -> usize {
+        let mut index = index;
+        while roots[index] != index {
+            roots[index] = roots[roots[index]];
+            index = roots[index];
+        }
+        index
+    }
+
+    let mut pairs: Vec<(usize, usize, f64, Vec<&str>)> = vec![];
+    for a in 0..fingerprints.len() {
+        for b in (a + 1)..fingerprints.len() {
+            let (score, reasons) = compare(&fingerprints[a], &fingerprints[b]);
+            if score >= min_score {
+                let (root_a, root_b) = (root(&mut roots, a), root(&mut roots, b));
+                roots[root_b] = root_a;
+                pairs.push((a, b, score, reasons));
+            }
+        }
+    }
+
+    let mut clusters: HashMap<usize, Vec<(usize, usize, f64, Vec<&str>)>> = HashMap::new();
+    for pair in pairs {
+        let cluster_root = root(&mut roots, pair.0);
+        clusters.entry(cluster_root).or_default().push(pair);
+    }
+
+    let mut clusters: Vec<Value> = clusters
+        .into_values()
+        .map(|pairs| {
+            let mut members: Vec<usize> = pairs.iter().flat_map(|pair| [pair.0, pair.1]).collect();
+            members.sort();
+            members.dedup();
+
+            let score = pairs.iter().map(|pair| pair.2).fold(0.0, f64::max);
+
+            let pieces: Vec<Value> = members
+                .iter()
+                .map(|index| {
+                    serde_json::json!({
+                        "id": fingerprints[*index].id,
+                        "title": fingerprints[*index].title,
+                    })
+                })
+                .collect();
+
+            let pairs: Vec<Value> = pairs
+                .iter()
+                .map(|(a, b, score, reasons)| {
+                    serde_json::json!({
+                        "piece_ids": [fingerprints[*a].id, fingerprints[*b].id],
+                        "score": score,
+                        "reasons": reasons,
+                    })
+                })
+                .collect();
+
+            serde_json::json!({
+                "score": score,
+                "pieces": pieces,
+                "pairs": pairs,
+            })
+        })
+        .collect();
+
+    clusters.sort_by(|a, b| {
+        let a = a["score"].as_f64().unwrap();
+        let b = b["score"].as_f64().unwrap();
+        b.total_cmp(&a)
+    });
+
+    Ok(clusters)
+}
+
+async fn get_fingerprints(db: &DatabaseConnection) -> Result<Vec<Fingerprint>, DbErr> {
+    let pieces = pieces::Entity::find().all(db).await?;
+    let credits = pieces_musicians::Entity::find()
+        .filter(
+            Condition::any()
+                .add(pieces_musicians::Column::Role.eq("composer"))
+                .add(pieces_musicians::Column::Role.eq("arranger")),
+        )
+        .all(db)
+        .await?;
+    let scores = scores::Entity::find().all(db).await?;
+    let parts = parts::Entity::find().all(db).await?;
+
+    let mut fingerprints: Vec<Fingerprint> = vec![];
+
+    for piece in pieces {
+        let musician_ids = |role: &str| -> HashSet<i32> {
+            credits
+                .iter()
+                .filter(|credit| credit.piece_id == piece.id && credit.role == role)
+                .map(|credit| credit.musician_id)
+                .collect()
+        };
+
+        // files attached before hashes were stored are hashed once at startup
+        let file_hashes = scores
+            .iter()
+            .filter(|score| score.piece_id == piece.id)
+            .map(|score| &score.sha256)
+            .chain(
+                parts
+                    .iter()
+                    .filter(|part| part.piece_id == piece.id)
+                    .map(|part| &part.sha256),
+            )
+            .filter_map(|sha256| sha256.clone());
+
+        fingerprints.push(Fingerprint {
+            id: piece.id,
+            normalized_title: normalize_title(&piece.title),
+            composer_ids: musician_ids("composer"),
+            arranger_ids: musician_ids("arranger"),
+            catalogue_number: piece
+                .catalogue_number
+                .as_deref()
+                .and_then(normalize_catalogue_number),
+            file_hashes: file_hashes.collect(),
+            title: piece.title,
+        });
+    }
+
+    Ok(fingerprints)
+}
+
+fn compare(a: &Fingerprint, b: &Fingerprint) -> (f64, Vec<&'static str>) {
+    // an identical attached file settles it
+    if !a.file_hashes.is_disjoint(&b.file_hashes) {
+        return (1.0, vec!["identical file"]);
+    }
+
+    let mut score = 0.0;
+    let mut reasons = vec![];
+
+    let title_similarity = similarity(&a.normalized_title, &b.normalized_title);
+    score += TITLE_WEIGHT * title_similarity;
+    if title_similarity == 1.0 {
+        reasons.push("same title");
+    } else if title_similarity >= 0.8 {
+        reasons.push("similar title");
+    }
+
+    if !a.composer_ids.is_empty() && !b.composer_ids.is_empty() {
+        let overlap = jaccard(&a.composer_ids, &b.composer_ids);
+        score += COMPOSERS_WEIGHT * overlap;
+        if overlap > 0.0 {
+            reasons.push("same composer");
+        }
+    }
+
+    // different arrangers usually mean a different arrangement of the same work
+    if !a.arranger_ids.is_empty() && !b.arranger_ids.is_empty() {
+        let overlap = jaccard(&a.arranger_ids, &b.arranger_ids);
+        if overlap > 0.0 {
+            score += ARRANGERS_WEIGHT * overlap;
+            reasons.push("same arranger");
+        } else {
+            score -= ARRANGERS_MISMATCH_PENALTY;
+        }
+    }
+
+    if let (Some(a), Some(b)) = (&a.catalogue_number, &b.catalogue_number) {
+        if a == b {
+            score += CATALOGUE_NUMBER_WEIGHT;
+            reasons.push("same catalogue number");
+        }
+    }
+
+    (score.max(0.0), reasons)
+}
+
+/// Lowercases the title and drops punctuation and leading articles.
+fn normalize_title(title: &str) -> String {
+    let cleaned: String = title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+
+    let mut words: Vec<&str> = cleaned.split_whitespace().collect();
+    if words.len() > 1 && ["the", "a", "an"].contains(&words[0]) {
+        words.remove(0);
+    }
+
+    words.join(" ")
+}
+
+fn normalize_catalogue_number(catalogue_number: &str) -> Option<String> {
+    let normalized: String = catalogue_number
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect();
+
+    if normalized.is_empty() {
+        None
+    } else {
+        Some(normalized)
+    }
+}
+
+/// Levenshtein distance scaled to 0.0 (nothing alike) ..= 1.0 (equal).
+fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+
+    if longest == 0 {
+        return 0.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+fn jaccard(a: &HashSet<i32>, b: &HashSet<i32>) -> f64 {
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(
+            normalize_title("The Stars and Stripes Forever!"),
+            "stars and stripes forever"
+        );
+        assert_eq!(normalize_title("  Sleigh   Ride "), "sleigh ride");
+        assert_eq!(normalize_title("A"), "a");
+    }
+
+    #[test]
+    fn test_compare() {
+        let fingerprint =
+            |id: i32, title: &str, composer_ids: Vec<i32>, arranger_ids: Vec<i32>| Fingerprint {
+                id,
+                title: title.to_string(),
+                normalized_title: normalize_title(title),
+                composer_ids: composer_ids.into_iter().collect(),
+                arranger_ids: arranger_ids.into_iter().collect(),
+                catalogue_number: None,
+                file_hashes: HashSet::new(),
+            };
+
+        let original = fingerprint(1, "First Suite in Eb", vec![1], vec![]);
+        let retitled = fingerprint(2, "First Suite in E♭", vec![1], vec![]);
+        let arrangement = fingerprint(3, "First Suite in Eb", vec![1], vec![2]);
+        let other_arrangement = fingerprint(4, "First Suite in Eb", vec![1], vec![3]);
+
+        assert!(compare(&original, &retitled).0 >= LIKELY_DUPLICATE_SCORE);
+        assert!(compare(&original, &arrangement).0 >= LIKELY_DUPLICATE_SCORE);
+        assert!(compare(&arrangement, &other_arrangement).0 < LIKELY_DUPLICATE_SCORE);
+    }
+}
@@ -3,7 +3,9 @@ use sea_orm::{
     QuerySelect, RelationTrait,
 };
 
-use crate::entities::{ensemble_parts_instruments, ensembles_parts, instruments};
+use crate::entities::{
+    ensemble_parts_instruments, ensemble_parts_players, ensembles_parts, instruments, players,
+};
 
 use serde_json::Value;
 
@@ -22,12 +24,15 @@ pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr>
                 .all(db)
                 .await?;
 
+            let players = get_players(db, id).await?;
+
             let part = serde_json::json!({
                 "id": part.id,
                 "name": part.name,
                 "created_at": part.created_at,
                 "updated_at": part.updated_at,
                 "instruments": instruments,
+                "players": players,
             });
 
             Ok(part)
@@ -116,6 +121,61 @@ pub async fn set_instruments(
     }
 }
 
+/// Seats the players in the chair, replacing whoever sat there before.
+pub async fn set_players(
+    db: &DatabaseConnection,
+    part_id: i32,
+    player_ids: Vec<i32>,
+) -> Result<(), DbErr> {
+    let part = ensembles_parts::Entity::find_by_id(part_id).one(db).await?;
+    match part {
+        Some(part) => {
+            ensemble_parts_players::Entity::delete_many()
+                .filter(ensemble_parts_players::Column::PartId.eq(part_id))
+                .exec(db)
+                .await?;
+
+            for player_id in &player_ids {
+                let active_part_player = ensemble_parts_players::ActiveModel {
+                    part_id: ActiveValue::Set(part_id),
+                    player_id: ActiveValue::Set(*player_id),
+                };
+
+                ensemble_parts_players::Entity::insert(active_part_player)
+                    .exec(db)
+                    .await?;
+            }
+
+            let mut part: ensembles_parts::ActiveModel = part.into();
+            part.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+            ensembles_parts::Entity::update(part).exec(db).await?;
+
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Part with id {} not found",
+            part_id
+        ))),
+    }
+}
+
+pub(crate) async fn get_players(
+    db: &DatabaseConnection,
+    part_id: i32,
+) -> Result<Vec<Value>, DbErr> {
+    let players = players::Entity::find()
+        .join_rev(
+            JoinType::InnerJoin,
+            ensemble_parts_players::Relation::Players.def(),
+        )
+        .filter(ensemble_parts_players::Column::PartId.eq(part_id))
+        .into_json()
+        .all(db)
+        .await?;
+
+    Ok(players)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
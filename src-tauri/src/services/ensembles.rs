@@ -104,10 +104,13 @@ async fn get_parts(db: &DatabaseConnection, id: i32) -> Result<Vec<Value>, DbErr
             .all(db)
             .await?;
 
+        let players = crate::services::ensemble_parts::get_players(db, part_id).await?;
+
         let part = serde_json::json!({
             "id": part.id,
             "name": part.name,
             "instruments": instruments,
+            "players": players,
             "created_at": part.created_at,
             "updated_at": part.updated_at
         });
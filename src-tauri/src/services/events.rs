@@ -0,0 +1,557 @@
+use sea_orm::{
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::entities::{events, setlists};
+
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{fs, path::Path};
+
+pub const EVENT_TYPES: [&str; 3] = ["concert", "rehearsal", "sectional"];
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+const ICS_DATE_FORMAT: &str = "%Y%m%d";
+const ICS_DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+/// A concert or rehearsal. Times are local, either YYYY-MM-DD HH:MM or
+/// YYYY-MM-DD for events lasting all day. The call time is HH:MM.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct EventDetails {
+    pub event_type: String,
+    pub title: String,
+    pub starts_at: String,
+    pub ends_at: Option<String>,
+    pub call_time: Option<String>,
+    pub location: Option<String>,
+    pub setlist_id: Option<i32>,
+    pub notes: Option<String>,
+}
+
+/// An event read from an iCalendar file, with the setlist still referred to by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CalendarEvent {
+    uid: Option<String>,
+    event_type: String,
+    title: String,
+    starts_at: Option<String>,
+    ends_at: Option<String>,
+    call_time: Option<String>,
+    location: Option<String>,
+    setlist: Option<String>,
+    notes: Option<String>,
+}
+
+pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<Value>, DbErr> {
+    let events = events::Entity::find()
+        .order_by_asc(events::Column::StartsAt)
+        .all(db)
+        .await?;
+
+    to_json(db, &events).await
+}
+
+pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr> {
+    let event = find(db, id).await?;
+    let mut events = to_json(db, &[event]).await?;
+    Ok(events.remove(0))
+}
+
+pub async fn get_by_setlist(db: &DatabaseConnection, setlist_id: i32) -> Result<Vec<Value>, DbErr> {
+    let events = events::Entity::find()
+        .filter(events::Column::SetlistId.eq(setlist_id))
+        .order_by_asc(events::Column::StartsAt)
+        .all(db)
+        .await?;
+
+    to_json(db, &events).await
+}
+
+pub async fn add(db: &DatabaseConnection, details: EventDetails) -> Result<i32, DbErr> {
+    insert(db, details, new_uid()).await
+}
+
+pub async fn update(db: &DatabaseConnection, id: i32, details: EventDetails) -> Result<(), DbErr> {
+    validate(&details)?;
+
+    let event = find(db, id).await?;
+    let mut event: events::ActiveModel = event.into();
+
+    event.event_type = ActiveValue::Set(details.event_type);
+    event.title = ActiveValue::Set(details.title);
+    event.starts_at = ActiveValue::Set(details.starts_at);
+    event.ends_at = ActiveValue::Set(details.ends_at);
+    event.call_time = ActiveValue::Set(details.call_time);
+    event.location = ActiveValue::Set(details.location);
+    event.setlist_id = ActiveValue::Set(details.setlist_id);
+    event.notes = ActiveValue::Set(details.notes);
+    event.updated_at = ActiveValue::Set(Local::now().naive_local().to_string());
+
+    events::Entity::update(event).exec(db).await?;
+    Ok(())
+}
+
+pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    events::Entity::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
+/// Writes every event to an iCalendar file. Returns the number of events written.
+pub async fn export_ics(db: &DatabaseConnection, path: &Path) -> Result<usize, DbErr> {
+    let events = events::Entity::find()
+        .order_by_asc(events::Column::StartsAt)
+        .all(db)
+        .await?;
+    let setlists = setlists::Entity::find().all(db).await?;
+
+    fs::write(path, to_ics(&events, &setlists)?)
+        .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string())))?;
+
+    Ok(events.len())
+}
+
+/// Reads events from an iCalendar file. Events already in the library (matched by UID)
+/// are updated, new ones are added and setlists are linked by name.
+pub async fn import_ics(db: &DatabaseConnection, path: &Path) -> Result<Value, DbErr> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string())))?;
+    let setlists = setlists::Entity::find().all(db).await?;
+
+    let mut added = 0;
+    let mut updated = 0;
+    let mut skipped = 0;
+
+    for calendar_event in from_ics(&text) {
+        let starts_at = match &calendar_event.starts_at {
+            Some(starts_at) => starts_at.clone(),
+            None => {
+                skipped += 1;
+                continue;
+            }
+        };
+
+        let details = EventDetails {
+            event_type: calendar_event.event_type,
+            title: calendar_event.title,
+            starts_at,
+            ends_at: calendar_event.ends_at,
+            call_time: calendar_event.call_time,
+            location: calendar_event.location,
+            setlist_id: calendar_event.setlist.and_then(|name| {
+                setlists
+                    .iter()
+                    .find(|setlist| setlist.name == name)
+                    .map(|setlist| setlist.id)
+            }),
+            notes: calendar_event.notes,
+        };
+        if validate(&details).is_err() {
+            skipped += 1;
+            continue;
+        }
+
+        let existing = match &calendar_event.uid {
+            Some(uid) => {
+                events::Entity::find()
+                    .filter(events::Column::Uid.eq(uid.as_str()))
+                    .one(db)
+                    .await?
+            }
+            None => None,
+        };
+
+        match existing {
+            Some(event) => {
+                update(db, event.id, details).await?;
+                updated += 1;
+            }
+            None => {
+                let uid = calendar_event.uid.unwrap_or_else(new_uid);
+                insert(db, details, uid).await?;
+                added += 1;
+            }
+        }
+    }
+
+    Ok(serde_json::json!({
+        "added": added,
+        "updated": updated,
+        "skipped": skipped,
+    }))
+}
+
+async fn insert(db: &DatabaseConnection, details: EventDetails, uid: String) -> Result<i32, DbErr> {
+    validate(&details)?;
+
+    let active_event = events::ActiveModel {
+        event_type: ActiveValue::Set(details.event_type),
+        title: ActiveValue::Set(details.title),
+        starts_at: ActiveValue::Set(details.starts_at),
+        ends_at: ActiveValue::Set(details.ends_at),
+        call_time: ActiveValue::Set(details.call_time),
+        location: ActiveValue::Set(details.location),
+        setlist_id: ActiveValue::Set(details.setlist_id),
+        notes: ActiveValue::Set(details.notes),
+        uid: ActiveValue::Set(uid),
+        ..Default::default()
+    };
+
+    let event = events::Entity::insert(active_event).exec(db).await?;
+    Ok(event.last_insert_id)
+}
+
+async fn find(db: &DatabaseConnection, id: i32) -> Result<events::Model, DbErr> {
+    let event = events::Entity::find_by_id(id).one(db).await?;
+    match event {
+        Some(event) => Ok(event),
+        None => Err(DbErr::RecordNotFound(format!(
+            "Event with id {} not found",
+            id
+        ))),
+    }
+}
+
+async fn to_json(db: &DatabaseConnection, events: &[events::Model]) -> Result<Vec<Value>, DbErr> {
+    let setlists = setlists::Entity::find()
+        .filter(setlists::Column::Id.is_in(events.iter().filter_map(|event| event.setlist_id)))
+        .all(db)
+        .await?;
+
+    let events = events
+        .iter()
+        .map(|event| {
+            let setlist = event
+                .setlist_id
+                .and_then(|setlist_id| setlists.iter().find(|setlist| setlist.id == setlist_id));
+
+            serde_json::json!({
+                "id": event.id,
+                "event_type": event.event_type,
+                "title": event.title,
+                "starts_at": event.starts_at,
+                "ends_at": event.ends_at,
+                "call_time": event.call_time,
+                "location": event.location,
+                "setlist_id": event.setlist_id,
+                "setlist_name": setlist.map(|setlist| setlist.name.clone()),
+                "notes": event.notes,
+                "uid": event.uid,
+                "created_at": event.created_at,
+                "updated_at": event.updated_at,
+            })
+        })
+        .collect();
+
+    Ok(events)
+}
+
+fn validate(details: &EventDetails) -> Result<(), DbErr> {
+    if !EVENT_TYPES.contains(&details.event_type.as_str()) {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Event type {} is not one of {}",
+            details.event_type,
+            EVENT_TYPES.join(", ")
+        ))));
+    }
+
+    let (starts_at, _) = parse_when(&details.starts_at)?;
+    if let Some(ends_at) = &details.ends_at {
+        let (ends_at, _) = parse_when(ends_at)?;
+        if ends_at < starts_at {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "Event {} ends before it starts",
+                details.title
+            ))));
+        }
+    }
+
+    if let Some(call_time) = &details.call_time {
+        if chrono::NaiveTime::parse_from_str(call_time, "%H:%M").is_err() {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "{} is not a time in the form HH:MM",
+                call_time
+            ))));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a stored event time. The flag is set for dates without a time of day.
+fn parse_when(value: &str) -> Result<(NaiveDateTime, bool), DbErr> {
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(value, DATE_TIME_FORMAT) {
+        return Ok((date_time, false));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, DATE_FORMAT) {
+        return Ok((date.and_hms_opt(0, 0, 0).unwrap(), true));
+    }
+
+    Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+        "{} is not a time in the form YYYY-MM-DD HH:MM or YYYY-MM-DD",
+        value
+    ))))
+}
+
+fn new_uid() -> String {
+    format!(
+        "{}@blackbird",
+        Utc::now()
+            .format("%Y%m%dT%H%M%S%.9f")
+            .to_string()
+            .replace('.', "-")
+    )
+}
+
+fn to_ics(events: &[events::Model], setlists: &[setlists::Model]) -> Result<String, DbErr> {
+    let mut lines: Vec<String> = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//Blackbird//Blackbird//EN"),
+        String::from("CALSCALE:GREGORIAN"),
+    ];
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    for event in events {
+        lines.push(String::from("BEGIN:VEVENT"));
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}", stamp));
+
+        let (starts_at, all_day) = parse_when(&event.starts_at)?;
+        lines.push(format_ics_time("DTSTART", starts_at, all_day));
+        if let Some(ends_at) = &event.ends_at {
+            let (ends_at, all_day) = parse_when(ends_at)?;
+            // the end of an all day event is the first day after it
+            let ends_at = match all_day {
+                true => ends_at + chrono::Duration::days(1),
+                false => ends_at,
+            };
+            lines.push(format_ics_time("DTEND", ends_at, all_day));
+        }
+
+        lines.push(format!("SUMMARY:{}", escape(&event.title)));
+        lines.push(format!("CATEGORIES:{}", event.event_type.to_uppercase()));
+        if let Some(location) = &event.location {
+            lines.push(format!("LOCATION:{}", escape(location)));
+        }
+        if let Some(notes) = &event.notes {
+            lines.push(format!("DESCRIPTION:{}", escape(notes)));
+        }
+        if let Some(call_time) = &event.call_time {
+            lines.push(format!("X-BLACKBIRD-CALL-TIME:{}", call_time));
+        }
+        if let Some(setlist) = event
+            .setlist_id
+            .and_then(|setlist_id| setlists.iter().find(|setlist| setlist.id == setlist_id))
+        {
+            lines.push(format!("X-BLACKBIRD-SETLIST:{}", escape(&setlist.name)));
+        }
+        lines.push(String::from("END:VEVENT"));
+    }
+
+    lines.push(String::from("END:VCALENDAR"));
+
+    Ok(lines
+        .iter()
+        .map(|line| fold(line))
+        .collect::<Vec<String>>()
+        .join("\r\n")
+        + "\r\n")
+}
+
+fn from_ics(text: &str) -> Vec<CalendarEvent> {
+    // lines starting with whitespace continue the previous one
+    let mut lines: Vec<String> = vec![];
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut calendar_events: Vec<CalendarEvent> = vec![];
+    let mut current: Option<CalendarEvent> = None;
+
+    for line in lines {
+        let (key, value) = match line.split_once(':') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let mut params = key.split(';');
+        let name = params.next().unwrap_or_default().to_uppercase();
+        let params: Vec<String> = params.map(|param| param.to_uppercase()).collect();
+
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VEVENT") => {
+                current = Some(CalendarEvent {
+                    event_type: String::from(EVENT_TYPES[0]),
+                    ..Default::default()
+                });
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                calendar_events.extend(current.take());
+            }
+            ("UID", Some(event)) => event.uid = Some(value.to_string()),
+            ("SUMMARY", Some(event)) => event.title = unescape(value),
+            ("LOCATION", Some(event)) => event.location = Some(unescape(value)),
+            ("DESCRIPTION", Some(event)) => event.notes = Some(unescape(value)),
+            ("X-BLACKBIRD-CALL-TIME", Some(event)) => event.call_time = Some(value.to_string()),
+            ("X-BLACKBIRD-SETLIST", Some(event)) => event.setlist = Some(unescape(value)),
+            ("CATEGORIES", Some(event)) => {
+                if let Some(event_type) = value
+                    .split(',')
+                    .map(|category| category.trim().to_lowercase())
+                    .find(|category| EVENT_TYPES.contains(&category.as_str()))
+                {
+                    event.event_type = event_type;
+                }
+            }
+            ("DTSTART", Some(event)) => event.starts_at = parse_ics_time(value, &params, false),
+            ("DTEND", Some(event)) => event.ends_at = parse_ics_time(value, &params, true),
+            _ => {}
+        }
+    }
+
+    calendar_events
+}
+
+fn format_ics_time(name: &str, time: NaiveDateTime, all_day: bool) -> String {
+    match all_day {
+        true => format!("{};VALUE=DATE:{}", name, time.format(ICS_DATE_FORMAT)),
+        false => format!("{}:{}", name, time.format(ICS_DATE_TIME_FORMAT)),
+    }
+}
+
+/// Converts an iCalendar time to the stored form. UTC times are moved to local time,
+/// times with a TZID are taken as local.
+fn parse_ics_time(value: &str, params: &[String], end: bool) -> Option<String> {
+    let all_day = params.iter().any(|param| param == "VALUE=DATE") || value.len() == 8;
+
+    if all_day {
+        let date = NaiveDate::parse_from_str(value, ICS_DATE_FORMAT).ok()?;
+        let date = match end {
+            true => date.pred_opt()?,
+            false => date,
+        };
+        return Some(date.format(DATE_FORMAT).to_string());
+    }
+
+    let time = match value.strip_suffix('Z') {
+        Some(utc) => {
+            let time = NaiveDateTime::parse_from_str(utc, ICS_DATE_TIME_FORMAT).ok()?;
+            Utc.from_utc_datetime(&time)
+                .with_timezone(&Local)
+                .naive_local()
+        }
+        None => NaiveDateTime::parse_from_str(value, ICS_DATE_TIME_FORMAT).ok()?,
+    };
+    Some(time.format(DATE_TIME_FORMAT).to_string())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => {}
+        }
+    }
+
+    unescaped
+}
+
+/// Breaks a content line into chunks of at most 75 bytes, as iCalendar requires.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ics_round_trip() {
+        let now = Local::now().naive_local().to_string();
+        let event = events::Model {
+            id: 1,
+            event_type: String::from("rehearsal"),
+            title: String::from("Dress rehearsal; full orchestra, with soloist"),
+            starts_at: String::from("2026-11-20 19:30"),
+            ends_at: Some(String::from("2026-11-20 22:00")),
+            call_time: Some(String::from("19:00")),
+            location: Some(String::from("Main hall")),
+            setlist_id: Some(4),
+            notes: Some(String::from(
+                "Bring the new parts.\nThe stage door opens at 18:45, park behind the building.",
+            )),
+            uid: String::from("test-event@blackbird"),
+            created_at: now.clone(),
+            updated_at: now.clone(),
+        };
+        let setlist = setlists::Model {
+            id: 4,
+            name: String::from("Winter concert"),
+            created_at: now.clone(),
+            updated_at: now,
+        };
+
+        let ics = to_ics(&[event.clone()], &[setlist]).unwrap();
+        assert!(ics.lines().all(|line| line.len() <= 75));
+
+        let calendar_events = from_ics(&ics);
+        assert_eq!(
+            calendar_events,
+            vec![CalendarEvent {
+                uid: Some(event.uid),
+                event_type: event.event_type,
+                title: event.title,
+                starts_at: Some(event.starts_at),
+                ends_at: event.ends_at,
+                call_time: event.call_time,
+                location: event.location,
+                setlist: Some(String::from("Winter concert")),
+                notes: event.notes,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_all_day_events() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:a\r\nSUMMARY:Sectionals\r\n\
+            CATEGORIES:SECTIONAL\r\nDTSTART;VALUE=DATE:20261107\r\n\
+            DTEND;VALUE=DATE:20261109\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+        let calendar_events = from_ics(ics);
+        assert_eq!(calendar_events[0].event_type, "sectional");
+        assert_eq!(calendar_events[0].starts_at.as_deref(), Some("2026-11-07"));
+        assert_eq!(calendar_events[0].ends_at.as_deref(), Some("2026-11-08"));
+    }
+}
@@ -0,0 +1,436 @@
+use sea_orm::{ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+
+use crate::entities::{part_files, parts, scores};
+use crate::services::pieces;
+use crate::utils;
+
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A score or part file, whichever table it comes from.
+struct File {
+    kind: &'static str,
+    id: i32,
+    piece_id: i32,
+    name: String,
+    path: String,
+    sha256: Option<String>,
+}
+
+/// What storing a file did to it.
+enum Stored {
+    /// Replaced by a clone of the stored copy, freeing this many bytes.
+    Cloned(u64),
+    /// Left as it is because the drive can't clone files.
+    Unsupported,
+    Unchanged,
+}
+
+/// Where files are kept by their hash, inside the library so clones stay on one drive.
+pub fn store_dir(working_directory: &str) -> PathBuf {
+    Path::new(working_directory).join(".store")
+}
+
+/// Hashes every score and part file, of one piece or of the whole library, and
+/// compares it to the hash stored when the file was attached.
+///
+/// Files attached before hashes were stored have theirs recorded now.
+pub async fn verify(db: &DatabaseConnection, piece_id: Option<i32>) -> Result<Value, DbErr> {
+    let files = get_files(db, piece_id).await?;
+
+    let mut ok = 0;
+    let mut recorded = 0;
+    let mut mismatched: Vec<Value> = vec![];
+    let mut missing: Vec<Value> = vec![];
+
+    for file in &files {
+        let actual = match utils::sha256_file(&file.path) {
+            Ok(actual) => actual,
+            Err(_) => {
+                missing.push(to_json(file, None));
+                continue;
+            }
+        };
+
+        match &file.sha256 {
+            Some(expected) if *expected == actual => ok += 1,
+            Some(_) => mismatched.push(to_json(file, Some(&actual))),
+            None => {
+                set_sha256(db, file, actual).await?;
+                recorded += 1;
+            }
+        }
+    }
+
+    Ok(serde_json::json!({
+        "checked": files.len(),
+        "ok": ok,
+        "recorded": recorded,
+        "mismatched": mismatched,
+        "missing": missing,
+    }))
+}
+
+/// Hashes the files attached before hashes were stored, so duplicate checks can go
+/// by the stored hashes alone. Returns how many were recorded.
+pub async fn record_missing_hashes(db: &DatabaseConnection) -> Result<usize, DbErr> {
+    let files = get_files(db, None).await?;
+
+    let mut recorded = 0;
+    for file in files.iter().filter(|file| file.sha256.is_none()) {
+        if let Ok(sha256) = utils::sha256_file(&file.path) {
+            set_sha256(db, file, sha256).await?;
+            recorded += 1;
+        }
+    }
+
+    Ok(recorded)
+}
+
+/// Replaces identical files with copy-on-write clones of one copy in the store,
+/// named by its hash. Clones share their storage until one of them is written to,
+/// so a file changed in place only changes for its own piece.
+///
+/// Only files that still match their stored hash are cloned. On drives that can't
+/// clone files, files are left as they are and counted as unsupported. Going
+/// through the whole library also removes stored copies no file has anymore.
+pub async fn deduplicate(
+    db: &DatabaseConnection,
+    store_dir: &Path,
+    piece_id: Option<i32>,
+) -> Result<Value, DbErr> {
+    let files = get_files(db, piece_id).await?;
+
+    let mut cloned = 0;
+    let mut unsupported = 0;
+    let mut saved_bytes: u64 = 0;
+    let mut failed: Vec<Value> = vec![];
+
+    for file in &files {
+        match store_file(db, store_dir, file).await? {
+            Ok(Stored::Cloned(size)) => {
+                cloned += 1;
+                saved_bytes += size;
+            }
+            Ok(Stored::Unsupported) => unsupported += 1,
+            Ok(Stored::Unchanged) => {}
+            Err(e) => {
+                let mut failure = to_json(file, None);
+                failure["error"] = Value::from(e.to_string());
+                failed.push(failure);
+            }
+        }
+    }
+
+    let removed = match piece_id {
+        Some(_) => 0,
+        None => collect_garbage(db, store_dir).await?,
+    };
+
+    Ok(serde_json::json!({
+        "cloned": cloned,
+        "unsupported": unsupported,
+        "saved_bytes": saved_bytes,
+        "removed": removed,
+        "failed": failed,
+    }))
+}
+
+/// Puts one score or part file into the store, e.g. right after it was added, without
+/// going through the other files of its piece again.
+pub async fn store(
+    db: &DatabaseConnection,
+    store_dir: &Path,
+    kind: &str,
+    id: i32,
+) -> Result<(), DbErr> {
+    let file = match kind {
+        "score" => scores::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .and_then(from_score),
+        _ => parts::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .and_then(from_part),
+    };
+
+    match file {
+        Some(file) => match store_file(db, store_dir, &file).await? {
+            Ok(_) => Ok(()),
+            Err(e) => Err(io_err(e)),
+        },
+        None => Ok(()),
+    }
+}
+
+/// Removes stored copies whose hash no score, part or part file has anymore, such as
+/// those of deleted pieces. Returns how many were removed.
+pub async fn collect_garbage(db: &DatabaseConnection, store_dir: &Path) -> Result<usize, DbErr> {
+    if !store_dir.exists() {
+        return Ok(0);
+    }
+
+    let mut hashes: HashSet<String> = HashSet::new();
+    hashes.extend(
+        scores::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|score| score.sha256),
+    );
+    hashes.extend(
+        parts::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|part| part.sha256),
+    );
+    hashes.extend(
+        part_files::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|part_file| part_file.sha256),
+    );
+
+    let mut removed = 0;
+    for prefix in fs::read_dir(store_dir).map_err(io_err)? {
+        let prefix = prefix.map_err(io_err)?.path();
+        if !prefix.is_dir() {
+            continue;
+        }
+
+        for stored in fs::read_dir(&prefix).map_err(io_err)? {
+            let stored = stored.map_err(io_err)?.path();
+            let sha256 = stored
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if !hashes.contains(&sha256) {
+                fs::remove_file(&stored).map_err(io_err)?;
+                removed += 1;
+            }
+        }
+        pieces::remove_dir_if_empty(&prefix);
+    }
+
+    Ok(removed)
+}
+
+async fn get_files(db: &DatabaseConnection, piece_id: Option<i32>) -> Result<Vec<File>, DbErr> {
+    let mut scores = scores::Entity::find();
+    let mut parts = parts::Entity::find();
+    if let Some(piece_id) = piece_id {
+        scores = scores.filter(scores::Column::PieceId.eq(piece_id));
+        parts = parts.filter(parts::Column::PieceId.eq(piece_id));
+    }
+
+    let scores = scores.all(db).await?.into_iter().filter_map(from_score);
+    let parts = parts.all(db).await?.into_iter().filter_map(from_part);
+
+    Ok(scores.chain(parts).collect())
+}
+
+fn from_score(score: scores::Model) -> Option<File> {
+    Some(File {
+        kind: "score",
+        id: score.id,
+        piece_id: score.piece_id,
+        name: score.name,
+        path: score.path?,
+        sha256: score.sha256,
+    })
+}
+
+fn from_part(part: parts::Model) -> Option<File> {
+    Some(File {
+        kind: "part",
+        id: part.id,
+        piece_id: part.piece_id,
+        name: part.name,
+        path: part.path?,
+        sha256: part.sha256,
+    })
+}
+
+async fn set_sha256(db: &DatabaseConnection, file: &File, sha256: String) -> Result<(), DbErr> {
+    match file.kind {
+        "score" => {
+            let score = scores::ActiveModel {
+                id: ActiveValue::Unchanged(file.id),
+                sha256: ActiveValue::Set(Some(sha256)),
+                ..Default::default()
+            };
+            scores::Entity::update(score).exec(db).await?;
+        }
+        _ => {
+            let part = parts::ActiveModel {
+                id: ActiveValue::Unchanged(file.id),
+                sha256: ActiveValue::Set(Some(sha256)),
+                ..Default::default()
+            };
+            parts::Entity::update(part).exec(db).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Hashes a file and clones it from the store when it still matches its stored hash.
+/// Database errors end the run, file errors are reported for the file alone.
+async fn store_file(
+    db: &DatabaseConnection,
+    store_dir: &Path,
+    file: &File,
+) -> Result<io::Result<Stored>, DbErr> {
+    let actual = match utils::sha256_file(&file.path) {
+        Ok(actual) => actual,
+        Err(_) => return Ok(Ok(Stored::Unchanged)),
+    };
+    match &file.sha256 {
+        Some(expected) if *expected == actual => {}
+        Some(_) => return Ok(Ok(Stored::Unchanged)),
+        None => set_sha256(db, file, actual.clone()).await?,
+    }
+
+    Ok(clone(store_dir, Path::new(&file.path), &actual))
+}
+
+/// Clones a file from its copy in the store, putting it there first if it's the first
+/// of its kind.
+fn clone(store_dir: &Path, path: &Path, sha256: &str) -> io::Result<Stored> {
+    let stored = store_dir.join(&sha256[..2]).join(sha256);
+
+    if !stored.exists() {
+        if let Some(parent) = stored.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if reflink_copy::reflink(path, &stored).is_err() {
+            let _ = fs::remove_file(&stored);
+        } else if !is_hard_linked(path)? {
+            return Ok(Stored::Unchanged);
+        }
+    }
+
+    // clone next to the file first, so it is never missing if cloning fails.
+    // Clones can't be told apart from copies, so files are cloned again each run
+    let size = fs::metadata(path)?.len();
+    let cloning = path.with_extension("cloning");
+    if !stored.exists() || reflink_copy::reflink(&stored, &cloning).is_err() {
+        let _ = fs::remove_file(&cloning);
+        // libraries stored before files were cloned have them hard linked, which
+        // shares every write, so those get a copy of their own
+        if is_hard_linked(path)? {
+            fs::copy(path, &cloning)?;
+            fs::rename(&cloning, path)?;
+        }
+        return Ok(Stored::Unsupported);
+    }
+    fs::rename(&cloning, path)?;
+
+    Ok(Stored::Cloned(size))
+}
+
+#[cfg(unix)]
+fn is_hard_linked(path: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    Ok(fs::metadata(path)?.nlink() > 1)
+}
+
+#[cfg(not(unix))]
+fn is_hard_linked(_path: &Path) -> io::Result<bool> {
+    Ok(false)
+}
+
+fn io_err(e: io::Error) -> DbErr {
+    DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string()))
+}
+
+fn to_json(file: &File, actual: Option<&str>) -> Value {
+    serde_json::json!({
+        "kind": file.kind,
+        "id": file.id,
+        "piece_id": file.piece_id,
+        "name": file.name,
+        "path": file.path,
+        "expected": file.sha256,
+        "actual": actual,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+    use crate::services::{parts as parts_service, pieces as pieces_service};
+
+    #[tokio::test]
+    async fn test_verify_and_deduplicate() {
+        let db = init().await.unwrap();
+
+        let directory = std::env::temp_dir().join("blackbird test files");
+        fs::create_dir_all(&directory).unwrap();
+        let first = directory.join("1.1_Clarinet 2.pdf");
+        let second = directory.join("1.2_Clarinet 2.pdf");
+        fs::write(&first, b"the same part").unwrap();
+        fs::write(&second, b"the same part").unwrap();
+
+        let piece_id = pieces_service::add(
+            &db,
+            String::from("test hashed piece"),
+            None,
+            directory.to_str().unwrap().to_string(),
+            None,
+            String::new(),
+        )
+        .await
+        .unwrap();
+        let path = |path: &PathBuf| Some(path.to_str().unwrap().to_string());
+        parts_service::add(&db, String::from("Clarinet 2"), path(&first), piece_id)
+            .await
+            .unwrap();
+        parts_service::add(&db, String::from("Clarinet 2"), path(&second), piece_id)
+            .await
+            .unwrap();
+
+        let report = verify(&db, Some(piece_id)).await.unwrap();
+        assert_eq!(report["checked"], 2);
+        assert_eq!(report["ok"], 2);
+
+        let store = directory.join(".store");
+        let sha256 = utils::sha256_file(&first).unwrap();
+        let report = deduplicate(&db, &store, Some(piece_id)).await.unwrap();
+        assert!(report["failed"].as_array().unwrap().is_empty());
+        assert!(report["cloned"] == 1 || report["unsupported"] == 2);
+        assert_eq!(fs::read(&second).unwrap(), b"the same part");
+
+        fs::write(&second, b"a damaged part").unwrap();
+        let report = verify(&db, Some(piece_id)).await.unwrap();
+        // cloned files only share their contents until one is written to
+        assert_eq!(report["mismatched"].as_array().unwrap().len(), 1);
+
+        // files hard linked by earlier versions get a copy of their own
+        fs::remove_file(&second).unwrap();
+        fs::hard_link(&first, &second).unwrap();
+        let report = deduplicate(&db, &store, Some(piece_id)).await.unwrap();
+        assert!(report["failed"].as_array().unwrap().is_empty());
+        assert!(!is_hard_linked(&first).unwrap());
+        assert!(!is_hard_linked(&second).unwrap());
+
+        crate::entities::pieces::Entity::delete_by_id(piece_id)
+            .exec(&db)
+            .await
+            .unwrap();
+        collect_garbage(&db, &store).await.unwrap();
+        assert!(!store.join(&sha256[..2]).exists());
+        fs::remove_dir_all(&directory).unwrap();
+
+        let _ = db.close().await;
+    }
+}
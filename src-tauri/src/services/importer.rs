@@ -0,0 +1,712 @@
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait};
+
+use crate::entities::{instruments, musician_aliases, musicians, pieces, pieces_musicians};
+use crate::naming::{FileNames, Templates};
+use crate::services::instruments::guess;
+use crate::services::musicians::{display_name, sort_name};
+use crate::services::scores::is_score_name;
+use crate::services::{musicians as musicians_service, parts, pieces as pieces_service, scores};
+
+use serde_json::Value;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// How most collections are laid out: a folder per composer with a folder per piece.
+pub const DEFAULT_PATTERN: &str = "{composer}/{title}/{part}.pdf";
+
+/// What a path pattern can pick out. `ignore` matches anything, e.g. a "Band" folder.
+pub const PATTERN_FIELDS: [&str; 6] = ["composer", "arranger", "title", "year", "part", "ignore"];
+
+/// A piece of a path pattern segment, e.g. `{year} - {title}` is a field, a literal and a field.
+#[derive(Debug, PartialEq)]
+enum Token {
+    Literal(String),
+    Field(String),
+}
+
+/// A piece found in the folder, with the files that belong to it.
+struct FoundPiece {
+    title: String,
+    composer: Option<String>,
+    arranger: Option<String>,
+    year: Option<i32>,
+    files: Vec<FoundFile>,
+}
+
+struct FoundFile {
+    path: PathBuf,
+    name: String,
+    is_score: bool,
+    instrument: Option<instruments::Model>,
+}
+
+/// Imports every PDF under `source` whose path matches the pattern, e.g.
+/// `{composer}/{title}/{part}.pdf`, as pieces with their scores and parts.
+///
+/// Musicians are matched by name (or alias) and added when there is no match.
+/// Parts get the instrument their name points to, and files with names like
+/// "Full Score" become scores. Pieces already in the library are left out, and
+/// pieces that can't be imported are listed as failed without stopping the rest.
+/// With `dry_run` nothing is changed and the report shows what would be imported.
+pub async fn import_folder(
+    db: &DatabaseConnection,
+    source: &Path,
+    pattern: &str,
+    working_directory: &Path,
+    templates: &Templates,
+    dry_run: bool,
+) -> Result<Value, DbErr> {
+    if !source.is_dir() {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "{} is not a folder",
+            source.display()
+        ))));
+    }
+    let segments =
+        parse_pattern(pattern).map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e)))?;
+
+    let mut paths: Vec<PathBuf> = vec![];
+    find_pdfs(source, &mut paths)
+        .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string())))?;
+
+    let instruments = instruments::Entity::find().all(db).await?;
+    let mut found: Vec<FoundPiece> = vec![];
+    let mut unmatched: Vec<String> = vec![];
+
+    for path in paths {
+        let components: Vec<String> = path
+            .strip_prefix(source)
+            .unwrap_or(&path)
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect();
+
+        let values = match match_path(&segments, &components) {
+            Some(values) => values,
+            None => {
+                unmatched.push(path.to_string_lossy().to_string());
+                continue;
+            }
+        };
+        let value = |field: &str| {
+            values
+                .iter()
+                .find(|(name, _)| name == field)
+                .map(|(_, value)| value.clone())
+        };
+
+        // patterns always have a title
+        let title = value("title").unwrap_or_default();
+        let composer = value("composer");
+        let arranger = value("arranger");
+        let name = value("part").unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+        let is_score = is_score_name(&name);
+        let instrument = match is_score {
+            true => None,
+            false => guess(&name, &instruments).cloned(),
+        };
+        let file = FoundFile {
+            path,
+            name,
+            is_score,
+            instrument,
+        };
+
+        let piece = found.iter_mut().find(|piece| {
+            piece.title.to_lowercase() == title.to_lowercase()
+                && piece.composer == composer
+                && piece.arranger == arranger
+        });
+        match piece {
+            Some(piece) => piece.files.push(file),
+            None => found.push(FoundPiece {
+                title,
+                composer,
+                arranger,
+                year: value("year").and_then(|year| year.parse().ok()),
+                files: vec![file],
+            }),
+        }
+    }
+
+    let mut musicians = musicians::Entity::find().all(db).await?;
+    let aliases = musician_aliases::Entity::find().all(db).await?;
+    let existing = pieces::Entity::find().all(db).await?;
+    let composer_credits: Vec<pieces_musicians::Model> = pieces_musicians::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|credit| credit.role == "composer")
+        .collect();
+
+    let mut new_musicians: Vec<String> = vec![];
+    let mut report: Vec<Value> = vec![];
+    let mut failed: Vec<Value> = vec![];
+    let mut created = 0;
+
+    for mut piece in found {
+        let mut credit = |name: &Option<String>| -> Option<musicians::Model> {
+            let name = name.as_deref()?;
+            let musician = find_musician(&musicians, &aliases, name).cloned();
+            if musician.is_none() && !new_musicians.contains(&name.to_string()) {
+                new_musicians.push(name.to_string());
+            }
+            musician
+        };
+        let mut composer = credit(&piece.composer);
+        let mut arranger = credit(&piece.arranger);
+
+        let exists = existing.iter().any(|existing| {
+            existing.title.to_lowercase() == piece.title.to_lowercase()
+                && match &composer {
+                    Some(composer) => composer_credits.iter().any(|credit| {
+                        credit.piece_id == existing.id && credit.musician_id == composer.id
+                    }),
+                    None => piece.composer.is_none(),
+                }
+        });
+
+        // scores come first, like in the edit wizard
+        piece.files.sort_by_key(|file| !file.is_score);
+
+        let mut piece_id = None;
+        if !dry_run && !exists {
+            let result: Result<i32, DbErr> = async {
+                if composer.is_none() {
+                    composer = add_musician(db, &mut musicians, &piece.composer).await?;
+                }
+                if arranger.is_none() {
+                    arranger = add_musician(db, &mut musicians, &piece.arranger).await?;
+                }
+
+                create_piece(
+                    db,
+                    &piece,
+                    working_directory,
+                    templates,
+                    composer.as_ref(),
+                    arranger.as_ref(),
+                )
+                .await
+            }
+            .await;
+
+            match result {
+                Ok(id) => {
+                    piece_id = Some(id);
+                    created += 1;
+                }
+                Err(e) => {
+                    failed.push(serde_json::json!({
+                        "title": piece.title,
+                        "error": e.to_string(),
+                    }));
+                    continue;
+                }
+            }
+        }
+
+        let files = |is_score: bool| -> Vec<Value> {
+            piece
+                .files
+                .iter()
+                .filter(|file| file.is_score == is_score)
+                .map(|file| {
+                    serde_json::json!({
+                        "name": file.name,
+                        "path": file.path,
+                        "instrument_id": file.instrument.as_ref().map(|instrument| instrument.id),
+                        "instrument_name": file.instrument.as_ref().map(|instrument| instrument.name.clone()),
+                    })
+                })
+                .collect()
+        };
+
+        report.push(serde_json::json!({
+            "title": piece.title,
+            "composer": piece.composer,
+            "composer_id": composer.as_ref().map(|composer| composer.id),
+            "arranger": piece.arranger,
+            "arranger_id": arranger.as_ref().map(|arranger| arranger.id),
+            "year_published": piece.year,
+            "exists": exists,
+            "piece_id": piece_id,
+            "scores": files(true),
+            "parts": files(false),
+        }));
+    }
+
+    Ok(serde_json::json!({
+        "dry_run": dry_run,
+        "pieces": report,
+        "created": created,
+        "new_musicians": new_musicians,
+        "unmatched": unmatched,
+        "failed": failed,
+    }))
+}
+
+async fn add_musician(
+    db: &DatabaseConnection,
+    musicians: &mut Vec<musicians::Model>,
+    name: &Option<String>,
+) -> Result<Option<musicians::Model>, DbErr> {
+    let name = match name {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+    // an earlier piece of this import may have added them already
+    if let Some(musician) = find_musician(musicians, &[], name) {
+        return Ok(Some(musician.clone()));
+    }
+
+    let (first_name, last_name) = split_musician_name(name);
+    let id = musicians_service::add(db, first_name, last_name).await?;
+    let musician = musicians::Entity::find_by_id(id).one(db).await?;
+    if let Some(musician) = &musician {
+        musicians.push(musician.clone());
+    }
+
+    Ok(musician)
+}
+
+/// Adds the piece with its files copied into the library. Returns its id.
+async fn create_piece(
+    db: &DatabaseConnection,
+    piece: &FoundPiece,
+    working_directory: &Path,
+    templates: &Templates,
+    composer: Option<&musicians::Model>,
+    arranger: Option<&musicians::Model>,
+) -> Result<i32, DbErr> {
+    let piece_id = pieces_service::add(
+        db,
+        piece.title.clone(),
+        piece.year,
+        String::new(),
+        None,
+        String::new(),
+    )
+    .await?;
+
+    // leave nothing half imported behind
+    let result = add_files(
+        db,
+        piece_id,
+        piece,
+        working_directory,
+        templates,
+        composer,
+        arranger,
+    )
+    .await;
+    if let Err(e) = result {
+        pieces_service::delete(db, piece_id).await?;
+        return Err(e);
+    }
+
+    Ok(piece_id)
+}
+
+async fn add_files(
+    db: &DatabaseConnection,
+    piece_id: i32,
+    piece: &FoundPiece,
+    working_directory: &Path,
+    templates: &Templates,
+    composer: Option<&musicians::Model>,
+    arranger: Option<&musicians::Model>,
+) -> Result<(), DbErr> {
+    let io_err = |e: io::Error| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string()));
+
+    for (musician, role) in [(composer, "composer"), (arranger, "arranger")] {
+        if let Some(musician) = musician {
+            pieces_service::set_musicians(
+                db,
+                piece_id,
+                vec![musician.id],
+                String::from(role),
+                working_directory,
+                templates,
+            )
+            .await?;
+        }
+    }
+
+    let folder =
+        pieces_service::move_to_library_folder(db, working_directory, templates, piece_id).await?;
+    let names = pieces_service::library_names(db, piece_id).await?;
+
+    let mut score_count = 0;
+    let mut part_count = 0;
+
+    for file in &piece.files {
+        let number = match file.is_score {
+            true => {
+                score_count += 1;
+                score_count
+            }
+            false => {
+                part_count += 1;
+                part_count
+            }
+        };
+        let destination = folder.join(
+            templates.file(
+                &names,
+                &FileNames {
+                    is_score: file.is_score,
+                    order: number,
+                    name: file.name.clone(),
+                    instrument: file
+                        .instrument
+                        .as_ref()
+                        .map(|instrument| instrument.name.clone()),
+                },
+            ),
+        );
+        fs::copy(&file.path, &destination).map_err(io_err)?;
+
+        let path = destination.to_str().map(|path| path.to_string());
+        match file.is_score {
+            true => {
+                scores::add(db, file.name.clone(), path, piece_id).await?;
+            }
+            false => {
+                let part_id = parts::add(db, file.name.clone(), path, piece_id).await?;
+                let instrument_ids = file
+                    .instrument
+                    .iter()
+                    .map(|instrument| instrument.id)
+                    .collect();
+                parts::set_instruments(db, part_id, instrument_ids).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn find_pdfs(folder: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(folder)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            !path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            find_pdfs(&path, paths)?;
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"))
+        {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds a musician by any of their names, written either "First Last" or "Last, First".
+fn find_musician<'a>(
+    musicians: &'a [musicians::Model],
+    aliases: &[musician_aliases::Model],
+    name: &str,
+) -> Option<&'a musicians::Model> {
+    let (first_name, last_name) = split_musician_name(name);
+    let mut names = vec![name.trim().to_lowercase()];
+    if let Some(last_name) = &last_name {
+        names.push(format!("{} {}", first_name, last_name).to_lowercase());
+        names.push(format!("{}, {}", last_name, first_name).to_lowercase());
+    }
+
+    musicians.iter().find(|musician| {
+        [display_name(musician), sort_name(musician)]
+            .into_iter()
+            .chain(
+                aliases
+                    .iter()
+                    .filter(|alias| alias.musician_id == musician.id)
+                    .map(|alias| alias.name.clone()),
+            )
+            .any(|known| names.contains(&known.to_lowercase()))
+    })
+}
+
+/// Splits "Holst, Gustav" or "Gustav Holst" into first and last name.
+fn split_musician_name(name: &str) -> (String, Option<String>) {
+    let name = name.trim();
+
+    if let Some((last_name, first_name)) = name.split_once(',') {
+        if !first_name.trim().is_empty() {
+            return (
+                first_name.trim().to_string(),
+                Some(last_name.trim().to_string()),
+            );
+        }
+    }
+
+    match name.rsplit_once(' ') {
+        Some((first_name, last_name)) => {
+            (first_name.trim().to_string(), Some(last_name.to_string()))
+        }
+        None => (name.trim_end_matches(',').to_string(), None),
+    }
+}
+
+fn parse_pattern(pattern: &str) -> Result<Vec<Vec<Token>>, String> {
+    let segments: Vec<Vec<Token>> = pattern
+        .trim_matches('/')
+        .split('/')
+        .map(parse_segment)
+        .collect::<Result<_, _>>()?;
+
+    let has_title = segments
+        .iter()
+        .flatten()
+        .any(|token| *token == Token::Field(String::from("title")));
+    if !has_title {
+        return Err(format!("Pattern {} has no {{title}}", pattern));
+    }
+
+    Ok(segments)
+}
+
+fn parse_segment(segment: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut rest = segment;
+
+    while !rest.is_empty() {
+        match rest.find('{') {
+            Some(0) => {
+                let end = rest
+                    .find('}')
+                    .ok_or_else(|| format!("{} has a {{ without a }}", segment))?;
+                let field = &rest[1..end];
+                if !PATTERN_FIELDS.contains(&field) {
+                    return Err(format!(
+                        "{{{}}} is not one of {{{}}}",
+                        field,
+                        PATTERN_FIELDS.join("}, {")
+                    ));
+                }
+                tokens.push(Token::Field(field.to_string()));
+                rest = &rest[end + 1..];
+            }
+            Some(start) => {
+                tokens.push(Token::Literal(rest[..start].to_string()));
+                rest = &rest[start..];
+            }
+            None => {
+                tokens.push(Token::Literal(rest.to_string()));
+                rest = "";
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Matches the folders and file name of a path against the pattern's segments,
+/// returning the value of every field.
+fn match_path(segments: &[Vec<Token>], components: &[String]) -> Option<Vec<(String, String)>> {
+    if segments.len() != components.len() {
+        return None;
+    }
+
+    let mut values = vec![];
+    for (tokens, component) in segments.iter().zip(components) {
+        values.extend(match_tokens(tokens, component)?);
+    }
+
+    Some(values)
+}
+
+/// Fields followed by a literal take as little as they can, so `{year} - {title}`
+/// splits "1909 - Suite in F - No. 2" at the first " - ". Other fields take as much
+/// as they can.
+fn match_tokens(tokens: &[Token], text: &str) -> Option<Vec<(String, String)>> {
+    match tokens.split_first() {
+        None => text.is_empty().then(Vec::new),
+        Some((Token::Literal(literal), rest)) => {
+            let head = text.get(..literal.len())?;
+            if !head.eq_ignore_ascii_case(literal) {
+                return None;
+            }
+            match_tokens(rest, &text[literal.len()..])
+        }
+        Some((Token::Field(field), rest)) => {
+            let mut ends: Vec<usize> = text
+                .char_indices()
+                .map(|(index, _)| index)
+                .skip(1)
+                .chain([text.len()])
+                .collect();
+            if !matches!(rest.first(), Some(Token::Literal(_))) {
+                ends.reverse();
+            }
+            for end in ends {
+                let value = text[..end].trim();
+                if value.is_empty() {
+                    continue;
+                }
+                if let Some(mut values) = match_tokens(rest, &text[end..]) {
+                    values.insert(0, (field.clone(), value.to_string()));
+                    return Some(values);
+                }
+            }
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+
+    #[test]
+    fn test_match_path() {
+        let segments = parse_pattern("{composer}/{year} - {title}/{part}.pdf").unwrap();
+        let components = [
+            String::from("Holst, Gustav"),
+            String::from("1909 - Suite in F - No. 2"),
+            String::from("Clarinet 2.PDF"),
+        ];
+        let values = match_path(&segments, &components).unwrap();
+        assert_eq!(
+            values,
+            vec![
+                (String::from("composer"), String::from("Holst, Gustav")),
+                (String::from("year"), String::from("1909")),
+                (String::from("title"), String::from("Suite in F - No. 2")),
+                (String::from("part"), String::from("Clarinet 2")),
+            ]
+        );
+
+        assert!(match_path(&segments, &components[1..]).is_none());
+        assert!(parse_pattern("{composer}/{part}.pdf").is_err());
+        assert!(parse_pattern("{composer}/{name}/{part}.pdf").is_err());
+    }
+
+    #[test]
+    fn test_split_musician_name() {
+        assert_eq!(
+            split_musician_name("Holst, Gustav"),
+            (String::from("Gustav"), Some(String::from("Holst")))
+        );
+        assert_eq!(
+            split_musician_name("Ludwig van Beethoven"),
+            (String::from("Ludwig van"), Some(String::from("Beethoven")))
+        );
+        assert_eq!(
+            split_musician_name("Traditional"),
+            (String::from("Traditional"), None)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run() {
+        let db = init().await.unwrap();
+
+        let source = std::env::temp_dir().join("blackbird test import");
+        let piece = source.join("Holst, Gustav").join("First Suite in Eb");
+        fs::create_dir_all(&piece).unwrap();
+        fs::write(piece.join("Full Score.pdf"), b"score").unwrap();
+        fs::write(piece.join("Clarinet 2.pdf"), b"part").unwrap();
+        fs::write(source.join("Holst, Gustav").join("stray.pdf"), b"stray").unwrap();
+
+        let report = import_folder(
+            &db,
+            &source,
+            DEFAULT_PATTERN,
+            &source,
+            &Templates::default(),
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!(report["created"], 0);
+        assert_eq!(report["pieces"][0]["title"], "First Suite in Eb");
+        assert_eq!(report["pieces"][0]["scores"][0]["name"], "Full Score");
+        assert_eq!(
+            report["pieces"][0]["parts"][0]["instrument_name"],
+            "Clarinet in Bb"
+        );
+        assert_eq!(report["unmatched"].as_array().unwrap().len(), 1);
+
+        fs::remove_dir_all(&source).unwrap();
+
+        let _ = db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_import() {
+        let db = init().await.unwrap();
+
+        let directory = std::env::temp_dir().join("blackbird test import files");
+        let source = directory.join("source");
+        let library = directory.join("library");
+        let piece = source
+            .join("Importer, Tess")
+            .join("1911 - Test Import March");
+        fs::create_dir_all(&piece).unwrap();
+        fs::create_dir_all(&library).unwrap();
+        fs::write(piece.join("Full Score.pdf"), b"score").unwrap();
+        fs::write(piece.join("Clarinet 2.pdf"), b"part").unwrap();
+
+        let report = import_folder(
+            &db,
+            &source,
+            "{composer}/{year} - {title}/{part}.pdf",
+            &library,
+            &Templates::default(),
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(report["created"], 1);
+        assert!(report["failed"].as_array().unwrap().is_empty());
+        assert_eq!(report["pieces"][0]["year_published"], 1911);
+
+        let piece_id = report["pieces"][0]["piece_id"].as_i64().unwrap() as i32;
+        let imported = pieces_service::get_by_id(&db, piece_id).await.unwrap();
+        assert_eq!(imported["title"], "Test Import March");
+        assert_eq!(imported["scores"].as_array().unwrap().len(), 1);
+        let part_path = imported["parts"][0]["path"].as_str().unwrap();
+        assert!(Path::new(part_path).starts_with(&library));
+        assert_eq!(fs::read(part_path).unwrap(), b"part");
+
+        // importing again leaves the piece out
+        let report = import_folder(
+            &db,
+            &source,
+            "{composer}/{year} - {title}/{part}.pdf",
+            &library,
+            &Templates::default(),
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(report["created"], 0);
+        assert_eq!(report["pieces"][0]["exists"], true);
+
+        let composer_id = report["pieces"][0]["composer_id"].as_i64().unwrap() as i32;
+        pieces_service::delete(&db, piece_id).await.unwrap();
+        musicians_service::delete(&db, composer_id).await.unwrap();
+        let _ = fs::remove_dir_all(&directory);
+
+        let _ = db.close().await;
+    }
+}
@@ -0,0 +1,929 @@
+use sea_orm::{
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::entities::{draft_files, drafts, instruments, musicians};
+use crate::naming::{FileNames, Templates};
+use crate::services::instruments::{guess, is_key, name_words};
+use crate::services::scores::{is_score_name, SCORE_WORDS};
+use crate::services::{parts, pieces, scores};
+
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Drafts waiting to be reviewed and filed.
+pub const PENDING: &str = "pending";
+/// Drafts that were reviewed and aren't to be filed. Their files stay in the
+/// inbox, but aren't queued again.
+pub const DISMISSED: &str = "dismissed";
+/// Drafts filed into the library from an archive other drafts still need. They
+/// are kept out of sight until the archive goes, so its entries aren't queued again.
+pub const FILED: &str = "filed";
+
+pub const FILE_KINDS: [&str; 2] = ["score", "part"];
+
+/// A PDF found in the inbox, on its own, in a folder or in a ZIP archive.
+struct Found {
+    path: PathBuf,
+    entry: Option<String>,
+    stem: String,
+}
+
+/// Files that look like they belong to the same piece.
+struct Group {
+    title: String,
+    source: Option<PathBuf>,
+    files: Vec<Found>,
+}
+
+/// The words part names are made of, taken from the instrument names.
+struct Vocabulary {
+    /// Words that name an instrument (or a score), like "clarinet" or "bass".
+    kinds: HashSet<String>,
+    /// Anything else that may be part of a part name, like "in" or "bb".
+    words: HashSet<String>,
+}
+
+/// Returns the drafts waiting for review with their files, oldest first.
+pub async fn get_drafts(
+    db: &DatabaseConnection,
+    include_dismissed: bool,
+) -> Result<Vec<Value>, DbErr> {
+    let mut query = drafts::Entity::find().order_by_asc(drafts::Column::Id);
+    query = match include_dismissed {
+        true => query.filter(drafts::Column::Status.ne(FILED)),
+        false => query.filter(drafts::Column::Status.eq(PENDING)),
+    };
+    let drafts = query.all(db).await?;
+
+    let files = draft_files::Entity::find()
+        .filter(draft_files::Column::DraftId.is_in(drafts.iter().map(|draft| draft.id)))
+        .order_by_asc(draft_files::Column::Id)
+        .all(db)
+        .await?;
+    let instruments = instruments::Entity::find().all(db).await?;
+
+    let drafts = drafts
+        .iter()
+        .map(|draft| {
+            let files: Vec<Value> = files
+                .iter()
+                .filter(|file| file.draft_id == draft.id)
+                .map(|file| {
+                    let instrument = instruments
+                        .iter()
+                        .find(|instrument| Some(instrument.id) == file.instrument_id);
+
+                    serde_json::json!({
+                        "id": file.id,
+                        "path": file.path,
+                        "entry": file.entry,
+                        "name": file.name,
+                        "kind": file.kind,
+                        "instrument_id": file.instrument_id,
+                        "instrument_name": instrument.map(|instrument| instrument.name.clone()),
+                    })
+                })
+                .collect();
+
+            serde_json::json!({
+                "id": draft.id,
+                "title": draft.title,
+                "source": draft.source,
+                "status": draft.status,
+                "files": files,
+                "created_at": draft.created_at,
+                "updated_at": draft.updated_at,
+            })
+        })
+        .collect();
+
+    Ok(drafts)
+}
+
+/// Looks for PDFs and ZIP archives in the inbox that aren't queued yet and
+/// queues them as drafts, grouping files by the piece their names point to.
+///
+/// Loose PDFs are grouped by title, e.g. "Suite in F - Clarinet 1.pdf" and
+/// "Suite in F - Flute.pdf". Each folder or ZIP archive is a piece of its own.
+/// Drafts whose files have all disappeared are removed.
+pub async fn scan(db: &DatabaseConnection, inbox: &Path) -> Result<Value, DbErr> {
+    if !inbox.is_dir() {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "The inbox {} is not a folder",
+            inbox.display()
+        ))));
+    }
+
+    let removed = remove_missing(db).await?;
+
+    let instruments = instruments::Entity::find().all(db).await?;
+    let vocabulary = vocabulary(&instruments);
+    let queued: HashSet<(String, Option<String>)> = draft_files::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|file| (file.path, file.entry))
+        .collect();
+
+    let groups = find_groups(inbox, &vocabulary)
+        .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string())))?;
+
+    let mut added_drafts = 0;
+    let mut added_files = 0;
+
+    for group in groups {
+        let files: Vec<Found> = group
+            .files
+            .into_iter()
+            .filter(|found| {
+                let key = (
+                    found.path.to_string_lossy().to_string(),
+                    found.entry.clone(),
+                );
+                !queued.contains(&key)
+            })
+            .collect();
+        if files.is_empty() {
+            continue;
+        }
+
+        let source = group
+            .source
+            .as_ref()
+            .map(|source| source.to_string_lossy().to_string());
+        let draft_id = match find_pending(db, &group.title, &source).await? {
+            Some(draft) => draft.id,
+            None => {
+                let active_draft = drafts::ActiveModel {
+                    title: ActiveValue::Set(group.title.clone()),
+                    source: ActiveValue::Set(source.clone()),
+                    status: ActiveValue::Set(String::from(PENDING)),
+                    ..Default::default()
+                };
+                added_drafts += 1;
+                drafts::Entity::insert(active_draft)
+                    .exec(db)
+                    .await?
+                    .last_insert_id
+            }
+        };
+
+        for found in files {
+            // files in a folder or archive only have to name their part
+            let part_name = match &source {
+                Some(_) => split_name(&found.stem, &vocabulary)
+                    .1
+                    .unwrap_or_else(|| found.stem.clone()),
+                None => split_name(&found.stem, &vocabulary)
+                    .1
+                    .unwrap_or_else(|| String::from("Score")),
+            };
+            let is_score = is_score_name(&part_name);
+            let instrument_id = match is_score {
+                true => None,
+                false => guess(&part_name, &instruments).map(|instrument| instrument.id),
+            };
+
+            let active_file = draft_files::ActiveModel {
+                draft_id: ActiveValue::Set(draft_id),
+                path: ActiveValue::Set(found.path.to_string_lossy().to_string()),
+                entry: ActiveValue::Set(found.entry),
+                name: ActiveValue::Set(part_name),
+                kind: ActiveValue::Set(String::from(match is_score {
+                    true => "score",
+                    false => "part",
+                })),
+                instrument_id: ActiveValue::Set(instrument_id),
+                ..Default::default()
+            };
+            draft_files::Entity::insert(active_file).exec(db).await?;
+            added_files += 1;
+        }
+    }
+
+    Ok(serde_json::json!({
+        "drafts": added_drafts,
+        "files": added_files,
+        "removed": removed,
+    }))
+}
+
+pub async fn update(db: &DatabaseConnection, id: i32, title: String) -> Result<(), DbErr> {
+    let draft = find_draft(db, id).await?;
+
+    let mut draft: drafts::ActiveModel = draft.into();
+    draft.title = ActiveValue::Set(title);
+    draft.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+    drafts::Entity::update(draft).exec(db).await?;
+
+    Ok(())
+}
+
+pub async fn update_file(
+    db: &DatabaseConnection,
+    id: i32,
+    name: String,
+    kind: String,
+    instrument_id: Option<i32>,
+) -> Result<(), DbErr> {
+    if !FILE_KINDS.contains(&kind.as_str()) {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "File kind {} is not one of {}",
+            kind,
+            FILE_KINDS.join(", ")
+        ))));
+    }
+
+    let file = draft_files::Entity::find_by_id(id).one(db).await?;
+    match file {
+        Some(file) => {
+            let mut file: draft_files::ActiveModel = file.into();
+            file.name = ActiveValue::Set(name);
+            file.kind = ActiveValue::Set(kind);
+            file.instrument_id = ActiveValue::Set(instrument_id);
+            file.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+            draft_files::Entity::update(file).exec(db).await?;
+
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Draft file with id {} not found",
+            id
+        ))),
+    }
+}
+
+/// Moves a file to another draft, or to a draft of its own when the grouping was wrong.
+pub async fn move_file(
+    db: &DatabaseConnection,
+    id: i32,
+    draft_id: Option<i32>,
+) -> Result<i32, DbErr> {
+    let file = draft_files::Entity::find_by_id(id).one(db).await?;
+    let file = match file {
+        Some(file) => file,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Draft file with id {} not found",
+                id
+            )))
+        }
+    };
+
+    let draft_id = match draft_id {
+        Some(draft_id) => find_draft(db, draft_id).await?.id,
+        None => {
+            let from = find_draft(db, file.draft_id).await?;
+            let active_draft = drafts::ActiveModel {
+                title: ActiveValue::Set(from.title),
+                status: ActiveValue::Set(String::from(PENDING)),
+                ..Default::default()
+            };
+            drafts::Entity::insert(active_draft)
+                .exec(db)
+                .await?
+                .last_insert_id
+        }
+    };
+
+    let from_id = file.draft_id;
+    let mut file: draft_files::ActiveModel = file.into();
+    file.draft_id = ActiveValue::Set(draft_id);
+    file.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+    draft_files::Entity::update(file).exec(db).await?;
+
+    remove_if_empty(db, from_id).await?;
+
+    Ok(draft_id)
+}
+
+pub async fn dismiss(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    let draft = find_draft(db, id).await?;
+
+    let mut draft: drafts::ActiveModel = draft.into();
+    draft.status = ActiveValue::Set(String::from(DISMISSED));
+    draft.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+    drafts::Entity::update(draft).exec(db).await?;
+
+    Ok(())
+}
+
+/// Files a draft into the library as a new piece, named by the templates,
+/// and removes its files from the inbox. Returns the id of the new piece.
+pub async fn file(
+    db: &DatabaseConnection,
+    id: i32,
+    working_directory: &Path,
+    templates: &Templates,
+    composer_id: Option<i32>,
+) -> Result<i32, DbErr> {
+    let draft = find_draft(db, id).await?;
+    if draft.status == FILED {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Draft {} was already filed",
+            draft.title
+        ))));
+    }
+    let mut files = draft_files::Entity::find()
+        .filter(draft_files::Column::DraftId.eq(id))
+        .order_by_asc(draft_files::Column::Id)
+        .all(db)
+        .await?;
+    if files.is_empty() {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Draft {} has no files",
+            draft.title
+        ))));
+    }
+    // scores come first, like in the edit wizard
+    files.sort_by_key(|file| file.kind != "score");
+
+    let composer = match composer_id {
+        Some(composer_id) => {
+            let composer = musicians::Entity::find_by_id(composer_id).one(db).await?;
+            match composer {
+                Some(composer) => Some(composer),
+                None => {
+                    return Err(DbErr::RecordNotFound(format!(
+                        "Musician with id {} not found",
+                        composer_id
+                    )))
+                }
+            }
+        }
+        None => None,
+    };
+
+    let piece_id = pieces::add(
+        db,
+        draft.title.clone(),
+        None,
+        String::new(),
+        None,
+        String::new(),
+    )
+    .await?;
+
+    // leave nothing half filed behind
+    let result = file_piece(
+        db,
+        piece_id,
+        working_directory,
+        templates,
+        &files,
+        &composer,
+    )
+    .await;
+    if let Err(e) = result {
+        pieces::delete(db, piece_id).await?;
+        return Err(e);
+    }
+
+    let io_err = |e: io::Error| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string()));
+    let mut paths: Vec<&str> = files.iter().map(|file| file.path.as_str()).collect();
+    paths.sort();
+    paths.dedup();
+
+    // an archive can hold the files of several drafts and stays until the last is filed
+    let mut removed_paths: Vec<&str> = vec![];
+    for path in paths {
+        if is_needed_elsewhere(db, id, path).await? {
+            continue;
+        }
+        if Path::new(path).exists() {
+            fs::remove_file(path).map_err(io_err)?;
+        }
+        removed_paths.push(path);
+    }
+
+    // a folder dropped into the inbox goes once it's empty, the inbox itself stays
+    let source = draft.source.as_deref().map(Path::new);
+    if let Some(source) = source.filter(|source| source.is_dir()) {
+        for file_path in &removed_paths {
+            let mut folder = Path::new(file_path).parent();
+            while let Some(path) = folder.filter(|path| path.starts_with(source)) {
+                pieces::remove_dir_if_empty(path);
+                folder = path.parent();
+            }
+        }
+    }
+
+    // drafts filed earlier from an archive that is gone now have nothing left to keep
+    remove_filed(db, &removed_paths).await?;
+
+    let kept = files
+        .iter()
+        .any(|file| !removed_paths.contains(&file.path.as_str()));
+    match kept {
+        true => {
+            let mut draft: drafts::ActiveModel = draft.into();
+            draft.status = ActiveValue::Set(String::from(FILED));
+            draft.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+            drafts::Entity::update(draft).exec(db).await?;
+        }
+        false => {
+            drafts::Entity::delete_by_id(id).exec(db).await?;
+        }
+    }
+
+    Ok(piece_id)
+}
+
+async fn file_piece(
+    db: &DatabaseConnection,
+    piece_id: i32,
+    working_directory: &Path,
+    templates: &Templates,
+    files: &[draft_files::Model],
+    composer: &Option<musicians::Model>,
+) -> Result<(), DbErr> {
+    let io_err = |e: io::Error| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string()));
+
+    if let Some(composer) = composer {
+        pieces::set_musicians(
+            db,
+            piece_id,
+            vec![composer.id],
+            String::from("composer"),
+            working_directory,
+            templates,
+        )
+        .await?;
+    }
+
+    let folder = pieces::move_to_library_folder(db, working_directory, templates, piece_id).await?;
+
+    let names = pieces::library_names(db, piece_id).await?;
+    let instruments = instruments::Entity::find().all(db).await?;
+
+    let mut score_count = 0;
+    let mut part_count = 0;
+
+    for file in files {
+        let is_score = file.kind == "score";
+        let number = match is_score {
+            true => {
+                score_count += 1;
+                score_count
+            }
+            false => {
+                part_count += 1;
+                part_count
+            }
+        };
+        let instrument = instruments
+            .iter()
+            .find(|instrument| Some(instrument.id) == file.instrument_id);
+        let destination = folder.join(templates.file(
+            &names,
+            &FileNames {
+                is_score,
+                order: number,
+                name: file.name.clone(),
+                instrument: instrument.map(|instrument| instrument.name.clone()),
+            },
+        ));
+        copy_out(file, &destination).map_err(io_err)?;
+
+        let path = destination.to_str().map(|path| path.to_string());
+        match is_score {
+            true => {
+                scores::add(db, file.name.clone(), path, piece_id).await?;
+            }
+            false => {
+                let part_id = parts::add(db, file.name.clone(), path, piece_id).await?;
+                let instrument_ids = file.instrument_id.into_iter().collect();
+                parts::set_instruments(db, part_id, instrument_ids).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Copies a file out of the inbox, unpacking it when it is in an archive.
+fn copy_out(file: &draft_files::Model, destination: &Path) -> io::Result<()> {
+    match &file.entry {
+        Some(entry) => {
+            let mut archive = zip::ZipArchive::new(fs::File::open(&file.path)?)?;
+            let mut entry = archive.by_name(entry)?;
+            let mut destination = fs::File::create(destination)?;
+            io::copy(&mut entry, &mut destination)?;
+        }
+        None => {
+            fs::copy(&file.path, destination)?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn find_draft(db: &DatabaseConnection, id: i32) -> Result<drafts::Model, DbErr> {
+    let draft = drafts::Entity::find_by_id(id).one(db).await?;
+    match draft {
+        Some(draft) => Ok(draft),
+        None => Err(DbErr::RecordNotFound(format!(
+            "Draft with id {} not found",
+            id
+        ))),
+    }
+}
+
+/// Finds the pending draft new files of a group go to: the one from the same
+/// folder or archive, or for loose files the one with the same title.
+async fn find_pending(
+    db: &DatabaseConnection,
+    title: &str,
+    source: &Option<String>,
+) -> Result<Option<drafts::Model>, DbErr> {
+    let pending = drafts::Entity::find()
+        .filter(drafts::Column::Status.eq(PENDING))
+        .all(db)
+        .await?;
+
+    Ok(pending.into_iter().find(|draft| match source {
+        Some(_) => draft.source == *source,
+        None => draft.source.is_none() && draft.title.to_lowercase() == title.to_lowercase(),
+    }))
+}
+
+/// Whether a file is still queued in a draft other than the one being filed,
+/// e.g. an archive whose entries were moved to drafts of their own.
+async fn is_needed_elsewhere(
+    db: &DatabaseConnection,
+    draft_id: i32,
+    path: &str,
+) -> Result<bool, DbErr> {
+    let draft_ids: Vec<i32> = draft_files::Entity::find()
+        .filter(draft_files::Column::Path.eq(path))
+        .filter(draft_files::Column::DraftId.ne(draft_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|file| file.draft_id)
+        .collect();
+    if draft_ids.is_empty() {
+        return Ok(false);
+    }
+
+    let drafts = drafts::Entity::find()
+        .filter(drafts::Column::Id.is_in(draft_ids))
+        .filter(drafts::Column::Status.ne(FILED))
+        .all(db)
+        .await?;
+    Ok(!drafts.is_empty())
+}
+
+async fn remove_filed(db: &DatabaseConnection, paths: &[&str]) -> Result<(), DbErr> {
+    let draft_ids: Vec<i32> = draft_files::Entity::find()
+        .filter(draft_files::Column::Path.is_in(paths.iter().map(|path| path.to_string())))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|file| file.draft_id)
+        .collect();
+
+    drafts::Entity::delete_many()
+        .filter(drafts::Column::Id.is_in(draft_ids))
+        .filter(drafts::Column::Status.eq(FILED))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Removes queued files that are no longer in the inbox, and drafts left without files.
+async fn remove_missing(db: &DatabaseConnection) -> Result<usize, DbErr> {
+    let files = draft_files::Entity::find().all(db).await?;
+    let missing: Vec<&draft_files::Model> = files
+        .iter()
+        .filter(|file| !Path::new(&file.path).exists())
+        .collect();
+
+    draft_files::Entity::delete_many()
+        .filter(draft_files::Column::Id.is_in(missing.iter().map(|file| file.id)))
+        .exec(db)
+        .await?;
+
+    let mut draft_ids: Vec<i32> = missing.iter().map(|file| file.draft_id).collect();
+    draft_ids.dedup();
+    let mut removed = 0;
+    for draft_id in draft_ids {
+        if remove_if_empty(db, draft_id).await? {
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+async fn remove_if_empty(db: &DatabaseConnection, draft_id: i32) -> Result<bool, DbErr> {
+    let files = draft_files::Entity::find()
+        .filter(draft_files::Column::DraftId.eq(draft_id))
+        .all(db)
+        .await?;
+    if !files.is_empty() {
+        return Ok(false);
+    }
+
+    drafts::Entity::delete_by_id(draft_id).exec(db).await?;
+    Ok(true)
+}
+
+fn find_groups(inbox: &Path, vocabulary: &Vocabulary) -> io::Result<Vec<Group>> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(inbox)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    entries.sort();
+
+    let mut groups: Vec<Group> = vec![];
+
+    for path in entries {
+        let stem = stem(&path);
+        if stem.starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let mut files = vec![];
+            find_pdfs(&path, &mut files)?;
+            groups.push(Group {
+                title: name.replace('_', " ").trim().to_string(),
+                source: Some(path),
+                files,
+            });
+            continue;
+        }
+
+        match extension(&path).as_str() {
+            "zip" => {
+                // an archive that can't be read is left for the next scan
+                let files = match find_zipped_pdfs(&path) {
+                    Ok(files) => files,
+                    Err(_) => continue,
+                };
+                groups.push(Group {
+                    title: stem.replace('_', " ").trim().to_string(),
+                    source: Some(path),
+                    files,
+                });
+            }
+            "pdf" => {
+                let (title, _) = split_name(&stem, vocabulary);
+                let found = Found {
+                    path: path.clone(),
+                    entry: None,
+                    stem,
+                };
+
+                let group = groups.iter_mut().find(|group| {
+                    group.source.is_none() && group.title.to_lowercase() == title.to_lowercase()
+                });
+                match group {
+                    Some(group) => group.files.push(found),
+                    None => groups.push(Group {
+                        title,
+                        source: None,
+                        files: vec![found],
+                    }),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(groups)
+}
+
+fn find_pdfs(folder: &Path, files: &mut Vec<Found>) -> io::Result<()> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(folder)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            find_pdfs(&path, files)?;
+        } else if extension(&path) == "pdf" {
+            files.push(Found {
+                stem: stem(&path),
+                path,
+                entry: None,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn find_zipped_pdfs(path: &Path) -> io::Result<Vec<Found>> {
+    let archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+
+    let mut names: Vec<&str> = archive
+        .file_names()
+        // resource forks macOS puts in archives
+        .filter(|name| !name.starts_with("__MACOSX/"))
+        .filter(|name| extension(Path::new(name)) == "pdf")
+        .collect();
+    names.sort();
+
+    Ok(names
+        .into_iter()
+        .map(|name| Found {
+            path: path.to_path_buf(),
+            entry: Some(name.to_string()),
+            stem: stem(Path::new(name)),
+        })
+        .collect())
+}
+
+fn vocabulary(instruments: &[instruments::Model]) -> Vocabulary {
+    let mut kinds: HashSet<String> = SCORE_WORDS.iter().map(|word| word.to_string()).collect();
+    let mut words: HashSet<String> = HashSet::from([String::from("in"), String::from("part")]);
+
+    for instrument in instruments {
+        for word in name_words(&instrument.name) {
+            match word == "in" || is_key(&word) {
+                true => words.insert(word),
+                false => kinds.insert(word),
+            };
+        }
+    }
+
+    Vocabulary { kinds, words }
+}
+
+/// Splits a file name into the title of the piece and the part, e.g.
+/// "Suite in F - Clarinet 1" or "Suite_in_F_Bb_Clarinet_1" into "Suite in F"
+/// and "Clarinet 1". Names that don't end in a part only have a title.
+fn split_name(stem: &str, vocabulary: &Vocabulary) -> (String, Option<String>) {
+    let stem = stem.replace('_', " ");
+
+    let is_part = |words: &[&str]| {
+        let words: Vec<String> = words.iter().flat_map(|word| name_words(word)).collect();
+        words.iter().any(|word| vocabulary.kinds.contains(word))
+            && words
+                .iter()
+                .all(|word| vocabulary.kinds.contains(word) || vocabulary.words.contains(word))
+    };
+
+    if let Some((title, part)) = stem.rsplit_once(" - ") {
+        if !title.trim().is_empty() && is_part(&part.split_whitespace().collect::<Vec<&str>>()) {
+            return (title.trim().to_string(), Some(part.trim().to_string()));
+        }
+    }
+
+    let words: Vec<&str> = stem.split_whitespace().collect();
+    for start in 1..words.len() {
+        let starts_part = name_words(words[start])
+            .iter()
+            .any(|word| vocabulary.kinds.contains(word));
+        if !starts_part || !is_part(&words[start..]) {
+            continue;
+        }
+
+        // take a key before the instrument along, unless it is the key of the piece
+        let mut start = start;
+        let is_key_word = |word: &str| {
+            let words = name_words(word);
+            !words.is_empty() && words.iter().all(|word| is_key(word))
+        };
+        if start > 1
+            && is_key_word(words[start - 1])
+            && !words[start - 2].eq_ignore_ascii_case("in")
+        {
+            start -= 1;
+        }
+
+        let title = words[..start].join(" ");
+        let title = title.trim_end_matches(|c: char| c == '-' || c.is_whitespace());
+        return (title.to_string(), Some(words[start..].join(" ")));
+    }
+
+    (stem.trim().to_string(), None)
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+
+    #[tokio::test]
+    async fn test_split_name() {
+        let db = init().await.unwrap();
+        let instruments = instruments::Entity::find().all(&db).await.unwrap();
+        let vocabulary = vocabulary(&instruments);
+
+        let split = |stem: &str| split_name(stem, &vocabulary);
+        assert_eq!(
+            split("Suite in F - Clarinet 1"),
+            (String::from("Suite in F"), Some(String::from("Clarinet 1")))
+        );
+        assert_eq!(
+            split("Suite_in_F_Bb_Clarinet_1"),
+            (
+                String::from("Suite in F"),
+                Some(String::from("Bb Clarinet 1"))
+            )
+        );
+        assert_eq!(
+            split("Horn Concerto Horn in F"),
+            (
+                String::from("Horn Concerto"),
+                Some(String::from("Horn in F"))
+            )
+        );
+        assert_eq!(
+            split("Suite in F Full Score"),
+            (String::from("Suite in F"), Some(String::from("Full Score")))
+        );
+        assert_eq!(split("Suite in F"), (String::from("Suite in F"), None));
+
+        let _ = db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_file_from_shared_archive() {
+        use std::io::Write;
+
+        let db = init().await.unwrap();
+
+        let directory = std::env::temp_dir().join("blackbird test inbox archive");
+        let inbox = directory.join("inbox");
+        let library = directory.join("library");
+        fs::create_dir_all(&inbox).unwrap();
+        fs::create_dir_all(&library).unwrap();
+
+        let archive = inbox.join("Shared Archive March.zip");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        for name in ["Flute.pdf", "Tuba.pdf"] {
+            zip.start_file(name, zip::write::FileOptions::default())
+                .unwrap();
+            zip.write_all(b"not really a pdf").unwrap();
+        }
+        zip.finish().unwrap();
+
+        scan(&db, &inbox).await.unwrap();
+        let files = draft_files::Entity::find()
+            .filter(draft_files::Column::Path.eq(archive.to_string_lossy().to_string()))
+            .order_by_asc(draft_files::Column::Id)
+            .all(&db)
+            .await
+            .unwrap();
+        assert_eq!(files.len(), 2);
+
+        // the tuba part turns out to be another piece
+        let first_id = files[0].draft_id;
+        let second_id = move_file(&db, files[1].id, None).await.unwrap();
+
+        let templates = Templates::default();
+        let first_piece = file(&db, first_id, &library, &templates, None)
+            .await
+            .unwrap();
+        assert!(archive.exists());
+        assert!(file(&db, first_id, &library, &templates, None)
+            .await
+            .is_err());
+
+        // entries of a filed draft aren't queued again
+        let report = scan(&db, &inbox).await.unwrap();
+        assert_eq!(report["files"], 0);
+
+        let second_piece = file(&db, second_id, &library, &templates, None)
+            .await
+            .unwrap();
+        assert!(!archive.exists());
+        assert!(drafts::Entity::find_by_id(first_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .is_none());
+
+        pieces::delete(&db, first_piece).await.unwrap();
+        pieces::delete(&db, second_piece).await.unwrap();
+        let _ = fs::remove_dir_all(&directory);
+
+        let _ = db.close().await;
+    }
+}
@@ -1,11 +1,58 @@
 use sea_orm::{
-    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, TransactionTrait,
 };
 
-use crate::entities::instruments;
+use crate::entities::{ensemble_parts_instruments, instruments, parts_instruments};
 
+use serde::Deserialize;
 use serde_json::Value;
 
+/// Abbreviations often found in part names, e.g. "Tpt 1".
+const ABBREVIATIONS: [(&str, &str); 14] = [
+    ("picc", "piccolo"),
+    ("fl", "flute"),
+    ("ob", "oboe"),
+    ("cl", "clarinet"),
+    ("clar", "clarinet"),
+    ("bsn", "bassoon"),
+    ("sax", "saxophone"),
+    ("hn", "horn"),
+    ("tpt", "trumpet"),
+    ("tbn", "trombone"),
+    ("euph", "euphonium"),
+    ("perc", "percussion"),
+    ("timp", "timpani"),
+    ("vln", "violin"),
+];
+
+/// Keys transposing instruments are named by, most common first.
+const KEYS: [&str; 12] = [
+    "bb", "f", "eb", "c", "a", "d", "ab", "g", "e", "db", "gb", "b",
+];
+
+/// What a part that only names the kind of instrument is usually for, e.g. "Trombone 2".
+const USUAL_INSTRUMENTS: [&str; 5] = [
+    "Tenor Trombone",
+    "Alto Saxophone",
+    "Clarinet in Bb",
+    "Trumpet in Bb",
+    "Horn in F",
+];
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct InstrumentMetadata {
+    /// Semitones from written to sounding pitch, e.g. -2 for an instrument in Bb.
+    pub transposition: Option<i32>,
+    /// Comma separated, e.g. "treble" or "treble,bass".
+    pub clefs: Option<String>,
+    /// Sounding range in scientific pitch notation, e.g. "E3".
+    pub range_low: Option<String>,
+    pub range_high: Option<String>,
+    /// Instruments of the same family can cover each other's parts.
+    pub family: Option<String>,
+    pub score_order: Option<i32>,
+}
+
 pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<Value>, DbErr> {
     let instruments = instruments::Entity::find().into_json().all(db).await?;
 
@@ -79,7 +126,8 @@ pub async fn update(
 
             instrument.name = ActiveValue::Set(name);
             instrument.category = ActiveValue::Set(category);
-            instrument.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+            instrument.updated_at =
+                ActiveValue::Set(chrono::Local::now().naive_local().to_string());
 
             let result = instruments::Entity::update(instrument).exec(db).await;
 
@@ -100,11 +148,432 @@ pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
     Ok(())
 }
 
+pub async fn set_metadata(
+    db: &DatabaseConnection,
+    id: i32,
+    metadata: InstrumentMetadata,
+) -> Result<(), DbErr> {
+    for pitch in [&metadata.range_low, &metadata.range_high]
+        .into_iter()
+        .flatten()
+    {
+        if pitch_to_midi(pitch).is_none() {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "{} is not a pitch in scientific pitch notation",
+                pitch
+            ))));
+        }
+    }
+
+    if let (Some(low), Some(high)) = (&metadata.range_low, &metadata.range_high) {
+        if pitch_to_midi(low) > pitch_to_midi(high) {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "Range low {} is above range high {}",
+                low, high
+            ))));
+        }
+    }
+
+    let instrument = instruments::Entity::find_by_id(id).one(db).await?;
+    match instrument {
+        Some(instrument) => {
+            let mut instrument: instruments::ActiveModel = instrument.into();
+
+            instrument.transposition = ActiveValue::Set(metadata.transposition);
+            instrument.clefs = ActiveValue::Set(metadata.clefs);
+            instrument.range_low = ActiveValue::Set(metadata.range_low);
+            instrument.range_high = ActiveValue::Set(metadata.range_high);
+            instrument.family = ActiveValue::Set(metadata.family);
+            instrument.score_order = ActiveValue::Set(metadata.score_order);
+            instrument.updated_at =
+                ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            instruments::Entity::update(instrument).exec(db).await?;
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Instrument with id {} not found",
+            id
+        ))),
+    }
+}
+
+/// Returns the other instruments of the same family, each with the interval a
+/// player has to transpose a part for this instrument by to read it.
+pub async fn get_substitutes(db: &DatabaseConnection, id: i32) -> Result<Vec<Value>, DbErr> {
+    let instrument = instruments::Entity::find_by_id(id).one(db).await?;
+    let instrument = match instrument {
+        Some(instrument) => instrument,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Instrument with id {} not found",
+                id
+            )))
+        }
+    };
+
+    let family = match &instrument.family {
+        Some(family) => family.clone(),
+        None => return Ok(vec![]),
+    };
+
+    let mut substitutes = instruments::Entity::find()
+        .filter(instruments::Column::Family.eq(family))
+        .filter(instruments::Column::Id.ne(id))
+        .all(db)
+        .await?;
+    substitutes.sort_by_key(score_order_key);
+
+    let substitutes = substitutes
+        .iter()
+        .map(|substitute| {
+            serde_json::json!({
+                "id": substitute.id,
+                "name": substitute.name,
+                "family": substitute.family,
+                "transposition": substitute.transposition,
+                "transpose_by": transpose_by(&instrument, substitute),
+            })
+        })
+        .collect();
+
+    Ok(substitutes)
+}
+
+/// Semitones a part written for `from` has to be shifted by to be read on `to`,
+/// or `None` when the two can't stand in for each other.
+pub fn transpose_by(from: &instruments::Model, to: &instruments::Model) -> Option<i32> {
+    if from.id == to.id {
+        return Some(0);
+    }
+
+    match (&from.family, &to.family) {
+        (Some(from_family), Some(to_family)) if from_family == to_family => {
+            Some(from.transposition? - to.transposition?)
+        }
+        _ => None,
+    }
+}
+
+/// Sorts instruments in standard score order, leaving unordered ones at the end.
+pub fn score_order_key(instrument: &instruments::Model) -> (i32, i32) {
+    (instrument.score_order.unwrap_or(i32::MAX), instrument.id)
+}
+
+/// Converts scientific pitch notation such as "C4", "Bb3" or "F#5" to a MIDI note number.
+pub fn pitch_to_midi(pitch: &str) -> Option<i32> {
+    let pitch = pitch.trim();
+    let mut chars = pitch.chars();
+
+    let step = match chars.next()?.to_ascii_uppercase() {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+
+    let rest = chars.as_str();
+    let octave_start = rest.find(|c: char| c.is_ascii_digit() || c == '-')?;
+    let (accidentals, octave) = rest.split_at(octave_start);
+
+    let mut alteration = 0;
+    for accidental in accidentals.chars() {
+        match accidental {
+            'b' | '♭' => alteration -= 1,
+            '#' | '♯' => alteration += 1,
+            _ => return None,
+        }
+    }
+
+    let octave: i32 = octave.parse().ok()?;
+    Some((octave + 1) * 12 + step + alteration)
+}
+
+/// Guesses the instrument a part is for from its name, e.g. "Clarinet 2" is for
+/// the Clarinet in Bb and "Bass Clarinet" for the Bass Clarinet.
+///
+/// The instrument whose name shares the most words with the part wins. When
+/// none matches completely, the usual instrument of the kind the part names is taken.
+pub fn guess<'a>(
+    part_name: &str,
+    instruments: &'a [instruments::Model],
+) -> Option<&'a instruments::Model> {
+    let words = name_words(part_name);
+    let key = words.iter().find(|word| is_key(word));
+
+    let candidates: Vec<(Vec<String>, Option<String>, &instruments::Model)> = instruments
+        .iter()
+        .map(|instrument| {
+            let (base, instrument_key) = split_key(&instrument.name);
+            (base, instrument_key, instrument)
+        })
+        .filter(|(base, _, _)| !base.is_empty())
+        // a part in Eb isn't for the instrument in Bb
+        .filter(|(_, instrument_key, _)| match (key, instrument_key) {
+            (Some(key), Some(instrument_key)) => key == instrument_key,
+            _ => true,
+        })
+        .collect();
+
+    let key_rank = |instrument_key: &Option<String>| match instrument_key {
+        Some(instrument_key) => KEYS
+            .iter()
+            .position(|key| *key == instrument_key.as_str())
+            .unwrap_or(KEYS.len()),
+        None => 0,
+    };
+
+    let complete = candidates
+        .iter()
+        .filter(|(base, _, _)| base.iter().all(|word| words.contains(word)))
+        .min_by(|(a_base, a_key, _), (b_base, b_key, _)| {
+            b_base
+                .len()
+                .cmp(&a_base.len())
+                .then(key_rank(a_key).cmp(&key_rank(b_key)))
+        });
+    if let Some((_, _, instrument)) = complete {
+        return Some(*instrument);
+    }
+
+    // the last word names the kind of instrument, e.g. "Tenor Trombone"
+    candidates
+        .iter()
+        .filter(|(base, _, _)| base.last().is_some_and(|kind| words.contains(kind)))
+        .min_by_key(|(base, instrument_key, instrument)| {
+            let usual = USUAL_INSTRUMENTS
+                .iter()
+                .position(|name| *name == instrument.name)
+                .unwrap_or(USUAL_INSTRUMENTS.len());
+            (usual, base.len(), key_rank(instrument_key))
+        })
+        .map(|(_, _, instrument)| *instrument)
+}
+
+/// Whether a word of a name is a key such as "bb" or "f".
+pub fn is_key(word: &str) -> bool {
+    KEYS.contains(&word)
+}
+
+/// Splits a name into lowercase words, spelling out abbreviations and flats,
+/// so "B♭ Tpt. 2" becomes "bb trumpet". Part numbers are left out.
+pub fn name_words(name: &str) -> Vec<String> {
+    let name = name
+        .to_lowercase()
+        .replace('♭', "b")
+        .replace("-flat", "b")
+        .replace(" flat", "b");
+
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !is_part_number(word))
+        .map(|word| {
+            ABBREVIATIONS
+                .iter()
+                .find(|(abbreviation, _)| *abbreviation == word)
+                .map_or(word, |(_, full)| *full)
+                .to_string()
+        })
+        .collect()
+}
+
+/// Folds the duplicates into the survivor, re-pointing every part and ensemble
+/// part that uses them. Default instruments can only ever be survivors.
+pub async fn merge(
+    db: &DatabaseConnection,
+    survivor_id: i32,
+    duplicate_ids: Vec<i32>,
+) -> Result<(), DbErr> {
+    let survivor = instruments::Entity::find_by_id(survivor_id).one(db).await?;
+    if survivor.is_none() {
+        return Err(DbErr::RecordNotFound(format!(
+            "Instrument with id {} not found",
+            survivor_id
+        )));
+    }
+
+    let duplicate_ids: Vec<i32> = duplicate_ids
+        .into_iter()
+        .filter(|id| *id != survivor_id)
+        .collect();
+
+    let duplicates = instruments::Entity::find()
+        .filter(instruments::Column::Id.is_in(duplicate_ids.clone()))
+        .all(db)
+        .await?;
+    if let Some(duplicate) = duplicates.iter().find(|duplicate| duplicate.is_default) {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Cannot merge instrument with id {} because it is a default",
+            duplicate.id
+        ))));
+    }
+
+    let txn = db.begin().await?;
+
+    let part_instruments = parts_instruments::Entity::find()
+        .filter(parts_instruments::Column::InstrumentId.is_in(duplicate_ids.clone()))
+        .all(&txn)
+        .await?;
+
+    for part_instrument in part_instruments {
+        let existing =
+            parts_instruments::Entity::find_by_id((part_instrument.part_id, survivor_id))
+                .one(&txn)
+                .await?;
+
+        parts_instruments::Entity::delete_by_id((
+            part_instrument.part_id,
+            part_instrument.instrument_id,
+        ))
+        .exec(&txn)
+        .await?;
+
+        if existing.is_none() {
+            let active_part_instrument = parts_instruments::ActiveModel {
+                part_id: ActiveValue::Set(part_instrument.part_id),
+                instrument_id: ActiveValue::Set(survivor_id),
+            };
+
+            parts_instruments::Entity::insert(active_part_instrument)
+                .exec(&txn)
+                .await?;
+        }
+    }
+
+    let ensemble_part_instruments = ensemble_parts_instruments::Entity::find()
+        .filter(ensemble_parts_instruments::Column::InstrumentId.is_in(duplicate_ids.clone()))
+        .all(&txn)
+        .await?;
+
+    for part_instrument in ensemble_part_instruments {
+        let existing =
+            ensemble_parts_instruments::Entity::find_by_id((part_instrument.part_id, survivor_id))
+                .one(&txn)
+                .await?;
+
+        ensemble_parts_instruments::Entity::delete_by_id((
+            part_instrument.part_id,
+            part_instrument.instrument_id,
+        ))
+        .exec(&txn)
+        .await?;
+
+        if existing.is_none() {
+            let active_part_instrument = ensemble_parts_instruments::ActiveModel {
+                part_id: ActiveValue::Set(part_instrument.part_id),
+                instrument_id: ActiveValue::Set(survivor_id),
+            };
+
+            ensemble_parts_instruments::Entity::insert(active_part_instrument)
+                .exec(&txn)
+                .await?;
+        }
+    }
+
+    instruments::Entity::delete_many()
+        .filter(instruments::Column::Id.is_in(duplicate_ids))
+        .exec(&txn)
+        .await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Splits an instrument name into the words that describe it and the key it is in.
+fn split_key(name: &str) -> (Vec<String>, Option<String>) {
+    let words = name_words(name);
+    let key = words.iter().find(|word| is_key(word)).cloned();
+    let base = words
+        .into_iter()
+        .filter(|word| word != "in" && !is_key(word))
+        .collect();
+
+    (base, key)
+}
+
+/// Numbers that tell parts for the same instrument apart, e.g. "2", "2nd" or "II".
+fn is_part_number(word: &str) -> bool {
+    let digits = word.trim_end_matches(char::is_alphabetic);
+    let is_number = !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && matches!(&word[digits.len()..], "" | "st" | "nd" | "rd" | "th");
+
+    is_number || matches!(word, "i" | "ii" | "iii" | "iv" | "vi")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::db::init;
 
+    #[test]
+    fn test_pitch_to_midi() {
+        assert_eq!(pitch_to_midi("C4"), Some(60));
+        assert_eq!(pitch_to_midi("A4"), Some(69));
+        assert_eq!(pitch_to_midi("Bb3"), Some(58));
+        assert_eq!(pitch_to_midi("F#5"), Some(78));
+        assert_eq!(pitch_to_midi("C-1"), Some(0));
+        assert_eq!(pitch_to_midi("H2"), None);
+        assert_eq!(pitch_to_midi("C"), None);
+    }
+
+    #[test]
+    fn test_name_words() {
+        assert_eq!(name_words("B♭ Tpt. 2"), vec!["bb", "trumpet"]);
+        assert_eq!(name_words("Horn in F 1st"), vec!["horn", "in", "f"]);
+        assert_eq!(
+            name_words("Clarinet in B-flat II"),
+            vec!["clarinet", "in", "bb"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_guess() {
+        let db = init().await.unwrap();
+        let instruments = instruments::Entity::find().all(&db).await.unwrap();
+
+        let guess =
+            |name: &str| guess(name, &instruments).map(|instrument| instrument.name.as_str());
+        assert_eq!(guess("Clarinet 2"), Some("Clarinet in Bb"));
+        assert_eq!(guess("Eb Clarinet"), Some("Clarinet in Eb"));
+        assert_eq!(guess("Bass Clarinet"), Some("Bass Clarinet"));
+        assert_eq!(guess("Tenor Sax"), Some("Tenor Saxophone"));
+        assert_eq!(guess("Trombone 1"), Some("Tenor Trombone"));
+        assert_eq!(guess("Horn 3"), Some("Horn in F"));
+        assert_eq!(guess("Full Score"), None);
+
+        let _ = db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_substitutes() {
+        let db = init().await.unwrap();
+
+        let trumpets = instruments::Entity::find()
+            .filter(instruments::Column::Name.is_in(["Trumpet in Bb", "Trumpet in C"]))
+            .all(&db)
+            .await
+            .unwrap();
+        let trumpet_in_bb = trumpets.iter().find(|t| t.name == "Trumpet in Bb").unwrap();
+        let trumpet_in_c = trumpets.iter().find(|t| t.name == "Trumpet in C").unwrap();
+
+        // a C part read on a Bb trumpet is played a whole step up
+        assert_eq!(transpose_by(trumpet_in_c, trumpet_in_bb), Some(2));
+        assert_eq!(transpose_by(trumpet_in_bb, trumpet_in_c), Some(-2));
+
+        let substitutes = get_substitutes(&db, trumpet_in_c.id).await.unwrap();
+        assert!(substitutes
+            .iter()
+            .any(|substitute| substitute["name"] == "Trumpet in Bb"));
+
+        let close_result = db.close().await;
+        assert!(close_result.is_ok());
+    }
+
     #[tokio::test]
     async fn test_get_all() {
         let db = init().await.unwrap();
@@ -175,4 +644,47 @@ mod tests {
         let close_result = db.close().await;
         assert!(close_result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_merge() {
+        let db = init().await.unwrap();
+
+        let piece_id = crate::services::pieces::add(
+            &db,
+            "Test Piece".to_string(),
+            None,
+            "test/path".to_string(),
+            None,
+            "".to_string(),
+        )
+        .await
+        .unwrap();
+        let part_id = crate::services::parts::add(&db, "Test Part".to_string(), None, piece_id)
+            .await
+            .unwrap();
+
+        let survivor_id = add(&db, String::from("Euphonium (custom)"), None, false)
+            .await
+            .unwrap();
+        let duplicate_id = add(&db, String::from("Euphonium (dup)"), None, false)
+            .await
+            .unwrap();
+
+        // both instruments on the same part collide on the composite key
+        crate::services::parts::set_instruments(&db, part_id, vec![survivor_id, duplicate_id])
+            .await
+            .unwrap();
+
+        let merge_result = merge(&db, survivor_id, vec![duplicate_id]).await;
+        assert!(merge_result.is_ok());
+
+        let part = crate::services::parts::get_by_id(&db, part_id)
+            .await
+            .unwrap();
+        assert_eq!(part["instruments"].as_array().unwrap().len(), 1);
+        assert!(get_by_id(&db, duplicate_id).await.is_err());
+
+        let close_result = db.close().await;
+        assert!(close_result.is_ok());
+    }
 }
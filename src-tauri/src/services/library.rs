@@ -0,0 +1,419 @@
+use sea_orm::{
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::entities::{instruments, parts, parts_instruments, pieces, scores};
+use crate::naming::{FileNames, Templates};
+use crate::services::part_files;
+use crate::services::pieces::{self as pieces_service, numbered};
+
+use serde_json::Value;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A score or part file and the name the templates give it.
+struct Rename {
+    is_score: bool,
+    id: i32,
+    from: PathBuf,
+    to: PathBuf,
+}
+
+/// Renames piece folders and their score and part files to match the templates,
+/// rewriting every stored path. Files kept outside their piece's folder stay where
+/// they are. With `dry_run` nothing changes and the report shows what would.
+pub async fn reorganize(
+    db: &DatabaseConnection,
+    working_directory: &Path,
+    templates: &Templates,
+    dry_run: bool,
+) -> Result<Value, DbErr> {
+    templates
+        .validate()
+        .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e)))?;
+
+    let pieces = pieces::Entity::find()
+        .order_by_asc(pieces::Column::Id)
+        .all(db)
+        .await?;
+    let instruments = instruments::Entity::find().all(db).await?;
+    let parts_instruments = parts_instruments::Entity::find().all(db).await?;
+
+    let mut report: Vec<Value> = vec![];
+    let mut failed: Vec<Value> = vec![];
+    let mut moved = 0;
+    let mut renamed = 0;
+
+    for piece in pieces {
+        // pieces without files have no folder to tidy
+        if piece.path.is_empty() {
+            continue;
+        }
+
+        let old_folder = PathBuf::from(&piece.path);
+        let folder =
+            pieces_service::library_folder(db, working_directory, templates, piece.id).await?;
+        let renames = get_renames(
+            db,
+            &piece,
+            &folder,
+            templates,
+            &instruments,
+            &parts_instruments,
+        )
+        .await?;
+        if folder == old_folder && renames.is_empty() {
+            continue;
+        }
+
+        if !dry_run {
+            if let Err(e) = apply(db, piece.id, &old_folder, &folder, &renames).await {
+                failed.push(serde_json::json!({
+                    "id": piece.id,
+                    "title": piece.title,
+                    "error": e.to_string(),
+                }));
+                continue;
+            }
+            if folder != old_folder {
+                moved += 1;
+            }
+            renamed += renames.len();
+        }
+
+        let files: Vec<Value> = renames
+            .iter()
+            .map(|rename| {
+                serde_json::json!({
+                    "from": rename.from,
+                    "to": rename.to,
+                })
+            })
+            .collect();
+        report.push(serde_json::json!({
+            "id": piece.id,
+            "title": piece.title,
+            "from": old_folder,
+            "to": folder,
+            "files": files,
+        }));
+    }
+
+    Ok(serde_json::json!({
+        "dry_run": dry_run,
+        "pieces": report,
+        "moved": moved,
+        "renamed": renamed,
+        "failed": failed,
+    }))
+}
+
+/// Renames the score and part files of one piece to match the templates, e.g.
+/// after the edit wizard added, renamed or removed some. The folder stays put.
+pub async fn rename_files(
+    db: &DatabaseConnection,
+    templates: &Templates,
+    piece_id: i32,
+) -> Result<(), DbErr> {
+    let piece = pieces::Entity::find_by_id(piece_id).one(db).await?;
+    let piece = match piece {
+        Some(piece) => piece,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Piece with id {} not found",
+                piece_id
+            )))
+        }
+    };
+    if piece.path.is_empty() {
+        return Ok(());
+    }
+
+    let folder = PathBuf::from(&piece.path);
+    let instruments = instruments::Entity::find().all(db).await?;
+    let parts_instruments = parts_instruments::Entity::find().all(db).await?;
+    let renames = get_renames(
+        db,
+        &piece,
+        &folder,
+        templates,
+        &instruments,
+        &parts_instruments,
+    )
+    .await?;
+
+    apply(db, piece.id, &folder, &folder, &renames).await
+}
+
+/// Works out the new name of every score and part file inside the piece folder,
+/// numbering them in the order they were added like the edit wizard does.
+async fn get_renames(
+    db: &DatabaseConnection,
+    piece: &pieces::Model,
+    folder: &Path,
+    templates: &Templates,
+    instruments: &[instruments::Model],
+    parts_instruments: &[parts_instruments::Model],
+) -> Result<Vec<Rename>, DbErr> {
+    let names = pieces_service::library_names(db, piece.id).await?;
+    let scores = scores::Entity::find()
+        .filter(scores::Column::PieceId.eq(piece.id))
+        .order_by_asc(scores::Column::Id)
+        .all(db)
+        .await?;
+    let parts = parts::Entity::find()
+        .filter(parts::Column::PieceId.eq(piece.id))
+        .order_by_asc(parts::Column::Id)
+        .all(db)
+        .await?;
+
+    let files = scores
+        .iter()
+        .enumerate()
+        .map(|(index, score)| {
+            let file = FileNames {
+                is_score: true,
+                order: index + 1,
+                name: score.name.clone(),
+                instrument: None,
+            };
+            (score.id, score.path.clone(), file)
+        })
+        .chain(parts.iter().enumerate().map(|(index, part)| {
+            let instrument = parts_instruments
+                .iter()
+                .find(|part_instrument| part_instrument.part_id == part.id)
+                .and_then(|part_instrument| {
+                    instruments
+                        .iter()
+                        .find(|instrument| instrument.id == part_instrument.instrument_id)
+                });
+            let file = FileNames {
+                is_score: false,
+                order: index + 1,
+                name: part.name.clone(),
+                instrument: instrument.map(|instrument| instrument.name.clone()),
+            };
+            (part.id, part.path.clone(), file)
+        }));
+
+    let old_folder = Path::new(&piece.path);
+    let mut renames = vec![];
+    let mut taken: Vec<PathBuf> = vec![];
+
+    for (id, path, file) in files {
+        let from = match path {
+            Some(path) if Path::new(&path).starts_with(old_folder) => PathBuf::from(path),
+            _ => continue,
+        };
+
+        // templates without {order} can give two files the same name
+        let to = numbered(&folder.join(templates.file(&names, &file)), |path| {
+            !taken.iter().any(|taken| taken == path)
+        });
+        taken.push(to.clone());
+
+        let moved_from = folder.join(from.strip_prefix(old_folder).unwrap_or(&from));
+        if moved_from != to {
+            renames.push(Rename {
+                is_score: file.is_score,
+                id,
+                from,
+                to,
+            });
+        }
+    }
+
+    Ok(renames)
+}
+
+async fn apply(
+    db: &DatabaseConnection,
+    piece_id: i32,
+    old_folder: &Path,
+    folder: &Path,
+    renames: &[Rename],
+) -> Result<(), DbErr> {
+    let io_err = |e: io::Error| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string()));
+
+    pieces_service::move_folder(db, piece_id, folder.to_path_buf()).await?;
+
+    // go through temporary names so files can swap names
+    let mut temporary: Vec<(&Rename, PathBuf, PathBuf)> = vec![];
+    for (index, rename) in renames.iter().enumerate() {
+        let from = folder.join(rename.from.strip_prefix(old_folder).unwrap_or(&rename.from));
+        if !from.exists() {
+            continue;
+        }
+
+        let temporary_path = folder.join(format!(".reorganizing {}.pdf", index));
+        if let Err(e) = fs::rename(&from, &temporary_path) {
+            restore(&temporary);
+            return Err(io_err(e));
+        }
+        temporary.push((rename, from, temporary_path));
+    }
+
+    for (index, (rename, from, temporary_path)) in temporary.iter().enumerate() {
+        // files the library doesn't know about keep their names
+        let to = numbered(&rename.to, |path| !path.exists());
+        if let Err(e) = fs::rename(temporary_path, &to) {
+            restore(&temporary[index..]);
+            return Err(io_err(e));
+        }
+        if let Err(e) = set_path(db, rename.is_score, rename.id, &to).await {
+            let _ = fs::rename(&to, from);
+            restore(&temporary[index + 1..]);
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Puts files parked under a temporary name back where they were, so a failed
+/// rename leaves no `.reorganizing` files behind and every stored path still works.
+fn restore(temporary: &[(&Rename, PathBuf, PathBuf)]) {
+    for (_, from, temporary_path) in temporary {
+        if let Err(e) = fs::rename(temporary_path, from) {
+            println!("library_restore_error: {}", e);
+        }
+    }
+}
+
+async fn set_path(
+    db: &DatabaseConnection,
+    is_score: bool,
+    id: i32,
+    path: &Path,
+) -> Result<(), DbErr> {
+    let path = path.to_str().map(|path| path.to_string());
+    let now = chrono::Local::now().naive_local().to_string();
+
+    match is_score {
+        true => {
+            if let Some(score) = scores::Entity::find_by_id(id).one(db).await? {
+                let mut score: scores::ActiveModel = score.into();
+                score.path = ActiveValue::Set(path);
+                score.updated_at = ActiveValue::Set(now);
+                scores::Entity::update(score).exec(db).await?;
+            }
+        }
+        false => {
+            if let Some(part) = parts::Entity::find_by_id(id).one(db).await? {
+                let mut part: parts::ActiveModel = part.into();
+                part.path = ActiveValue::Set(path.clone());
+                part.updated_at = ActiveValue::Set(now);
+                parts::Entity::update(part).exec(db).await?;
+                part_files::set_part_path(db, id, path.as_deref()).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+    use crate::services::{parts as parts_service, scores as scores_service};
+
+    #[tokio::test]
+    async fn test_reorganize() {
+        let db = init().await.unwrap();
+
+        let library = std::env::temp_dir().join("blackbird test reorganize");
+        let old_folder = library.join("old folder");
+        fs::create_dir_all(&old_folder).unwrap();
+        fs::write(old_folder.join("score.pdf"), b"score").unwrap();
+        fs::write(old_folder.join("part.pdf"), b"part").unwrap();
+
+        let piece_id = pieces_service::add(
+            &db,
+            String::from("test reorganized piece"),
+            Some(1909),
+            old_folder.to_string_lossy().to_string(),
+            None,
+            String::new(),
+        )
+        .await
+        .unwrap();
+        let score_path = old_folder.join("score.pdf").to_string_lossy().to_string();
+        let part_path = old_folder.join("part.pdf").to_string_lossy().to_string();
+        scores_service::add(&db, String::from("Full Score"), Some(score_path), piece_id)
+            .await
+            .unwrap();
+        let part_id = parts_service::add(&db, String::from("Tuba"), Some(part_path), piece_id)
+            .await
+            .unwrap();
+
+        let templates = Templates {
+            folder: String::from("{year}/{title}"),
+            file: String::from("{name}"),
+        };
+        let renames = get_renames(
+            &db,
+            &pieces::Entity::find_by_id(piece_id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap(),
+            &library.join("1909").join("test reorganized piece"),
+            &templates,
+            &[],
+            &[],
+        )
+        .await
+        .unwrap();
+        assert_eq!(renames.len(), 2);
+
+        let folder = library.join("1909").join("test reorganized piece");
+        apply(&db, piece_id, &old_folder, &folder, &renames)
+            .await
+            .unwrap();
+        assert!(folder.join("Tuba.pdf").exists());
+        assert!(!old_folder.exists());
+
+        let part = parts::Entity::find_by_id(part_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            part.path,
+            folder
+                .join("Tuba.pdf")
+                .to_str()
+                .map(|path| path.to_string())
+        );
+
+        pieces_service::delete(&db, piece_id).await.unwrap();
+        let _ = fs::remove_dir_all(&library);
+
+        let _ = db.close().await;
+    }
+
+    #[test]
+    fn test_restore() {
+        let folder = std::env::temp_dir().join("blackbird test restore");
+        fs::create_dir_all(&folder).unwrap();
+        let from = folder.join("Tuba.pdf");
+        let temporary_path = folder.join(".reorganizing 0.pdf");
+        fs::write(&temporary_path, b"part").unwrap();
+
+        let rename = Rename {
+            is_score: false,
+            id: 1,
+            from: from.clone(),
+            to: folder.join("Tuba 1.pdf"),
+        };
+        restore(&[(&rename, from.clone(), temporary_path.clone())]);
+        assert!(from.exists());
+        assert!(!temporary_path.exists());
+
+        let _ = fs::remove_dir_all(&folder);
+    }
+}
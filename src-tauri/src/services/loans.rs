@@ -0,0 +1,344 @@
+use sea_orm::{
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::entities::{ensembles, loans, parts, players};
+
+use chrono::NaiveDate;
+use serde_json::Value;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Returns the loans still out, or every loan ever made when `include_returned` is set.
+pub async fn get_all(db: &DatabaseConnection, include_returned: bool) -> Result<Vec<Value>, DbErr> {
+    let mut query = loans::Entity::find().order_by_asc(loans::Column::DueDate);
+    if !include_returned {
+        query = query.filter(loans::Column::ReturnedAt.is_null());
+    }
+
+    let loans = query.all(db).await?;
+    to_json(db, &loans).await
+}
+
+pub async fn get_by_part(db: &DatabaseConnection, part_id: i32) -> Result<Vec<Value>, DbErr> {
+    let loans = loans::Entity::find()
+        .filter(loans::Column::PartId.eq(part_id))
+        .order_by_desc(loans::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    to_json(db, &loans).await
+}
+
+/// Returns the loans a player still has to bring back.
+pub async fn get_by_player(db: &DatabaseConnection, player_id: i32) -> Result<Vec<Value>, DbErr> {
+    let loans = loans::Entity::find()
+        .filter(loans::Column::PlayerId.eq(player_id))
+        .filter(loans::Column::ReturnedAt.is_null())
+        .order_by_asc(loans::Column::DueDate)
+        .all(db)
+        .await?;
+
+    to_json(db, &loans).await
+}
+
+/// Lends copies of a part to either a player or a whole ensemble.
+pub async fn check_out(
+    db: &DatabaseConnection,
+    part_id: i32,
+    copies: i32,
+    player_id: Option<i32>,
+    ensemble_id: Option<i32>,
+    due_date: Option<String>,
+    notes: Option<String>,
+) -> Result<i32, DbErr> {
+    if player_id.is_some() == ensemble_id.is_some() {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(String::from(
+            "A loan goes to either a player or an ensemble",
+        ))));
+    }
+
+    if copies < 1 {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Cannot check out {} copies",
+            copies
+        ))));
+    }
+
+    if let Some(due_date) = &due_date {
+        parse_date(due_date)?;
+    }
+
+    let part = parts::Entity::find_by_id(part_id).one(db).await?;
+    let part = match part {
+        Some(part) => part,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Part with id {} not found",
+                part_id
+            )))
+        }
+    };
+
+    let available = part.copies - get_outstanding(db, part_id).await?;
+    if copies > available {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Only {} of {} copies of {} are on the shelf",
+            available, part.copies, part.name
+        ))));
+    }
+
+    let active_loan = loans::ActiveModel {
+        part_id: ActiveValue::Set(part_id),
+        player_id: ActiveValue::Set(player_id),
+        ensemble_id: ActiveValue::Set(ensemble_id),
+        copies: ActiveValue::Set(copies),
+        returned_copies: ActiveValue::Set(0),
+        due_date: ActiveValue::Set(due_date),
+        notes: ActiveValue::Set(notes),
+        ..Default::default()
+    };
+
+    let loan = loans::Entity::insert(active_loan).exec(db).await?;
+    Ok(loan.last_insert_id)
+}
+
+/// Records copies coming back. Without a count, everything still out is returned.
+pub async fn check_in(db: &DatabaseConnection, id: i32, copies: Option<i32>) -> Result<(), DbErr> {
+    let loan = loans::Entity::find_by_id(id).one(db).await?;
+    match loan {
+        Some(loan) => {
+            let outstanding = loan.copies - loan.returned_copies;
+            let copies = copies.unwrap_or(outstanding);
+
+            if copies < 1 || copies > outstanding {
+                return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                    "Cannot return {} copies, {} are out on loan {}",
+                    copies, outstanding, id
+                ))));
+            }
+
+            let now = chrono::Local::now().naive_local().to_string();
+            let returned_copies = loan.returned_copies + copies;
+            let fully_returned = returned_copies == loan.copies;
+
+            let mut loan: loans::ActiveModel = loan.into();
+            loan.returned_copies = ActiveValue::Set(returned_copies);
+            if fully_returned {
+                loan.returned_at = ActiveValue::Set(Some(now.clone()));
+            }
+            loan.updated_at = ActiveValue::Set(now);
+
+            loans::Entity::update(loan).exec(db).await?;
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Loan with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    loans::Entity::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
+/// Lists the parts of a piece with copies still out, so the set isn't filed back incomplete.
+pub async fn get_missing(db: &DatabaseConnection, piece_id: i32) -> Result<Value, DbErr> {
+    let parts = parts::Entity::find()
+        .filter(parts::Column::PieceId.eq(piece_id))
+        .all(db)
+        .await?;
+
+    let loans = loans::Entity::find()
+        .filter(loans::Column::PartId.is_in(parts.iter().map(|part| part.id)))
+        .filter(loans::Column::ReturnedAt.is_null())
+        .all(db)
+        .await?;
+    let loans = to_json(db, &loans).await?;
+
+    let mut missing_parts: Vec<Value> = vec![];
+    let mut missing_copies = 0;
+
+    for part in &parts {
+        let part_loans: Vec<&Value> = loans
+            .iter()
+            .filter(|loan| loan["part_id"] == part.id)
+            .collect();
+        let out: i64 = part_loans
+            .iter()
+            .map(|loan| loan["outstanding"].as_i64().unwrap_or_default())
+            .sum();
+
+        if out > 0 {
+            missing_copies += out;
+            missing_parts.push(serde_json::json!({
+                "id": part.id,
+                "name": part.name,
+                "copies": part.copies,
+                "missing": out,
+                "loans": part_loans,
+            }));
+        }
+    }
+
+    Ok(serde_json::json!({
+        "piece_id": piece_id,
+        "complete": missing_parts.is_empty(),
+        "missing_copies": missing_copies,
+        "parts": missing_parts,
+    }))
+}
+
+/// Counts the copies of a part still out on loan.
+pub(crate) async fn get_outstanding(db: &DatabaseConnection, part_id: i32) -> Result<i32, DbErr> {
+    let loans = loans::Entity::find()
+        .filter(loans::Column::PartId.eq(part_id))
+        .filter(loans::Column::ReturnedAt.is_null())
+        .all(db)
+        .await?;
+
+    Ok(loans
+        .iter()
+        .map(|loan| loan.copies - loan.returned_copies)
+        .sum())
+}
+
+async fn to_json(db: &DatabaseConnection, loans: &[loans::Model]) -> Result<Vec<Value>, DbErr> {
+    let parts = parts::Entity::find()
+        .filter(parts::Column::Id.is_in(loans.iter().map(|loan| loan.part_id)))
+        .all(db)
+        .await?;
+    let players = players::Entity::find()
+        .filter(players::Column::Id.is_in(loans.iter().filter_map(|loan| loan.player_id)))
+        .all(db)
+        .await?;
+    let ensembles = ensembles::Entity::find()
+        .filter(ensembles::Column::Id.is_in(loans.iter().filter_map(|loan| loan.ensemble_id)))
+        .all(db)
+        .await?;
+
+    let today = chrono::Local::now().date_naive();
+
+    let loans = loans
+        .iter()
+        .map(|loan| {
+            let part = parts.iter().find(|part| part.id == loan.part_id);
+            let borrower = match (loan.player_id, loan.ensemble_id) {
+                (Some(player_id), _) => players
+                    .iter()
+                    .find(|player| player.id == player_id)
+                    .map(|player| player.name.clone()),
+                (None, Some(ensemble_id)) => ensembles
+                    .iter()
+                    .find(|ensemble| ensemble.id == ensemble_id)
+                    .map(|ensemble| ensemble.name.clone()),
+                (None, None) => None,
+            };
+            let overdue = loan.returned_at.is_none()
+                && loan
+                    .due_date
+                    .as_deref()
+                    .and_then(|due_date| parse_date(due_date).ok())
+                    .is_some_and(|due_date| due_date < today);
+
+            serde_json::json!({
+                "id": loan.id,
+                "part_id": loan.part_id,
+                "part_name": part.map(|part| part.name.clone()),
+                "piece_id": part.map(|part| part.piece_id),
+                "player_id": loan.player_id,
+                "ensemble_id": loan.ensemble_id,
+                "borrower": borrower,
+                "copies": loan.copies,
+                "returned_copies": loan.returned_copies,
+                "outstanding": loan.copies - loan.returned_copies,
+                "due_date": loan.due_date,
+                "overdue": overdue,
+                "returned_at": loan.returned_at,
+                "notes": loan.notes,
+                "created_at": loan.created_at,
+                "updated_at": loan.updated_at,
+            })
+        })
+        .collect();
+
+    Ok(loans)
+}
+
+pub(crate) fn parse_date(date: &str) -> Result<NaiveDate, DbErr> {
+    NaiveDate::parse_from_str(date, DATE_FORMAT).map_err(|_| {
+        DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "{} is not a date in the form YYYY-MM-DD",
+            date
+        )))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+    use crate::services::{
+        parts as parts_service, pieces as pieces_service, players as players_service,
+    };
+
+    #[tokio::test]
+    async fn test_check_out_and_in() {
+        let db = init().await.unwrap();
+
+        let piece_id = pieces_service::add(
+            &db,
+            String::from("test loaned piece"),
+            None,
+            String::from("test path"),
+            None,
+            String::new(),
+        )
+        .await
+        .unwrap();
+        let part_id = parts_service::add(&db, String::from("Horn 1"), None, piece_id)
+            .await
+            .unwrap();
+        parts_service::set_copies(&db, part_id, 2).await.unwrap();
+        let player_id = players_service::add(&db, String::from("Sam Horn"), None, None, None)
+            .await
+            .unwrap();
+
+        let loan_id = check_out(&db, part_id, 2, Some(player_id), None, None, None)
+            .await
+            .unwrap();
+        // both copies are already out
+        assert!(
+            check_out(&db, part_id, 1, Some(player_id), None, None, None)
+                .await
+                .is_err()
+        );
+
+        // editing the part keeps its loans and copies, deleting it is refused
+        parts_service::update(&db, part_id, String::from("Horn in F 1"), None)
+            .await
+            .unwrap();
+        assert!(parts_service::delete(&db, part_id).await.is_err());
+        assert_eq!(get_outstanding(&db, part_id).await.unwrap(), 2);
+
+        check_in(&db, loan_id, Some(1)).await.unwrap();
+        let missing = get_missing(&db, piece_id).await.unwrap();
+        assert_eq!(missing["complete"], false);
+        assert_eq!(missing["missing_copies"], 1);
+        assert_eq!(missing["parts"][0]["loans"][0]["borrower"], "Sam Horn");
+
+        check_in(&db, loan_id, None).await.unwrap();
+        let missing = get_missing(&db, piece_id).await.unwrap();
+        assert_eq!(missing["complete"], true);
+
+        players_service::delete(&db, player_id).await.unwrap();
+        crate::entities::pieces::Entity::delete_by_id(piece_id)
+            .exec(&db)
+            .await
+            .unwrap();
+
+        let _ = db.close().await;
+    }
+}
@@ -7,3 +7,22 @@ pub mod scores;
 pub mod tags;
 pub mod setlists;
 pub mod ensemble_parts;
+pub mod tag_groups;
+pub mod duplicates;
+pub mod roles;
+pub mod coverage;
+pub mod players;
+pub mod assignments;
+pub mod loans;
+pub mod acquisitions;
+pub mod performances;
+pub mod events;
+pub mod copyright;
+pub mod rehearsal_notes;
+pub mod binders;
+pub mod thumbnails;
+pub mod files;
+pub mod inbox;
+pub mod importer;
+pub mod library;
+pub mod part_files;
@@ -1,21 +1,53 @@
-use sea_orm::{ActiveValue, DatabaseConnection, DbErr, EntityTrait};
+use sea_orm::{
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, TransactionTrait,
+};
 
-use crate::entities::musicians;
+use crate::entities::{musician_aliases, musicians, pieces, pieces_musicians};
+use crate::naming::Templates;
+use crate::services;
 
+use serde::Deserialize;
 use serde_json::Value;
+use std::path::Path;
+
+const ANONYMOUS_NAME: &str = "Anonymous";
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct MusicianDetails {
+    pub birth_year: Option<i32>,
+    pub death_year: Option<i32>,
+    pub nationality: Option<String>,
+    pub sort_name: Option<String>,
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub is_anonymous: bool,
+}
 
 pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<Value>, DbErr> {
-    let musicians = musicians::Entity::find().into_json().all(db).await?;
+    let mut musicians = musicians::Entity::find().all(db).await?;
+    let aliases = musician_aliases::Entity::find().all(db).await?;
+
+    musicians.sort_by_key(|musician| sort_name(musician).to_lowercase());
+
+    let musicians = musicians
+        .iter()
+        .map(|musician| to_json(musician, &aliases))
+        .collect();
+
     Ok(musicians)
 }
 
 pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr> {
-    let musician = musicians::Entity::find_by_id(id)
-        .into_json()
-        .one(db)
-        .await?;
+    let musician = musicians::Entity::find_by_id(id).one(db).await?;
     match musician {
-        Some(musician) => Ok(musician),
+        Some(musician) => {
+            let aliases = musician_aliases::Entity::find()
+                .filter(musician_aliases::Column::MusicianId.eq(id))
+                .all(db)
+                .await?;
+
+            Ok(to_json(&musician, &aliases))
+        }
         None => Err(DbErr::RecordNotFound(format!(
             "Musician with id {} not found",
             id
@@ -23,6 +55,43 @@ pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr>
     }
 }
 
+/// Finds musicians whose name, display name, sort name or any alias contains the query.
+pub async fn search(db: &DatabaseConnection, query: String) -> Result<Vec<Value>, DbErr> {
+    let query = query.trim().to_lowercase();
+
+    let mut musicians = musicians::Entity::find().all(db).await?;
+    let aliases = musician_aliases::Entity::find().all(db).await?;
+
+    musicians.retain(|musician| {
+        let names = [
+            Some(musician.first_name.clone()),
+            musician.last_name.clone(),
+            Some(display_name(musician)),
+            Some(sort_name(musician)),
+        ];
+
+        names
+            .iter()
+            .flatten()
+            .chain(
+                aliases
+                    .iter()
+                    .filter(|alias| alias.musician_id == musician.id)
+                    .map(|alias| &alias.name),
+            )
+            .any(|name| name.to_lowercase().contains(&query))
+    });
+
+    musicians.sort_by_key(|musician| sort_name(musician).to_lowercase());
+
+    let musicians = musicians
+        .iter()
+        .map(|musician| to_json(musician, &aliases))
+        .collect();
+
+    Ok(musicians)
+}
+
 pub async fn add(
     db: &DatabaseConnection,
     first_name: String,
@@ -63,7 +132,309 @@ pub async fn update(
     }
 }
 
+pub async fn set_details(
+    db: &DatabaseConnection,
+    id: i32,
+    details: MusicianDetails,
+) -> Result<(), DbErr> {
+    if let (Some(birth_year), Some(death_year)) = (details.birth_year, details.death_year) {
+        if death_year < birth_year {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "Death year {} is before birth year {}",
+                death_year, birth_year
+            ))));
+        }
+    }
+
+    let musician = musicians::Entity::find_by_id(id).one(db).await?;
+    match musician {
+        Some(musician) => {
+            let mut musician: musicians::ActiveModel = musician.into();
+
+            musician.birth_year = ActiveValue::Set(details.birth_year);
+            musician.death_year = ActiveValue::Set(details.death_year);
+            musician.nationality = ActiveValue::Set(details.nationality);
+            musician.sort_name = ActiveValue::Set(details.sort_name);
+            musician.display_name = ActiveValue::Set(details.display_name);
+            musician.is_anonymous = ActiveValue::Set(details.is_anonymous);
+            musician.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            musicians::Entity::update(musician).exec(db).await?;
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Musician with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn set_aliases(
+    db: &DatabaseConnection,
+    id: i32,
+    aliases: Vec<String>,
+) -> Result<(), DbErr> {
+    let musician = musicians::Entity::find_by_id(id).one(db).await?;
+    match musician {
+        Some(musician) => {
+            musician_aliases::Entity::delete_many()
+                .filter(musician_aliases::Column::MusicianId.eq(id))
+                .exec(db)
+                .await?;
+
+            for alias in aliases {
+                let active_alias = musician_aliases::ActiveModel {
+                    musician_id: ActiveValue::Set(id),
+                    name: ActiveValue::Set(alias),
+                    ..Default::default()
+                };
+
+                musician_aliases::Entity::insert(active_alias)
+                    .exec(db)
+                    .await?;
+            }
+
+            let mut musician: musicians::ActiveModel = musician.into();
+            musician.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+            musicians::Entity::update(musician).exec(db).await?;
+
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Musician with id {} not found",
+            id
+        ))),
+    }
+}
+
 pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
     musicians::Entity::delete_by_id(id).exec(db).await?;
     Ok(())
 }
+
+/// Folds the duplicates into the survivor, re-pointing every piece credit, then
+/// renames the folders of the credited pieces to match the templates. Pieces
+/// whose folder could not be renamed are reported; the merge itself stands.
+pub async fn merge(
+    db: &DatabaseConnection,
+    working_directory: &Path,
+    templates: &Templates,
+    survivor_id: i32,
+    duplicate_ids: Vec<i32>,
+) -> Result<Value, DbErr> {
+    let survivor = musicians::Entity::find_by_id(survivor_id).one(db).await?;
+    let survivor = match survivor {
+        Some(survivor) => survivor,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Musician with id {} not found",
+                survivor_id
+            )))
+        }
+    };
+
+    let duplicate_ids: Vec<i32> = duplicate_ids
+        .into_iter()
+        .filter(|id| *id != survivor_id)
+        .collect();
+
+    let txn = db.begin().await?;
+
+    let credits = pieces_musicians::Entity::find()
+        .filter(pieces_musicians::Column::MusicianId.is_in(duplicate_ids.clone()))
+        .all(&txn)
+        .await?;
+    let mut piece_ids: Vec<i32> = credits.iter().map(|credit| credit.piece_id).collect();
+    piece_ids.sort_unstable();
+    piece_ids.dedup();
+
+    for credit in credits {
+        // the survivor may already hold the same role on the piece
+        let existing = pieces_musicians::Entity::find_by_id((
+            credit.piece_id,
+            survivor_id,
+            credit.role.clone(),
+        ))
+        .one(&txn)
+        .await?;
+
+        pieces_musicians::Entity::delete_by_id((
+            credit.piece_id,
+            credit.musician_id,
+            credit.role.clone(),
+        ))
+        .exec(&txn)
+        .await?;
+
+        if existing.is_none() {
+            let active_piece_musician = pieces_musicians::ActiveModel {
+                piece_id: ActiveValue::Set(credit.piece_id),
+                musician_id: ActiveValue::Set(survivor_id),
+                role: ActiveValue::Set(credit.role),
+                order: ActiveValue::Set(credit.order),
+            };
+
+            pieces_musicians::Entity::insert(active_piece_musician)
+                .exec(&txn)
+                .await?;
+        }
+    }
+
+    // keep the duplicates' names so searches for them still find the survivor
+    musician_aliases::Entity::update_many()
+        .col_expr(
+            musician_aliases::Column::MusicianId,
+            sea_orm::sea_query::Expr::value(survivor_id),
+        )
+        .filter(musician_aliases::Column::MusicianId.is_in(duplicate_ids.clone()))
+        .exec(&txn)
+        .await?;
+
+    let duplicates = musicians::Entity::find()
+        .filter(musicians::Column::Id.is_in(duplicate_ids.clone()))
+        .all(&txn)
+        .await?;
+    for duplicate in &duplicates {
+        let name = display_name(duplicate);
+        if name != display_name(&survivor) {
+            let active_alias = musician_aliases::ActiveModel {
+                musician_id: ActiveValue::Set(survivor_id),
+                name: ActiveValue::Set(name),
+                ..Default::default()
+            };
+
+            musician_aliases::Entity::insert(active_alias)
+                .exec(&txn)
+                .await?;
+        }
+    }
+
+    musicians::Entity::delete_many()
+        .filter(musicians::Column::Id.is_in(duplicate_ids.clone()))
+        .exec(&txn)
+        .await?;
+
+    txn.commit().await?;
+
+    let mut failed: Vec<Value> = vec![];
+    for piece_id in piece_ids {
+        if let Err(e) =
+            services::pieces::rename_folder(db, working_directory, templates, piece_id).await
+        {
+            let title = pieces::Entity::find_by_id(piece_id)
+                .one(db)
+                .await?
+                .map(|piece| piece.title);
+            failed.push(serde_json::json!({
+                "id": piece_id,
+                "title": title,
+                "error": e.to_string(),
+            }));
+        }
+    }
+
+    Ok(serde_json::json!({
+        "failed": failed,
+    }))
+}
+
+/// Name as it should be shown, e.g. "Ludwig van Beethoven".
+pub fn display_name(musician: &musicians::Model) -> String {
+    if let Some(display_name) = non_empty(&musician.display_name) {
+        return display_name;
+    }
+
+    match non_empty(&musician.last_name) {
+        Some(last_name) => format!("{} {}", musician.first_name, last_name),
+        None if musician.first_name.is_empty() && musician.is_anonymous => {
+            String::from(ANONYMOUS_NAME)
+        }
+        None => musician.first_name.clone(),
+    }
+}
+
+/// Name as it should be sorted, e.g. "Beethoven, Ludwig van".
+pub fn sort_name(musician: &musicians::Model) -> String {
+    if let Some(sort_name) = non_empty(&musician.sort_name) {
+        return sort_name;
+    }
+
+    match non_empty(&musician.last_name) {
+        Some(last_name) => format!("{}, {}", last_name, musician.first_name),
+        None => display_name(musician),
+    }
+}
+
+pub(crate) fn to_json(musician: &musicians::Model, aliases: &[musician_aliases::Model]) -> Value {
+    let aliases: Vec<&str> = aliases
+        .iter()
+        .filter(|alias| alias.musician_id == musician.id)
+        .map(|alias| alias.name.as_str())
+        .collect();
+
+    serde_json::json!({
+        "id": musician.id,
+        "first_name": musician.first_name,
+        "last_name": musician.last_name,
+        "birth_year": musician.birth_year,
+        "death_year": musician.death_year,
+        "nationality": musician.nationality,
+        "sort_name": musician.sort_name,
+        "display_name": musician.display_name,
+        "is_anonymous": musician.is_anonymous,
+        "full_name": display_name(musician),
+        "sort_key": sort_name(musician),
+        "aliases": aliases,
+        "created_at": musician.created_at,
+        "updated_at": musician.updated_at,
+    })
+}
+
+fn non_empty(name: &Option<String>) -> Option<String> {
+    name.as_ref()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn musician(first_name: &str, last_name: Option<&str>) -> musicians::Model {
+        musicians::Model {
+            id: 1,
+            first_name: first_name.to_string(),
+            last_name: last_name.map(String::from),
+            created_at: String::new(),
+            updated_at: String::new(),
+            birth_year: None,
+            death_year: None,
+            nationality: None,
+            sort_name: None,
+            display_name: None,
+            is_anonymous: false,
+        }
+    }
+
+    #[test]
+    fn test_names() {
+        let holst = musician("Gustav", Some("Holst"));
+        assert_eq!(display_name(&holst), "Gustav Holst");
+        assert_eq!(sort_name(&holst), "Holst, Gustav");
+
+        let mut beethoven = musician("Ludwig", Some("van Beethoven"));
+        beethoven.sort_name = Some(String::from("Beethoven, Ludwig van"));
+        assert_eq!(display_name(&beethoven), "Ludwig van Beethoven");
+        assert_eq!(sort_name(&beethoven), "Beethoven, Ludwig van");
+
+        let mut tan_dun = musician("Dun", Some("Tan"));
+        tan_dun.display_name = Some(String::from("Tan Dun"));
+        tan_dun.sort_name = Some(String::from("Tan Dun"));
+        assert_eq!(display_name(&tan_dun), "Tan Dun");
+
+        let mut anonymous = musician("", None);
+        anonymous.is_anonymous = true;
+        assert_eq!(display_name(&anonymous), "Anonymous");
+        assert_eq!(sort_name(&anonymous), "Anonymous");
+    }
+}
@@ -0,0 +1,535 @@
+use sea_orm::{
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::entities::{part_files, parts, pieces};
+use crate::services::parts as parts_service;
+use crate::services::pieces::{numbered, remove_dir_if_empty};
+use crate::{naming, utils};
+
+use serde_json::Value;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// What a file of a part is. The file a part was added with is its original.
+pub const LABELS: [&str; 4] = ["original", "marked", "large print", "transposed"];
+
+/// Where replaced files are kept, inside the piece folder.
+pub const VERSIONS_FOLDER: &str = ".versions";
+
+/// Returns the files of a part, the primary one first. Replaced versions are only
+/// included with `include_history`.
+pub async fn get_by_part(
+    db: &DatabaseConnection,
+    part_id: i32,
+    include_history: bool,
+) -> Result<Vec<Value>, DbErr> {
+    let mut query = part_files::Entity::find()
+        .filter(part_files::Column::PartId.eq(part_id))
+        .order_by_desc(part_files::Column::IsPrimary)
+        .order_by_asc(part_files::Column::Label)
+        .order_by_desc(part_files::Column::Version);
+    if !include_history {
+        query = query.filter(part_files::Column::ReplacedAt.is_null());
+    }
+
+    let files = query.into_json().all(db).await?;
+    Ok(files)
+}
+
+/// Copies a file into the piece folder as another file of the part, named after
+/// the part's file with the label added, e.g. `1.3_Clarinet 2 (marked).pdf`.
+/// The first file of a part becomes its primary file. Returns the id of the file.
+pub async fn add(
+    db: &DatabaseConnection,
+    part_id: i32,
+    source: &Path,
+    label: String,
+    make_primary: bool,
+) -> Result<i32, DbErr> {
+    validate_label(&label)?;
+
+    let part = find_part(db, part_id).await?;
+    let current = get_current(db, part_id).await?;
+    if current.iter().any(|file| file.label == label) {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "{} already has a {} file, replace it to keep the old one as a version",
+            part.name, label
+        ))));
+    }
+
+    let destination = numbered(&get_labeled_path(db, &part, &label).await?, |path| {
+        !path.exists()
+    });
+    fs::copy(source, &destination).map_err(io_err)?;
+
+    let id = insert(db, part_id, &destination, label, 1, false).await?;
+    if make_primary || !current.iter().any(|file| file.is_primary) {
+        set_primary(db, id).await?;
+    }
+
+    Ok(id)
+}
+
+/// Replaces a file with a new one in the same place. The old file moves to the
+/// piece's `.versions` folder and stays listed as an earlier version.
+/// Returns the id of the new version.
+pub async fn replace(db: &DatabaseConnection, id: i32, source: &Path) -> Result<i32, DbErr> {
+    let file = find_file(db, id).await?;
+    if file.replaced_at.is_some() {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Version {} of {} was already replaced",
+            file.version, file.path
+        ))));
+    }
+
+    let path = PathBuf::from(&file.path);
+    if source == path {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "{} would replace itself",
+            file.path
+        ))));
+    }
+
+    // keep the old version before copying the new one over it
+    let folder = path
+        .parent()
+        .map(|parent| parent.join(VERSIONS_FOLDER))
+        .unwrap_or_else(|| PathBuf::from(VERSIONS_FOLDER));
+    fs::create_dir_all(&folder).map_err(io_err)?;
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    let kept = numbered(
+        &folder.join(format!("{} v{}{}", stem, file.version, extension)),
+        |path| !path.exists(),
+    );
+    let moved = path.exists();
+    if moved {
+        fs::rename(&path, &kept).map_err(io_err)?;
+    }
+
+    let mut new_id = None;
+    let result = async {
+        fs::copy(source, &path).map_err(io_err)?;
+
+        let id = insert(
+            db,
+            file.part_id,
+            &path,
+            file.label.clone(),
+            file.version + 1,
+            file.is_primary,
+        )
+        .await?;
+        new_id = Some(id);
+
+        let mut replaced: part_files::ActiveModel = file.clone().into();
+        replaced.path = ActiveValue::Set(kept.to_string_lossy().to_string());
+        replaced.is_primary = ActiveValue::Set(false);
+        replaced.replaced_at =
+            ActiveValue::Set(Some(chrono::Local::now().naive_local().to_string()));
+        replaced.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+        part_files::Entity::update(replaced).exec(db).await?;
+
+        // the part's PDF info is out of date now
+        if file.is_primary {
+            parts_service::set_primary_path(
+                db,
+                file.part_id,
+                path.to_str().map(|path| path.to_string()),
+            )
+            .await?;
+        }
+
+        Ok::<i32, DbErr>(id)
+    }
+    .await;
+
+    if result.is_err() {
+        // put the old version back where it was, in the database and on disk
+        if let Some(id) = new_id {
+            if let Err(e) = part_files::Entity::delete_by_id(id).exec(db).await {
+                println!("part_files_replace_error: {}", e);
+            }
+        }
+        let mut restored: part_files::ActiveModel = file.clone().into();
+        restored.path = ActiveValue::Set(file.path.clone());
+        restored.is_primary = ActiveValue::Set(file.is_primary);
+        restored.replaced_at = ActiveValue::Set(None);
+        if let Err(e) = part_files::Entity::update(restored).exec(db).await {
+            println!("part_files_replace_error: {}", e);
+        }
+        if moved {
+            if let Err(e) = fs::rename(&kept, &path) {
+                println!("part_files_replace_error: {}", e);
+            }
+        } else if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                println!("part_files_replace_error: {}", e);
+            }
+        }
+        remove_dir_if_empty(&folder);
+    }
+
+    result
+}
+
+/// Makes a file the one the part opens and prints with. `parts.path` follows it.
+pub async fn set_primary(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    let file = find_file(db, id).await?;
+    if file.replaced_at.is_some() {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Version {} of {} was replaced and can't be the primary file",
+            file.version, file.path
+        ))));
+    }
+
+    part_files::Entity::update_many()
+        .col_expr(
+            part_files::Column::IsPrimary,
+            sea_orm::sea_query::Expr::value(false),
+        )
+        .filter(part_files::Column::PartId.eq(file.part_id))
+        .exec(db)
+        .await?;
+
+    let part_id = file.part_id;
+    let path = file.path.clone();
+    let mut file: part_files::ActiveModel = file.into();
+    file.is_primary = ActiveValue::Set(true);
+    file.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+    part_files::Entity::update(file).exec(db).await?;
+
+    parts_service::set_primary_path(db, part_id, Some(path)).await
+}
+
+/// Removes a file and its earlier versions from the part and from disk. When it was
+/// the primary file, the original (or else any other file) takes over.
+pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    let file = find_file(db, id).await?;
+
+    let versions = part_files::Entity::find()
+        .filter(part_files::Column::PartId.eq(file.part_id))
+        .filter(part_files::Column::Label.eq(file.label.clone()))
+        .all(db)
+        .await?;
+
+    // a file only leaves the part once it is gone from disk, so nothing is orphaned
+    let mut failed = Vec::new();
+    for version in versions {
+        let path = Path::new(&version.path);
+        if path.exists() {
+            if let Err(e) = fs::remove_file(path) {
+                failed.push(format!("{}: {}", version.path, e));
+                continue;
+            }
+        }
+        if let Some(folder) = path
+            .parent()
+            .filter(|folder| folder.ends_with(VERSIONS_FOLDER))
+        {
+            remove_dir_if_empty(folder);
+        }
+        part_files::Entity::delete_by_id(version.id)
+            .exec(db)
+            .await?;
+    }
+
+    let kept = part_files::Entity::find_by_id(file.id).one(db).await?;
+    if file.is_primary && kept.is_none() {
+        let current = get_current(db, file.part_id).await?;
+        let next = current
+            .iter()
+            .find(|file| file.label == LABELS[0])
+            .or(current.first());
+        match next {
+            Some(next) => set_primary(db, next.id).await?,
+            None => parts_service::set_primary_path(db, file.part_id, None).await?,
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Couldn't remove {}",
+            failed.join(", ")
+        ))));
+    }
+
+    Ok(())
+}
+
+/// Keeps the primary file in step with `parts.path` for clients that only know
+/// about that, e.g. when a part is added or its file is moved.
+pub(crate) async fn set_part_path(
+    db: &DatabaseConnection,
+    part_id: i32,
+    path: Option<&str>,
+) -> Result<(), DbErr> {
+    let current = get_current(db, part_id).await?;
+
+    let path = match path {
+        Some(path) => path,
+        None => {
+            // a part without a path has no primary file, but keeps its files
+            part_files::Entity::update_many()
+                .col_expr(
+                    part_files::Column::IsPrimary,
+                    sea_orm::sea_query::Expr::value(false),
+                )
+                .filter(part_files::Column::PartId.eq(part_id))
+                .exec(db)
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let file = current
+        .iter()
+        .find(|file| file.path == path)
+        .or(current.iter().find(|file| file.is_primary))
+        .or(current.iter().find(|file| file.label == LABELS[0]));
+    let file = match file {
+        Some(file) => file.clone(),
+        None => {
+            insert(
+                db,
+                part_id,
+                Path::new(path),
+                String::from(LABELS[0]),
+                1,
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    if !file.is_primary {
+        part_files::Entity::update_many()
+            .col_expr(
+                part_files::Column::IsPrimary,
+                sea_orm::sea_query::Expr::value(false),
+            )
+            .filter(part_files::Column::PartId.eq(part_id))
+            .exec(db)
+            .await?;
+    }
+
+    let (sha256, file_size) = read_file(Path::new(path));
+    let mut file: part_files::ActiveModel = file.into();
+    file.path = ActiveValue::Set(path.to_string());
+    file.is_primary = ActiveValue::Set(true);
+    file.sha256 = ActiveValue::Set(sha256);
+    file.file_size = ActiveValue::Set(file_size);
+    file.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+    part_files::Entity::update(file).exec(db).await?;
+
+    Ok(())
+}
+
+async fn insert(
+    db: &DatabaseConnection,
+    part_id: i32,
+    path: &Path,
+    label: String,
+    version: i32,
+    is_primary: bool,
+) -> Result<i32, DbErr> {
+    let (sha256, file_size) = read_file(path);
+    let active_file = part_files::ActiveModel {
+        part_id: ActiveValue::Set(part_id),
+        path: ActiveValue::Set(path.to_string_lossy().to_string()),
+        label: ActiveValue::Set(label),
+        is_primary: ActiveValue::Set(is_primary),
+        version: ActiveValue::Set(version),
+        sha256: ActiveValue::Set(sha256),
+        file_size: ActiveValue::Set(file_size),
+        ..Default::default()
+    };
+
+    let file = part_files::Entity::insert(active_file).exec(db).await?;
+    Ok(file.last_insert_id)
+}
+
+/// Next to the part's file, or in the piece folder when the part has none yet.
+async fn get_labeled_path(
+    db: &DatabaseConnection,
+    part: &parts::Model,
+    label: &str,
+) -> Result<PathBuf, DbErr> {
+    if let Some(path) = part.path.as_deref().map(Path::new) {
+        if let (Some(folder), Some(stem)) = (path.parent(), path.file_stem()) {
+            return Ok(folder.join(format!("{} ({}).pdf", stem.to_string_lossy(), label)));
+        }
+    }
+
+    let piece = pieces::Entity::find_by_id(part.piece_id).one(db).await?;
+    match piece.filter(|piece| !piece.path.is_empty()) {
+        Some(piece) => Ok(PathBuf::from(piece.path).join(format!(
+            "{} ({}).pdf",
+            naming::file_name(&part.name),
+            label
+        ))),
+        None => Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Piece with id {} has no folder",
+            part.piece_id
+        )))),
+    }
+}
+
+async fn get_current(
+    db: &DatabaseConnection,
+    part_id: i32,
+) -> Result<Vec<part_files::Model>, DbErr> {
+    part_files::Entity::find()
+        .filter(part_files::Column::PartId.eq(part_id))
+        .filter(part_files::Column::ReplacedAt.is_null())
+        .order_by_asc(part_files::Column::Id)
+        .all(db)
+        .await
+}
+
+async fn find_file(db: &DatabaseConnection, id: i32) -> Result<part_files::Model, DbErr> {
+    let file = part_files::Entity::find_by_id(id).one(db).await?;
+    match file {
+        Some(file) => Ok(file),
+        None => Err(DbErr::RecordNotFound(format!(
+            "Part file with id {} not found",
+            id
+        ))),
+    }
+}
+
+async fn find_part(db: &DatabaseConnection, id: i32) -> Result<parts::Model, DbErr> {
+    let part = parts::Entity::find_by_id(id).one(db).await?;
+    match part {
+        Some(part) => Ok(part),
+        None => Err(DbErr::RecordNotFound(format!(
+            "Part with id {} not found",
+            id
+        ))),
+    }
+}
+
+fn read_file(path: &Path) -> (Option<String>, Option<i64>) {
+    let sha256 = utils::sha256_file(path).ok();
+    let file_size = fs::metadata(path)
+        .ok()
+        .map(|metadata| metadata.len() as i64);
+    (sha256, file_size)
+}
+
+fn validate_label(label: &str) -> Result<(), DbErr> {
+    match LABELS.contains(&label) {
+        true => Ok(()),
+        false => Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "File label {} is not one of {}",
+            label,
+            LABELS.join(", ")
+        )))),
+    }
+}
+
+fn io_err(e: io::Error) -> DbErr {
+    DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+    use crate::services::pieces as pieces_service;
+
+    #[tokio::test]
+    async fn test_versions() {
+        let db = init().await.unwrap();
+
+        let folder = std::env::temp_dir().join("blackbird test part files");
+        fs::create_dir_all(&folder).unwrap();
+        let original = folder.join("1.1_Horn.pdf");
+        let marked = folder.join("marked.pdf");
+        fs::write(&original, b"original").unwrap();
+        fs::write(&marked, b"marked").unwrap();
+
+        let piece_id = pieces_service::add(
+            &db,
+            String::from("test piece with part files"),
+            None,
+            folder.to_string_lossy().to_string(),
+            None,
+            String::new(),
+        )
+        .await
+        .unwrap();
+        let part_id = parts_service::add(
+            &db,
+            String::from("Horn"),
+            original.to_str().map(|path| path.to_string()),
+            piece_id,
+        )
+        .await
+        .unwrap();
+
+        // the part's own file is its primary original
+        let files = get_by_part(&db, part_id, false).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0]["label"], "original");
+
+        let marked_id = add(&db, part_id, &marked, String::from("marked"), true)
+            .await
+            .unwrap();
+        let part = find_part(&db, part_id).await.unwrap();
+        assert_eq!(
+            part.path,
+            folder
+                .join("1.1_Horn (marked).pdf")
+                .to_str()
+                .map(|path| path.to_string())
+        );
+        assert!(add(&db, part_id, &marked, String::from("marked"), false)
+            .await
+            .is_err());
+
+        fs::write(&marked, b"marked again").unwrap();
+        replace(&db, marked_id, &marked).await.unwrap();
+        let files = get_by_part(&db, part_id, true).await.unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0]["version"], 2);
+        assert!(folder
+            .join(VERSIONS_FOLDER)
+            .join("1.1_Horn (marked) v1.pdf")
+            .exists());
+
+        delete(&db, files[0]["id"].as_i64().unwrap() as i32)
+            .await
+            .unwrap();
+        let part = find_part(&db, part_id).await.unwrap();
+        assert_eq!(part.path, original.to_str().map(|path| path.to_string()));
+        // the marked file and its earlier version are gone from disk too
+        assert!(!folder.join("1.1_Horn (marked).pdf").exists());
+        assert!(!folder.join(VERSIONS_FOLDER).exists());
+
+        // deleting the part takes all of its files and versions with it
+        let marked_id = add(&db, part_id, &marked, String::from("marked"), false)
+            .await
+            .unwrap();
+        replace(&db, marked_id, &marked).await.unwrap();
+        parts_service::delete(&db, part_id).await.unwrap();
+        assert!(get_by_part(&db, part_id, true).await.unwrap().is_empty());
+        assert!(!original.exists());
+        assert!(!folder.join("1.1_Horn (marked).pdf").exists());
+        assert!(!folder.join(VERSIONS_FOLDER).exists());
+
+        pieces_service::delete(&db, piece_id).await.unwrap();
+
+        let _ = db.close().await;
+    }
+}
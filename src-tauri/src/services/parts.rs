@@ -3,9 +3,24 @@ use sea_orm::{
     QuerySelect, RelationTrait,
 };
 
-use crate::entities::{instruments, parts, parts_instruments};
+use crate::entities::{instruments, parts, parts_instruments, pieces};
+use crate::naming::{FileNames, Templates};
+use crate::services::pieces::numbered;
+use crate::services::{loans, part_files};
+use crate::{pdf, utils};
 
+use serde::Deserialize;
 use serde_json::Value;
+use std::{fs, path::PathBuf};
+
+/// A part inside a combined PDF, from `first_page` to `last_page` (counted from 1).
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct PartPages {
+    pub name: String,
+    pub first_page: u32,
+    pub last_page: u32,
+    pub instrument_ids: Vec<i32>,
+}
 
 pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<Value>, DbErr> {
     let parts = parts::Entity::find().into_json().all(db).await?;
@@ -26,14 +41,24 @@ pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr>
                 .into_json()
                 .all(db)
                 .await?;
+            let files = part_files::get_by_part(db, id, false).await?;
 
             let part = serde_json::json!({
                 "id": part.id,
                 "name": part.name,
                 "path": part.path,
+                "copies": part.copies,
+                "page_count": part.page_count,
+                "page_size": part.page_size,
+                "pdf_title": part.pdf_title,
+                "pdf_author": part.pdf_author,
+                "pdf_producer": part.pdf_producer,
+                "file_size": part.file_size,
+                "sha256": part.sha256,
                 "created_at": part.created_at,
                 "updated_at": part.updated_at,
                 "instruments": instruments,
+                "files": files,
             });
 
             Ok(part)
@@ -53,14 +78,18 @@ pub async fn add(
     path: Option<String>,
     piece_id: i32,
 ) -> Result<i32, DbErr> {
-    let active_part = parts::ActiveModel {
+    let mut active_part = parts::ActiveModel {
         name: ActiveValue::Set(name),
-        path: ActiveValue::Set(path),
         piece_id: ActiveValue::Set(piece_id),
         ..Default::default()
     };
+    set_path(&mut active_part, path.clone());
 
     let part = parts::Entity::insert(active_part).exec(db).await?;
+    if path.is_some() {
+        part_files::set_part_path(db, part.last_insert_id, path.as_deref()).await?;
+    }
+
     Ok(part.last_insert_id)
 }
 
@@ -76,11 +105,11 @@ pub async fn update(
             let mut part: parts::ActiveModel = part.into();
 
             part.name = ActiveValue::Set(name);
-            part.path = ActiveValue::Set(path);
+            set_path(&mut part, path.clone());
             part.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
 
             parts::Entity::update(part).exec(db).await?;
-            Ok(())
+            part_files::set_part_path(db, id, path.as_deref()).await
         }
         None => Err(DbErr::RecordNotFound(format!(
             "Part with id {} not found",
@@ -89,7 +118,22 @@ pub async fn update(
     }
 }
 
+/// Deletes a part and all of its files, unless copies of it are still out on loan.
+/// The loans would go with it.
 pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    check_not_on_loan(db, id).await?;
+
+    // every label goes with its earlier versions and the emptied `.versions` folder
+    let mut labels = Vec::new();
+    for file in part_files::get_by_part(db, id, true).await? {
+        if let (Some(id), Some(label)) = (file["id"].as_i64(), file["label"].as_str()) {
+            if !labels.contains(&label.to_string()) {
+                labels.push(label.to_string());
+                part_files::delete(db, id as i32).await?;
+            }
+        }
+    }
+
     parts::Entity::delete_by_id(id).exec(db).await?;
     Ok(())
 }
@@ -132,6 +176,208 @@ pub async fn set_instruments(
     }
 }
 
+/// Records how many paper copies of the part the library owns.
+pub async fn set_copies(db: &DatabaseConnection, id: i32, copies: i32) -> Result<(), DbErr> {
+    if copies < 0 {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Cannot own {} copies of a part",
+            copies
+        ))));
+    }
+
+    // the copies out on loan are still owned
+    let outstanding = loans::get_outstanding(db, id).await?;
+    if copies < outstanding {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Cannot own {} copies of part with id {} while {} are out on loan",
+            copies, id, outstanding
+        ))));
+    }
+
+    let part = parts::Entity::find_by_id(id).one(db).await?;
+    match part {
+        Some(part) => {
+            let mut part: parts::ActiveModel = part.into();
+
+            part.copies = ActiveValue::Set(copies);
+            part.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            parts::Entity::update(part).exec(db).await?;
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Part with id {} not found",
+            id
+        ))),
+    }
+}
+
+/// Splits a PDF holding all parts of a piece into one file per part in the piece folder,
+/// then adds the parts with their instruments. Returns the ids of the new parts.
+pub async fn split_pdf(
+    db: &DatabaseConnection,
+    piece_id: i32,
+    source: String,
+    parts_pages: Vec<PartPages>,
+    templates: &Templates,
+) -> Result<Vec<i32>, DbErr> {
+    let piece = pieces::Entity::find_by_id(piece_id).one(db).await?;
+    let piece = match piece {
+        Some(piece) => piece,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Piece with id {} not found",
+                piece_id
+            )))
+        }
+    };
+    if piece.path.is_empty() {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Piece with id {} has no folder",
+            piece_id
+        ))));
+    }
+
+    // part files are numbered after the ones already there, like the edit wizard does
+    let existing = parts::Entity::find()
+        .filter(parts::Column::PieceId.eq(piece_id))
+        .all(db)
+        .await?
+        .len();
+    let names = crate::services::pieces::library_names(db, piece_id).await?;
+    let instruments = instruments::Entity::find().all(db).await?;
+
+    // never write over a file that is already there, so failing can remove what it wrote
+    let mut taken: Vec<PathBuf> = vec![];
+    let ranges: Vec<_> = parts_pages
+        .iter()
+        .enumerate()
+        .map(|(index, part_pages)| {
+            let instrument = part_pages.instrument_ids.first().and_then(|instrument_id| {
+                instruments
+                    .iter()
+                    .find(|instrument| instrument.id == *instrument_id)
+            });
+            let file_name = templates.file(
+                &names,
+                &FileNames {
+                    is_score: false,
+                    order: existing + index + 1,
+                    name: part_pages.name.clone(),
+                    instrument: instrument.map(|instrument| instrument.name.clone()),
+                },
+            );
+            let path = numbered(&PathBuf::from(&piece.path).join(file_name), |path| {
+                !path.exists() && !taken.iter().any(|taken| taken == path)
+            });
+            taken.push(path.clone());
+            (part_pages.first_page..=part_pages.last_page, path)
+        })
+        .collect();
+
+    pdf::split(&source, &ranges)
+        .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string())))?;
+
+    let mut part_ids: Vec<i32> = vec![];
+
+    for (part_pages, (_, path)) in parts_pages.into_iter().zip(&ranges) {
+        let path = path.to_str().map(|path| path.to_string());
+        let added = async {
+            let part_id = add(db, part_pages.name, path, piece_id).await?;
+            part_ids.push(part_id);
+            set_instruments(db, part_id, part_pages.instrument_ids).await
+        }
+        .await;
+
+        // rather than leave the piece with only some of the parts
+        if let Err(e) = added {
+            for part_id in &part_ids {
+                let _ = parts::Entity::delete_by_id(*part_id).exec(db).await;
+            }
+            for (_, path) in &ranges {
+                let _ = fs::remove_file(path);
+            }
+            return Err(e);
+        }
+    }
+
+    Ok(part_ids)
+}
+
+/// Reads the PDF again, e.g. after the file was replaced outside the library.
+pub async fn refresh_pdf_info(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    let part = parts::Entity::find_by_id(id).one(db).await?;
+    match part {
+        Some(part) => {
+            let path = part.path.clone();
+            let mut part: parts::ActiveModel = part.into();
+
+            set_path(&mut part, path.clone());
+            part.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            parts::Entity::update(part).exec(db).await?;
+            part_files::set_part_path(db, id, path.as_deref()).await
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Part with id {} not found",
+            id
+        ))),
+    }
+}
+
+/// Points the part at its primary file, reading the PDF again.
+pub(crate) async fn set_primary_path(
+    db: &DatabaseConnection,
+    id: i32,
+    path: Option<String>,
+) -> Result<(), DbErr> {
+    let part = parts::Entity::find_by_id(id).one(db).await?;
+    match part {
+        Some(part) => {
+            let mut part: parts::ActiveModel = part.into();
+
+            set_path(&mut part, path);
+            part.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            parts::Entity::update(part).exec(db).await?;
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Part with id {} not found",
+            id
+        ))),
+    }
+}
+
+/// Stores the path along with the file's hash and what can be read from it when it is a PDF.
+fn set_path(part: &mut parts::ActiveModel, path: Option<String>) {
+    let info = pdf::try_read_info(&path);
+    let sha256 = path
+        .as_deref()
+        .and_then(|path| utils::sha256_file(path).ok());
+
+    part.path = ActiveValue::Set(path);
+    part.page_count = ActiveValue::Set(info.as_ref().map(|info| info.page_count));
+    part.page_size = ActiveValue::Set(info.as_ref().and_then(|info| info.page_size.clone()));
+    part.pdf_title = ActiveValue::Set(info.as_ref().and_then(|info| info.title.clone()));
+    part.pdf_author = ActiveValue::Set(info.as_ref().and_then(|info| info.author.clone()));
+    part.pdf_producer = ActiveValue::Set(info.as_ref().and_then(|info| info.producer.clone()));
+    part.file_size = ActiveValue::Set(info.as_ref().map(|info| info.file_size));
+    part.sha256 = ActiveValue::Set(sha256);
+}
+
+pub(crate) async fn check_not_on_loan(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    let outstanding = loans::get_outstanding(db, id).await?;
+    if outstanding > 0 {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "{} copies of part {} are still out on loan",
+            outstanding, id
+        ))));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,4 +454,133 @@ mod tests {
 
         let _ = db.close().await;
     }
+
+    #[tokio::test]
+    async fn test_set_copies_below_loans() {
+        let db = init().await.unwrap();
+
+        let piece_id = crate::services::pieces::add(
+            &db,
+            String::from("test copies piece"),
+            None,
+            String::new(),
+            None,
+            String::new(),
+        )
+        .await
+        .unwrap();
+        let part_id = add(&db, String::from("Trumpet 1"), None, piece_id)
+            .await
+            .unwrap();
+        set_copies(&db, part_id, 3).await.unwrap();
+        let ensemble_id =
+            crate::services::ensembles::add(&db, String::from("test copies band"), None)
+                .await
+                .unwrap();
+        loans::check_out(&db, part_id, 2, None, Some(ensemble_id), None, None)
+            .await
+            .unwrap();
+
+        assert!(set_copies(&db, part_id, 1).await.is_err());
+        set_copies(&db, part_id, 2).await.unwrap();
+
+        crate::services::ensembles::delete(&db, ensemble_id)
+            .await
+            .unwrap();
+        crate::entities::pieces::Entity::delete_by_id(piece_id)
+            .exec(&db)
+            .await
+            .unwrap();
+
+        let _ = db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_split_pdf() {
+        let db = init().await.unwrap();
+
+        let folder = std::env::temp_dir().join("blackbird test split pdf");
+        fs::create_dir_all(&folder).unwrap();
+        let source = folder.join(".scan.pdf");
+        crate::pdf::tests::write_test_pdf(&source, 4);
+
+        let piece_id = crate::services::pieces::add(
+            &db,
+            String::from("test split piece"),
+            None,
+            folder.to_string_lossy().to_string(),
+            None,
+            String::new(),
+        )
+        .await
+        .unwrap();
+        let templates = Templates {
+            file: String::from("{name}"),
+            ..Default::default()
+        };
+
+        let parts_pages = vec![
+            PartPages {
+                name: String::from("Flute"),
+                first_page: 1,
+                last_page: 3,
+                instrument_ids: vec![],
+            },
+            PartPages {
+                name: String::from("Tuba"),
+                first_page: 4,
+                last_page: 4,
+                instrument_ids: vec![],
+            },
+        ];
+        let part_ids = split_pdf(
+            &db,
+            piece_id,
+            source.to_string_lossy().to_string(),
+            parts_pages.clone(),
+            &templates,
+        )
+        .await
+        .unwrap();
+
+        let mut page_counts = vec![];
+        for part_id in &part_ids {
+            let part = parts::Entity::find_by_id(*part_id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            page_counts.push(part.page_count);
+        }
+        assert_eq!(page_counts, vec![Some(3), Some(1)]);
+        assert!(folder.join("Tuba.pdf").exists());
+
+        // an unknown instrument fails the split, leaving neither rows nor files
+        let mut unknown_instrument = parts_pages;
+        unknown_instrument[1].name = String::from("Horn");
+        unknown_instrument[1].instrument_ids = vec![-1];
+        assert!(split_pdf(
+            &db,
+            piece_id,
+            source.to_string_lossy().to_string(),
+            unknown_instrument,
+            &templates,
+        )
+        .await
+        .is_err());
+        let parts = parts::Entity::find()
+            .filter(parts::Column::PieceId.eq(piece_id))
+            .all(&db)
+            .await
+            .unwrap();
+        assert_eq!(parts.len(), 2);
+        assert!(!folder.join("Horn.pdf").exists());
+
+        crate::services::pieces::delete(&db, piece_id)
+            .await
+            .unwrap();
+        let _ = fs::remove_dir_all(&folder);
+
+        let _ = db.close().await;
+    }
 }
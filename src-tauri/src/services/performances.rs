@@ -0,0 +1,288 @@
+use sea_orm::{
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, JoinType, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait,
+};
+
+use crate::entities::{ensembles, performances, pieces, pieces_performances, pieces_setlists};
+use crate::services::loans::parse_date;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Where and when a concert took place. The date is in the form YYYY-MM-DD.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct PerformanceDetails {
+    pub date: String,
+    pub venue: Option<String>,
+    pub ensemble_id: Option<i32>,
+    pub conductor: Option<String>,
+    pub setlist_id: Option<i32>,
+    pub notes: Option<String>,
+}
+
+/// Returns every performance, most recent first.
+pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<Value>, DbErr> {
+    let performances = performances::Entity::find()
+        .order_by_desc(performances::Column::Date)
+        .all(db)
+        .await?;
+
+    let mut performances_with_data: Vec<Value> = vec![];
+
+    for performance in performances {
+        performances_with_data.push(get_data(db, performance).await?);
+    }
+
+    Ok(performances_with_data)
+}
+
+pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr> {
+    let performance = performances::Entity::find_by_id(id).one(db).await?;
+    match performance {
+        Some(performance) => get_data(db, performance).await,
+        None => Err(DbErr::RecordNotFound(format!(
+            "Performance with id {} not found",
+            id
+        ))),
+    }
+}
+
+/// Returns the performances a piece was played at, most recent first.
+pub async fn get_by_piece(db: &DatabaseConnection, piece_id: i32) -> Result<Vec<Value>, DbErr> {
+    let performances = performances::Entity::find()
+        .join_rev(
+            JoinType::InnerJoin,
+            pieces_performances::Relation::Performances.def(),
+        )
+        .filter(pieces_performances::Column::PieceId.eq(piece_id))
+        .order_by_desc(performances::Column::Date)
+        .into_json()
+        .all(db)
+        .await?;
+
+    Ok(performances)
+}
+
+/// Records a performance. When it is linked to a setlist, the pieces on the setlist
+/// are taken as the programme; `set_pieces` corrects it afterwards.
+pub async fn add(db: &DatabaseConnection, details: PerformanceDetails) -> Result<i32, DbErr> {
+    parse_date(&details.date)?;
+
+    let active_performance = performances::ActiveModel {
+        date: ActiveValue::Set(details.date),
+        venue: ActiveValue::Set(details.venue),
+        ensemble_id: ActiveValue::Set(details.ensemble_id),
+        conductor: ActiveValue::Set(details.conductor),
+        setlist_id: ActiveValue::Set(details.setlist_id),
+        notes: ActiveValue::Set(details.notes),
+        ..Default::default()
+    };
+
+    let performance = performances::Entity::insert(active_performance)
+        .exec(db)
+        .await?;
+    let id = performance.last_insert_id;
+
+    if let Some(setlist_id) = details.setlist_id {
+        let piece_ids: Vec<i32> = pieces_setlists::Entity::find()
+            .filter(pieces_setlists::Column::SetlistId.eq(setlist_id))
+            .all(db)
+            .await?
+            .iter()
+            .map(|piece_setlist| piece_setlist.piece_id)
+            .collect();
+
+        set_pieces(db, id, piece_ids).await?;
+    }
+
+    Ok(id)
+}
+
+pub async fn update(
+    db: &DatabaseConnection,
+    id: i32,
+    details: PerformanceDetails,
+) -> Result<(), DbErr> {
+    parse_date(&details.date)?;
+
+    let performance = performances::Entity::find_by_id(id).one(db).await?;
+    match performance {
+        Some(performance) => {
+            let mut performance: performances::ActiveModel = performance.into();
+
+            performance.date = ActiveValue::Set(details.date);
+            performance.venue = ActiveValue::Set(details.venue);
+            performance.ensemble_id = ActiveValue::Set(details.ensemble_id);
+            performance.conductor = ActiveValue::Set(details.conductor);
+            performance.setlist_id = ActiveValue::Set(details.setlist_id);
+            performance.notes = ActiveValue::Set(details.notes);
+            performance.updated_at =
+                ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            performances::Entity::update(performance).exec(db).await?;
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Performance with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    performances::Entity::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
+pub async fn set_pieces(
+    db: &DatabaseConnection,
+    id: i32,
+    piece_ids: Vec<i32>,
+) -> Result<(), DbErr> {
+    pieces_performances::Entity::delete_many()
+        .filter(pieces_performances::Column::PerformanceId.eq(id))
+        .exec(db)
+        .await?;
+
+    for piece_id in piece_ids {
+        let active_piece_performance = pieces_performances::ActiveModel {
+            piece_id: ActiveValue::Set(piece_id),
+            performance_id: ActiveValue::Set(id),
+        };
+
+        pieces_performances::Entity::insert(active_piece_performance)
+            .exec(db)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Returns how often a piece was performed and the date of the latest performance.
+pub async fn get_history(
+    db: &DatabaseConnection,
+    piece_id: i32,
+) -> Result<(usize, Option<String>), DbErr> {
+    let dates: Vec<String> = performances::Entity::find()
+        .join_rev(
+            JoinType::InnerJoin,
+            pieces_performances::Relation::Performances.def(),
+        )
+        .filter(pieces_performances::Column::PieceId.eq(piece_id))
+        .order_by_desc(performances::Column::Date)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|performance| performance.date)
+        .collect();
+
+    Ok((dates.len(), dates.into_iter().next()))
+}
+
+async fn get_data(
+    db: &DatabaseConnection,
+    performance: performances::Model,
+) -> Result<Value, DbErr> {
+    let ensemble = match performance.ensemble_id {
+        Some(ensemble_id) => ensembles::Entity::find_by_id(ensemble_id).one(db).await?,
+        None => None,
+    };
+
+    let pieces = pieces::Entity::find()
+        .join_rev(
+            JoinType::InnerJoin,
+            pieces_performances::Relation::Pieces.def(),
+        )
+        .filter(pieces_performances::Column::PerformanceId.eq(performance.id))
+        .all(db)
+        .await?;
+
+    Ok(serde_json::json!({
+        "id": performance.id,
+        "date": performance.date,
+        "venue": performance.venue,
+        "ensemble_id": performance.ensemble_id,
+        "ensemble_name": ensemble.map(|ensemble| ensemble.name),
+        "conductor": performance.conductor,
+        "setlist_id": performance.setlist_id,
+        "notes": performance.notes,
+        "created_at": performance.created_at,
+        "updated_at": performance.updated_at,
+        "pieces": pieces
+            .iter()
+            .map(|piece| serde_json::json!({
+                "id": piece.id,
+                "title": piece.title,
+            }))
+            .collect::<Vec<Value>>(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+    use crate::services::{pieces as pieces_service, setlists as setlists_service};
+
+    #[tokio::test]
+    async fn test_history() {
+        let db = init().await.unwrap();
+
+        let setlist_id = setlists_service::add(&db, String::from("test performed setlist"))
+            .await
+            .unwrap();
+        let piece_id = pieces_service::add(
+            &db,
+            String::from("test performed piece"),
+            None,
+            String::from("test path"),
+            None,
+            String::new(),
+        )
+        .await
+        .unwrap();
+        setlists_service::add_piece(&db, setlist_id, piece_id)
+            .await
+            .unwrap();
+
+        let first_id = add(
+            &db,
+            PerformanceDetails {
+                date: String::from("2001-05-12"),
+                setlist_id: Some(setlist_id),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let second_id = add(
+            &db,
+            PerformanceDetails {
+                date: String::from("2003-12-20"),
+                setlist_id: Some(setlist_id),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let (count, last_performed) = get_history(&db, piece_id).await.unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(last_performed, Some(String::from("2003-12-20")));
+
+        let not_performed = pieces_service::get_not_performed_since(&db, 3)
+            .await
+            .unwrap();
+        assert!(not_performed.iter().any(|piece| piece["id"] == piece_id));
+
+        delete(&db, first_id).await.unwrap();
+        delete(&db, second_id).await.unwrap();
+        setlists_service::delete(&db, setlist_id).await.unwrap();
+        pieces::Entity::delete_by_id(piece_id)
+            .exec(&db)
+            .await
+            .unwrap();
+
+        let _ = db.close().await;
+    }
+}
@@ -1,50 +1,38 @@
 use crate::entities::*;
+use crate::naming::{PieceNames, Templates};
+use crate::services::musicians::sort_name;
 use sea_orm::{
     ActiveValue, ColumnTrait, Condition, DatabaseConnection, DbBackend, DbErr, EntityTrait,
-    JoinType, QueryFilter, QueryOrder, QuerySelect, QueryTrait, RelationTrait,
+    JoinType, Order, QueryFilter, QueryOrder, QuerySelect, QueryTrait, RelationTrait,
+    TransactionTrait,
 };
+use serde::Deserialize;
 use serde_json::Value;
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Filters and ordering for `query`. Ratings run from 1 to 5; `sort_by` is one of
+/// title, year_published, difficulty, rating, audience_rating, created_at or updated_at.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct PieceQuery {
+    pub favorites_only: bool,
+    pub min_rating: Option<i32>,
+    pub min_audience_rating: Option<i32>,
+    pub sort_by: Option<String>,
+    pub descending: bool,
+}
 
 pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<Value>, DbErr> {
     let pieces = pieces::Entity::find().all(db).await?;
 
+    let roles = get_roles(db).await?;
     let mut pieces_with_data: Vec<Value> = vec![];
 
     for piece in pieces {
-        let id = piece.id;
-
-        let composers = get_musicians(db, id, "composer").await?;
-        let arrangers = get_musicians(db, id, "arranger").await?;
-        let orchestrators = get_musicians(db, id, "orchestrator").await?;
-        let lyricists = get_musicians(db, id, "lyricist").await?;
-        let transcribers = get_musicians(db, id, "transcriber").await?;
-        let setlists = get_setlists(db, id).await?;
-        let tags = get_tags(db, id).await?;
-        let scores = get_scores(db, id).await?;
-        let parts = get_parts(db, id).await?;
-
-        let piece = serde_json::json!({
-            "id": piece.id,
-            "title": piece.title,
-            "year_published": piece.year_published,
-            "path": piece.path,
-            "difficulty": piece.difficulty,
-            "notes": piece.notes,
-            "created_at": piece.created_at,
-            "updated_at": piece.updated_at,
-            "scores": scores,
-            "parts": parts,
-            "setlists": setlists,
-            "tags": tags,
-            "composers": composers,
-            "arrangers": arrangers,
-            "orchestrators": orchestrators,
-            "lyricists": lyricists,
-            "transcribers": transcribers,
-        });
-
-        pieces_with_data.push(piece);
+        pieces_with_data.push(get_data(db, piece, &roles).await?);
     }
 
     Ok(pieces_with_data)
@@ -57,45 +45,15 @@ pub async fn get_by_setlist(db: &DatabaseConnection, setlist_id: i32) -> Result<
             let pieces = pieces::Entity::find()
                 .join_rev(JoinType::InnerJoin, pieces_setlists::Relation::Pieces.def())
                 .filter(pieces_setlists::Column::SetlistId.eq(setlist_id))
+                .order_by_asc(pieces_setlists::Column::Order)
                 .all(db)
                 .await?;
 
+            let roles = get_roles(db).await?;
             let mut pieces_with_data: Vec<Value> = vec![];
 
             for piece in pieces {
-                let id = piece.id;
-
-                let composers = get_musicians(db, id, "composer").await?;
-                let arrangers = get_musicians(db, id, "arranger").await?;
-                let orchestrators = get_musicians(db, id, "orchestrator").await?;
-                let lyricists = get_musicians(db, id, "lyricist").await?;
-                let transcribers = get_musicians(db, id, "transcriber").await?;
-                let setlists = get_setlists(db, id).await?;
-                let tags = get_tags(db, id).await?;
-                let scores = get_scores(db, id).await?;
-                let parts = get_parts(db, id).await?;
-
-                let piece = serde_json::json!({
-                    "id": piece.id,
-                    "title": piece.title,
-                    "year_published": piece.year_published,
-                    "path": piece.path,
-                    "difficulty": piece.difficulty,
-                    "notes": piece.notes,
-                    "created_at": piece.created_at,
-                    "updated_at": piece.updated_at,
-                    "scores": scores,
-                    "parts": parts,
-                    "setlists": setlists,
-                    "tags": tags,
-                    "composers": composers,
-                    "arrangers": arrangers,
-                    "orchestrators": orchestrators,
-                    "lyricists": lyricists,
-                    "transcribers": transcribers,
-                });
-
-                pieces_with_data.push(piece);
+                pieces_with_data.push(get_data(db, piece, &roles).await?);
             }
 
             Ok(pieces_with_data)
@@ -111,39 +69,7 @@ pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr>
     let piece = pieces::Entity::find_by_id(id).one(db).await?;
 
     match piece {
-        Some(piece) => {
-            let composers = get_musicians(db, id, "composer").await?;
-            let arrangers = get_musicians(db, id, "arranger").await?;
-            let orchestrators = get_musicians(db, id, "orchestrator").await?;
-            let lyricists = get_musicians(db, id, "lyricist").await?;
-            let transcribers = get_musicians(db, id, "transcriber").await?;
-            let setlists = get_setlists(db, id).await?;
-            let tags = get_tags(db, id).await?;
-            let scores = get_scores(db, id).await?;
-            let parts = get_parts(db, id).await?;
-
-            let piece = serde_json::json!({
-                "id": piece.id,
-                "title": piece.title,
-                "year_published": piece.year_published,
-                "path": piece.path,
-                "difficulty": piece.difficulty,
-                "notes": piece.notes,
-                "created_at": piece.created_at,
-                "updated_at": piece.updated_at,
-                "scores": scores,
-                "parts": parts,
-                "setlists": setlists,
-                "tags": tags,
-                "composers": composers,
-                "arrangers": arrangers,
-                "orchestrators": orchestrators,
-                "lyricists": lyricists,
-                "transcribers": transcribers,
-            });
-
-            Ok(piece)
-        }
+        Some(piece) => get_data(db, piece, &get_roles(db).await?).await,
         None => Err(DbErr::RecordNotFound(format!(
             "Piece with id {} not found",
             id
@@ -151,6 +77,159 @@ pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr>
     }
 }
 
+/// Returns pieces tagged with the tag or any tag nested below it.
+pub async fn get_by_tag(db: &DatabaseConnection, tag_id: i32) -> Result<Vec<Value>, DbErr> {
+    let tag = tags::Entity::find_by_id(tag_id).one(db).await?;
+    match tag {
+        Some(_) => {
+            let tag_ids = crate::services::tags::get_descendant_ids(db, tag_id).await?;
+            get_by_tag_ids(db, tag_ids).await
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Tag with id {} not found",
+            tag_id
+        ))),
+    }
+}
+
+/// Returns pieces tagged with any tag in the group, including tags nested below them.
+pub async fn get_by_tag_group(db: &DatabaseConnection, group_id: i32) -> Result<Vec<Value>, DbErr> {
+    let group = tag_groups::Entity::find_by_id(group_id).one(db).await?;
+    match group {
+        Some(_) => {
+            let group_tags = tags::Entity::find()
+                .filter(tags::Column::GroupId.eq(group_id))
+                .all(db)
+                .await?;
+
+            let mut tag_ids: Vec<i32> = vec![];
+            for tag in group_tags {
+                for id in crate::services::tags::get_descendant_ids(db, tag.id).await? {
+                    if !tag_ids.contains(&id) {
+                        tag_ids.push(id);
+                    }
+                }
+            }
+
+            get_by_tag_ids(db, tag_ids).await
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Tag group with id {} not found",
+            group_id
+        ))),
+    }
+}
+
+/// Returns pieces not performed in the last `years` years, including ones never performed.
+pub async fn get_not_performed_since(
+    db: &DatabaseConnection,
+    years: u32,
+) -> Result<Vec<Value>, DbErr> {
+    let today = chrono::Local::now().date_naive();
+    let cutoff = today
+        .checked_sub_months(chrono::Months::new(12 * years))
+        .unwrap_or(chrono::NaiveDate::MIN);
+
+    let recent_performance_ids: Vec<i32> = performances::Entity::find()
+        .filter(performances::Column::Date.gte(cutoff.to_string()))
+        .all(db)
+        .await?
+        .iter()
+        .map(|performance| performance.id)
+        .collect();
+
+    let recent_piece_ids: Vec<i32> = pieces_performances::Entity::find()
+        .filter(pieces_performances::Column::PerformanceId.is_in(recent_performance_ids))
+        .all(db)
+        .await?
+        .iter()
+        .map(|piece_performance| piece_performance.piece_id)
+        .collect();
+
+    let pieces = pieces::Entity::find()
+        .filter(pieces::Column::Id.is_not_in(recent_piece_ids))
+        .all(db)
+        .await?;
+
+    let roles = get_roles(db).await?;
+    let mut pieces_with_data: Vec<Value> = vec![];
+
+    for piece in pieces {
+        pieces_with_data.push(get_data(db, piece, &roles).await?);
+    }
+
+    Ok(pieces_with_data)
+}
+
+/// Returns pieces with the stored copyright status (public_domain, protected or unknown).
+pub async fn get_by_copyright_status(
+    db: &DatabaseConnection,
+    status: String,
+) -> Result<Vec<Value>, DbErr> {
+    let pieces = pieces::Entity::find()
+        .filter(pieces::Column::CopyrightStatus.eq(status))
+        .all(db)
+        .await?;
+
+    let roles = get_roles(db).await?;
+    let mut pieces_with_data: Vec<Value> = vec![];
+
+    for piece in pieces {
+        pieces_with_data.push(get_data(db, piece, &roles).await?);
+    }
+
+    Ok(pieces_with_data)
+}
+
+pub async fn query(db: &DatabaseConnection, query: PieceQuery) -> Result<Vec<Value>, DbErr> {
+    let mut select = pieces::Entity::find();
+
+    if query.favorites_only {
+        select = select.filter(pieces::Column::IsFavorite.eq(true));
+    }
+    if let Some(min_rating) = query.min_rating {
+        select = select.filter(pieces::Column::Rating.gte(min_rating));
+    }
+    if let Some(min_audience_rating) = query.min_audience_rating {
+        select = select.filter(pieces::Column::AudienceRating.gte(min_audience_rating));
+    }
+
+    let column = match query.sort_by.as_deref().unwrap_or("title") {
+        "title" => pieces::Column::Title,
+        "year_published" => pieces::Column::YearPublished,
+        "difficulty" => pieces::Column::Difficulty,
+        "rating" => pieces::Column::Rating,
+        "audience_rating" => pieces::Column::AudienceRating,
+        "created_at" => pieces::Column::CreatedAt,
+        "updated_at" => pieces::Column::UpdatedAt,
+        sort_by => {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "Cannot sort pieces by {}",
+                sort_by
+            ))))
+        }
+    };
+    let order = match query.descending {
+        true => Order::Desc,
+        false => Order::Asc,
+    };
+
+    let pieces = select
+        .order_by(column, order)
+        .order_by_asc(pieces::Column::Id)
+        .all(db)
+        .await?;
+
+    let roles = get_roles(db).await?;
+    let mut pieces_with_data: Vec<Value> = vec![];
+
+    for piece in pieces {
+        pieces_with_data.push(get_data(db, piece, &roles).await?);
+    }
+
+    Ok(pieces_with_data)
+}
+
 pub async fn add(
     db: &DatabaseConnection,
     title: String,
@@ -178,6 +257,7 @@ pub async fn add(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn update(
     db: &DatabaseConnection,
     id: i32,
@@ -186,6 +266,8 @@ pub async fn update(
     path: String,
     difficulty: Option<i32>,
     notes: String,
+    working_directory: &Path,
+    templates: &Templates,
 ) -> Result<(), DbErr> {
     let piece = pieces::Entity::find_by_id(id).one(db).await?;
     match piece {
@@ -199,7 +281,150 @@ pub async fn update(
             piece.notes = ActiveValue::Set(notes);
             piece.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
 
-            let _result = pieces::Entity::update(piece).exec(db).await;
+            pieces::Entity::update(piece).exec(db).await?;
+
+            // a new title or year can mean a new folder name
+            rename_folder(db, working_directory, templates, id).await
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Piece with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn set_publication(
+    db: &DatabaseConnection,
+    id: i32,
+    publisher: Option<String>,
+    catalogue_number: Option<String>,
+    working_directory: &Path,
+    templates: &Templates,
+) -> Result<(), DbErr> {
+    let piece = pieces::Entity::find_by_id(id).one(db).await?;
+    match piece {
+        Some(piece) => {
+            let mut piece: pieces::ActiveModel = piece.into();
+
+            piece.publisher = ActiveValue::Set(publisher);
+            piece.catalogue_number = ActiveValue::Set(catalogue_number);
+            piece.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            pieces::Entity::update(piece).exec(db).await?;
+
+            // folder templates can use the catalogue number
+            rename_folder(db, working_directory, templates, id).await
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Piece with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn set_location(
+    db: &DatabaseConnection,
+    id: i32,
+    location_cabinet: Option<String>,
+    location_drawer: Option<String>,
+    location_box: Option<String>,
+) -> Result<(), DbErr> {
+    let piece = pieces::Entity::find_by_id(id).one(db).await?;
+    match piece {
+        Some(piece) => {
+            let mut piece: pieces::ActiveModel = piece.into();
+
+            piece.location_cabinet = ActiveValue::Set(location_cabinet);
+            piece.location_drawer = ActiveValue::Set(location_drawer);
+            piece.location_box = ActiveValue::Set(location_box);
+            piece.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            pieces::Entity::update(piece).exec(db).await?;
+
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Piece with id {} not found",
+            id
+        ))),
+    }
+}
+
+/// Suggests a title and composer for a new piece from the document info of a PDF,
+/// with the musicians already in the library whose name matches the author.
+pub async fn suggest_from_pdf(db: &DatabaseConnection, path: String) -> Result<Value, DbErr> {
+    let info = crate::pdf::read_info(&path)
+        .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string())))?;
+
+    let title = info.title.clone().or_else(|| {
+        Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+    });
+
+    let musicians = match &info.author {
+        Some(author) => crate::services::musicians::search(db, author.clone()).await?,
+        None => vec![],
+    };
+
+    Ok(serde_json::json!({
+        "title": title,
+        "composer": info.author,
+        "musicians": musicians,
+        "pdf": info,
+    }))
+}
+
+/// Sets the star rating and the audience reception, each from 1 to 5.
+pub async fn set_ratings(
+    db: &DatabaseConnection,
+    id: i32,
+    rating: Option<i32>,
+    audience_rating: Option<i32>,
+) -> Result<(), DbErr> {
+    for value in [rating, audience_rating].into_iter().flatten() {
+        if !(1..=5).contains(&value) {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "Rating {} is not between 1 and 5",
+                value
+            ))));
+        }
+    }
+
+    let piece = pieces::Entity::find_by_id(id).one(db).await?;
+    match piece {
+        Some(piece) => {
+            let mut piece: pieces::ActiveModel = piece.into();
+
+            piece.rating = ActiveValue::Set(rating);
+            piece.audience_rating = ActiveValue::Set(audience_rating);
+            piece.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            pieces::Entity::update(piece).exec(db).await?;
+
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Piece with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn set_favorite(
+    db: &DatabaseConnection,
+    id: i32,
+    is_favorite: bool,
+) -> Result<(), DbErr> {
+    let piece = pieces::Entity::find_by_id(id).one(db).await?;
+    match piece {
+        Some(piece) => {
+            let mut piece: pieces::ActiveModel = piece.into();
+
+            piece.is_favorite = ActiveValue::Set(is_favorite);
+            piece.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            pieces::Entity::update(piece).exec(db).await?;
 
             Ok(())
         }
@@ -224,28 +449,260 @@ pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
     }
 
     let _ = fs::remove_dir_all(path.clone());
-    let path = path.parent().unwrap();
-
-    if path.exists() {
-        let files = fs::read_dir(path).unwrap();
-        let mut is_empty = true;
-        for file in files {
-            let file = file.unwrap();
-            let file_name = file.file_name();
-            let file_name = file_name.to_str().unwrap();
-            if file_name != ".DS_Store" {
-                is_empty = false;
-            }
+    remove_dir_if_empty(path.parent().unwrap());
+
+    pieces::Entity::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
+/// Moves the piece folder on disk and rewrites every stored path that points inside it.
+/// The paths are only rewritten once the folder has moved, and the folder is moved back
+/// when they can't be. A piece without a folder on disk has no paths to rewrite.
+pub async fn move_folder(db: &DatabaseConnection, id: i32, new_path: PathBuf) -> Result<(), DbErr> {
+    let piece = pieces::Entity::find_by_id(id).one(db).await?;
+    let piece = match piece {
+        Some(piece) => piece,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Piece with id {} not found",
+                id
+            )))
+        }
+    };
+
+    let old_path = PathBuf::from(&piece.path);
+    if old_path == new_path {
+        return Ok(());
+    }
+
+    if new_path.exists() {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Cannot move piece with id {} because {} already exists",
+            id,
+            new_path.display()
+        ))));
+    }
+
+    let new_prefix = new_path.to_string_lossy().to_string();
+    let moved = !piece.path.is_empty() && old_path.exists();
+    // whole path components only, so "Piece 2" isn't taken for a file inside "Piece"
+    let rebase = |path: &str| -> Option<String> {
+        if !moved {
+            return None;
         }
-        if is_empty {
-            let _ = fs::remove_dir_all(path);
+        let rest = Path::new(path).strip_prefix(&old_path).ok()?;
+        match rest.as_os_str().is_empty() {
+            true => Some(new_prefix.clone()),
+            false => Some(new_path.join(rest).to_string_lossy().to_string()),
+        }
+    };
+
+    let txn = db.begin().await?;
+
+    let scores = scores::Entity::find()
+        .filter(scores::Column::PieceId.eq(id))
+        .all(&txn)
+        .await?;
+    for score in scores {
+        if let Some(path) = score.path.as_deref().and_then(rebase) {
+            let mut score: scores::ActiveModel = score.into();
+            score.path = ActiveValue::Set(Some(path));
+            scores::Entity::update(score).exec(&txn).await?;
         }
     }
 
-    pieces::Entity::delete_by_id(id).exec(db).await?;
+    let parts = parts::Entity::find()
+        .filter(parts::Column::PieceId.eq(id))
+        .all(&txn)
+        .await?;
+    let part_ids: Vec<i32> = parts.iter().map(|part| part.id).collect();
+    for part in parts {
+        if let Some(path) = part.path.as_deref().and_then(rebase) {
+            let mut part: parts::ActiveModel = part.into();
+            part.path = ActiveValue::Set(Some(path));
+            parts::Entity::update(part).exec(&txn).await?;
+        }
+    }
+
+    // every file of a part, earlier versions included
+    let part_files = part_files::Entity::find()
+        .filter(part_files::Column::PartId.is_in(part_ids))
+        .all(&txn)
+        .await?;
+    for part_file in part_files {
+        if let Some(path) = rebase(&part_file.path) {
+            let mut part_file: part_files::ActiveModel = part_file.into();
+            part_file.path = ActiveValue::Set(path);
+            part_files::Entity::update(part_file).exec(&txn).await?;
+        }
+    }
+
+    let mut piece: pieces::ActiveModel = piece.into();
+    piece.path = ActiveValue::Set(new_prefix.clone());
+    piece.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+    pieces::Entity::update(piece).exec(&txn).await?;
+
+    if moved {
+        let io_err = |e: std::io::Error| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string()));
+        if let Some(parent) = new_path.parent() {
+            fs::create_dir_all(parent).map_err(io_err)?;
+        }
+        // dropping the transaction rolls the paths back
+        fs::rename(&old_path, &new_path).map_err(io_err)?;
+    }
+
+    if let Err(e) = txn.commit().await {
+        if moved {
+            let _ = fs::rename(&new_path, &old_path);
+            if let Some(parent) = new_path.parent() {
+                remove_dir_if_empty(parent);
+            }
+        }
+        return Err(e);
+    }
+
+    if moved {
+        remove_dir_if_empty(old_path.parent().unwrap());
+    }
+
     Ok(())
 }
 
+/// Renames the piece folder when the folder template now names it differently,
+/// e.g. after the title or principal composer changed. Folders that are missing
+/// or kept outside the library are left where they are.
+pub async fn rename_folder(
+    db: &DatabaseConnection,
+    working_directory: &Path,
+    templates: &Templates,
+    id: i32,
+) -> Result<(), DbErr> {
+    let piece = pieces::Entity::find_by_id(id).one(db).await?;
+    let current = match piece {
+        Some(piece) => PathBuf::from(piece.path),
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Piece with id {} not found",
+                id
+            )))
+        }
+    };
+    if current.as_os_str().is_empty()
+        || !current.is_dir()
+        || !current.starts_with(working_directory)
+    {
+        return Ok(());
+    }
+
+    let folder = library_folder(db, working_directory, templates, id).await?;
+    move_folder(db, id, folder).await
+}
+
+/// What the piece is called in the library's naming templates. The first
+/// composer and arranger credited are the ones that count.
+pub async fn library_names(db: &DatabaseConnection, id: i32) -> Result<PieceNames, DbErr> {
+    let piece = pieces::Entity::find_by_id(id).one(db).await?;
+    let piece = match piece {
+        Some(piece) => piece,
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Piece with id {} not found",
+                id
+            )))
+        }
+    };
+
+    let composer = get_first_musician(db, id, "composer").await?;
+    let arranger = get_first_musician(db, id, "arranger").await?;
+
+    Ok(PieceNames {
+        piece_id: piece.id,
+        title: piece.title,
+        composer: composer.as_ref().map(sort_name),
+        composer_id: composer.map(|composer| composer.id),
+        arranger: arranger.as_ref().map(sort_name),
+        year: piece.year_published,
+        catalogue_number: piece.catalogue_number,
+    })
+}
+
+/// Where the folder template puts a piece in the library. When another piece
+/// or file already has that folder, a number is added, e.g. `First Suite in Eb (2)`.
+pub async fn library_folder(
+    db: &DatabaseConnection,
+    working_directory: &Path,
+    templates: &Templates,
+    id: i32,
+) -> Result<PathBuf, DbErr> {
+    let names = library_names(db, id).await?;
+    let folder = working_directory.join(templates.folder(&names));
+
+    let pieces = pieces::Entity::find().all(db).await?;
+    let current = pieces
+        .iter()
+        .find(|piece| piece.id == id)
+        .map(|piece| PathBuf::from(&piece.path));
+    // another piece can have the folder even when it isn't on disk
+    let is_free = |folder: &Path| {
+        Some(folder) == current.as_deref()
+            || (!folder.exists()
+                && !pieces
+                    .iter()
+                    .any(|piece| piece.id != id && Path::new(&piece.path) == folder))
+    };
+
+    Ok(numbered(&folder, is_free))
+}
+
+/// Moves the piece into the folder the templates give it, creating the folder
+/// when it isn't there yet. Returns the folder. Like `rename_folder`, a folder
+/// kept outside the library is left where it is.
+pub async fn move_to_library_folder(
+    db: &DatabaseConnection,
+    working_directory: &Path,
+    templates: &Templates,
+    id: i32,
+) -> Result<PathBuf, DbErr> {
+    let piece = pieces::Entity::find_by_id(id).one(db).await?;
+    let current = match piece {
+        Some(piece) => PathBuf::from(piece.path),
+        None => {
+            return Err(DbErr::RecordNotFound(format!(
+                "Piece with id {} not found",
+                id
+            )))
+        }
+    };
+    if !current.as_os_str().is_empty() {
+        if current.exists() && !current.is_dir() {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "{} is not a folder",
+                current.display()
+            ))));
+        }
+        if current.is_dir() && !current.starts_with(working_directory) {
+            return Ok(current);
+        }
+    }
+
+    let folder = library_folder(db, working_directory, templates, id).await?;
+    let has_name = folder
+        .file_name()
+        .is_some_and(|name| !name.to_string_lossy().trim().is_empty());
+    if !has_name || folder == working_directory || !folder.starts_with(working_directory) {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "The folder template gives piece with id {} no folder inside the library",
+            id
+        ))));
+    }
+
+    move_folder(db, id, folder.clone()).await?;
+    fs::create_dir_all(&folder)
+        .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string())))?;
+
+    Ok(folder)
+}
+
 pub async fn set_tags(
     db: &DatabaseConnection,
     piece_id: i32,
@@ -289,7 +746,11 @@ pub async fn set_musicians(
     piece_id: i32,
     musician_ids: Vec<i32>,
     role: String,
+    working_directory: &Path,
+    templates: &Templates,
 ) -> Result<(), DbErr> {
+    crate::services::roles::validate(db, &role).await?;
+
     let piece = pieces::Entity::find_by_id(piece_id).one(db).await?;
     match piece {
         Some(piece) => {
@@ -324,7 +785,8 @@ pub async fn set_musicians(
             piece.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
             let _result = pieces::Entity::update(piece).exec(db).await?;
 
-            Ok(())
+            // the principal composer or arranger can be part of the folder name
+            rename_folder(db, working_directory, templates, piece_id).await
         }
         None => Err(DbErr::RecordNotFound(format!(
             "Piece with id {} not found",
@@ -333,45 +795,188 @@ pub async fn set_musicians(
     }
 }
 
-pub async fn drop_scores(db: &DatabaseConnection, piece_id: i32) -> Result<(), DbErr> {
-    scores::Entity::delete_many()
-        .filter(scores::Column::PieceId.eq(piece_id))
-        .exec(db)
+/// Removes a folder with nothing left in it, such as a composer folder once its
+/// last piece folder is gone.
+pub(crate) fn remove_dir_if_empty(path: &Path) {
+    if !path.exists() {
+        return;
+    }
+
+    let files = fs::read_dir(path).unwrap();
+    let mut is_empty = true;
+    for file in files {
+        let file = file.unwrap();
+        let file_name = file.file_name();
+        let file_name = file_name.to_str().unwrap();
+        if file_name != ".DS_Store" {
+            is_empty = false;
+        }
+    }
+    if is_empty {
+        let _ = fs::remove_dir_all(path);
+    }
+}
+
+/// Everything shown about a piece. Pass the roles in, loaded once for all the
+/// pieces being returned.
+async fn get_data(
+    db: &DatabaseConnection,
+    piece: pieces::Model,
+    roles: &[roles::Model],
+) -> Result<Value, DbErr> {
+    let id = piece.id;
+
+    let setlists = get_setlists(db, id).await?;
+    let tags = get_tags(db, id).await?;
+    let scores = get_scores(db, id).await?;
+    let parts = get_parts(db, id).await?;
+    let acquisitions = crate::services::acquisitions::get_by_piece(db, id).await?;
+    let credits = get_credits(db, id, roles).await?;
+    let rehearsal_notes = crate::services::rehearsal_notes::get_by_piece(db, id).await?;
+    let (performance_count, last_performed) =
+        crate::services::performances::get_history(db, id).await?;
+
+    let piece = serde_json::json!({
+        "id": piece.id,
+        "title": piece.title,
+        "year_published": piece.year_published,
+        "path": piece.path,
+        "difficulty": piece.difficulty,
+        "notes": piece.notes,
+        "publisher": piece.publisher,
+        "catalogue_number": piece.catalogue_number,
+        "location_cabinet": piece.location_cabinet,
+        "location_drawer": piece.location_drawer,
+        "location_box": piece.location_box,
+        "copyright_status": piece.copyright_status,
+        "copyright_reason": piece.copyright_reason,
+        "copyright_rule": piece.copyright_rule,
+        "rating": piece.rating,
+        "audience_rating": piece.audience_rating,
+        "is_favorite": piece.is_favorite,
+        "created_at": piece.created_at,
+        "updated_at": piece.updated_at,
+        "scores": scores,
+        "parts": parts,
+        "setlists": setlists,
+        "tags": tags,
+        "acquisitions": acquisitions,
+        "rehearsal_notes": rehearsal_notes,
+        "performance_count": performance_count,
+        "last_performed": last_performed,
+        "roles": credits,
+    });
+
+    Ok(piece)
+}
+
+async fn get_by_tag_ids(db: &DatabaseConnection, tag_ids: Vec<i32>) -> Result<Vec<Value>, DbErr> {
+    let pieces = pieces::Entity::find()
+        .join_rev(JoinType::InnerJoin, pieces_tags::Relation::Pieces.def())
+        .filter(pieces_tags::Column::TagId.is_in(tag_ids))
+        .distinct()
+        .all(db)
         .await?;
 
-    Ok(())
+    let roles = get_roles(db).await?;
+    let mut pieces_with_data: Vec<Value> = vec![];
+
+    for piece in pieces {
+        pieces_with_data.push(get_data(db, piece, &roles).await?);
+    }
+
+    Ok(pieces_with_data)
 }
 
-pub async fn drop_parts(db: &DatabaseConnection, piece_id: i32) -> Result<(), DbErr> {
-    parts::Entity::delete_many()
-        .filter(parts::Column::PieceId.eq(piece_id))
-        .exec(db)
+async fn get_first_musician(
+    db: &DatabaseConnection,
+    piece_id: i32,
+    role: &str,
+) -> Result<Option<musicians::Model>, DbErr> {
+    let credit = pieces_musicians::Entity::find()
+        .filter(pieces_musicians::Column::PieceId.eq(piece_id))
+        .filter(pieces_musicians::Column::Role.eq(role))
+        .order_by_asc(pieces_musicians::Column::Order)
+        .one(db)
         .await?;
 
-    Ok(())
+    match credit {
+        Some(credit) => {
+            musicians::Entity::find_by_id(credit.musician_id)
+                .one(db)
+                .await
+        }
+        None => Ok(None),
+    }
 }
 
-async fn get_musicians(db: &DatabaseConnection, id: i32, role: &str) -> Result<Vec<Value>, DbErr> {
-    let composers_search_statement = musicians::Entity::find()
-        .join_rev(
-            JoinType::InnerJoin,
-            pieces_musicians::Relation::Musicians.def(),
-        )
-        .filter(
-            Condition::all()
-                .add(pieces_musicians::Column::PieceId.eq(id))
-                .add(pieces_musicians::Column::Role.eq(role)),
-        )
+/// The path itself when it is free, otherwise the first free one with a number
+/// after the name, before the `.pdf` of a file.
+pub(crate) fn numbered(path: &Path, is_free: impl Fn(&Path) -> bool) -> PathBuf {
+    if is_free(path) {
+        return path.to_path_buf();
+    }
+
+    // folders can have dots in their names, e.g. "Op. 5"
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (stem, extension) = match name.to_lowercase().ends_with(".pdf") {
+        true => (&name[..name.len() - 4], &name[name.len() - 4..]),
+        false => (name.as_str(), ""),
+    };
+
+    (2..)
+        .map(|number| path.with_file_name(format!("{} ({}){}", stem, number, extension)))
+        .find(|path| is_free(path))
+        .unwrap()
+}
+
+async fn get_roles(db: &DatabaseConnection) -> Result<Vec<roles::Model>, DbErr> {
+    roles::Entity::find()
+        .order_by_asc(roles::Column::Order)
+        .all(db)
+        .await
+}
+
+/// Returns the musicians credited on the piece, grouped by role in the roles' order.
+async fn get_credits(
+    db: &DatabaseConnection,
+    id: i32,
+    roles: &[roles::Model],
+) -> Result<Vec<Value>, DbErr> {
+    let credits = pieces_musicians::Entity::find()
+        .filter(pieces_musicians::Column::PieceId.eq(id))
         .order_by_asc(pieces_musicians::Column::Order)
-        .build(DbBackend::Sqlite);
+        .find_also_related(musicians::Entity)
+        .all(db)
+        .await?;
 
-    let composers = musicians::Entity::find()
-        .from_raw_sql(composers_search_statement)
-        .into_json()
+    let aliases = musician_aliases::Entity::find()
+        .filter(
+            musician_aliases::Column::MusicianId
+                .is_in(credits.iter().map(|(credit, _)| credit.musician_id)),
+        )
         .all(db)
         .await?;
 
-    Ok(composers)
+    Ok(roles
+        .iter()
+        .map(|role| {
+            let musicians: Vec<Value> = credits
+                .iter()
+                .filter(|(credit, _)| credit.role == role.name)
+                .filter_map(|(_, musician)| musician.as_ref())
+                .map(|musician| crate::services::musicians::to_json(musician, &aliases))
+                .collect();
+
+            serde_json::json!({
+                "role": role.name,
+                "musicians": musicians,
+            })
+        })
+        .collect())
 }
 
 async fn get_tags(db: &DatabaseConnection, id: i32) -> Result<Vec<Value>, DbErr> {
@@ -420,6 +1025,7 @@ async fn get_scores(db: &DatabaseConnection, id: i32) -> Result<Vec<Value>, DbEr
 async fn get_parts(db: &DatabaseConnection, id: i32) -> Result<Vec<Value>, DbErr> {
     let parts = parts::Entity::find()
         .filter(parts::Column::PieceId.eq(id))
+        .order_by_asc(parts::Column::Id)
         .all(db)
         .await?;
 
@@ -442,6 +1048,14 @@ async fn get_parts(db: &DatabaseConnection, id: i32) -> Result<Vec<Value>, DbErr
             "id": part.id,
             "name": part.name,
             "path": part.path,
+            "copies": part.copies,
+            "page_count": part.page_count,
+            "page_size": part.page_size,
+            "pdf_title": part.pdf_title,
+            "pdf_author": part.pdf_author,
+            "pdf_producer": part.pdf_producer,
+            "file_size": part.file_size,
+            "sha256": part.sha256,
             "instruments": instruments,
             "created_at": part.created_at,
             "updated_at": part.updated_at
@@ -453,6 +1067,29 @@ async fn get_parts(db: &DatabaseConnection, id: i32) -> Result<Vec<Value>, DbErr
     Ok(parts_with_instruments)
 }
 
+/// Returns the parts of a piece in standard score order. Parts whose instruments
+/// have no score order come last, in the order they were added.
+pub async fn get_parts_in_score_order(
+    db: &DatabaseConnection,
+    piece_id: i32,
+) -> Result<Vec<Value>, DbErr> {
+    let mut parts = get_parts(db, piece_id).await?;
+
+    // a part sits in the score where its highest-ranked instrument does
+    parts.sort_by_key(|part| {
+        let order = part["instruments"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|instrument| instrument["score_order"].as_i64())
+            .min()
+            .unwrap_or(i32::MAX as i64);
+        (order, part["id"].as_i64().unwrap_or_default())
+    });
+
+    Ok(parts)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -495,10 +1132,24 @@ mod tests {
         let db = init().await.unwrap();
 
         // Add a tag called "tag1" and another "tag2"
-        let add_tag_result = tags_add(&db, String::from("tag1"), String::from("#ffffff")).await;
+        let add_tag_result = tags_add(
+            &db,
+            String::from("tag1"),
+            Some(String::from("#ffffff")),
+            None,
+            None,
+        )
+        .await;
         assert!(add_tag_result.is_ok());
         let tag1_id = add_tag_result.unwrap();
-        let add_tag_result = tags_add(&db, String::from("tag2"), String::from("#000000")).await;
+        let add_tag_result = tags_add(
+            &db,
+            String::from("tag2"),
+            Some(String::from("#000000")),
+            None,
+            None,
+        )
+        .await;
         assert!(add_tag_result.is_ok());
         let tag2_id = add_tag_result.unwrap();
 
@@ -531,10 +1182,24 @@ mod tests {
         let db = init().await.unwrap();
 
         // Add a tag called "tag1" and another "tag2"
-        let add_tag_result = tags_add(&db, String::from("tag1"), String::from("#ffffff")).await;
+        let add_tag_result = tags_add(
+            &db,
+            String::from("tag1"),
+            Some(String::from("#ffffff")),
+            None,
+            None,
+        )
+        .await;
         assert!(add_tag_result.is_ok());
         let tag1_id = add_tag_result.unwrap();
-        let add_tag_result = tags_add(&db, String::from("tag2"), String::from("#000000")).await;
+        let add_tag_result = tags_add(
+            &db,
+            String::from("tag2"),
+            Some(String::from("#000000")),
+            None,
+            None,
+        )
+        .await;
         assert!(add_tag_result.is_ok());
         let tag2_id = add_tag_result.unwrap();
 
@@ -572,6 +1237,8 @@ mod tests {
             piece_id,
             vec![musician1_id, musician2_id],
             "composer".into(),
+            Path::new(""),
+            &Templates::default(),
         )
         .await;
         println!("{:#?}", add_piece_musician_result);
@@ -653,14 +1320,296 @@ mod tests {
             1,
             vec![musician1_id, musician2_id, musician3_id],
             "composer".into(),
+            Path::new(""),
+            &Templates::default(),
         )
         .await;
         assert!(add_musician_result.is_ok());
 
-        let get_composers_result = get_musicians(&db, 1, "composer").await;
+        let roles = get_roles(&db).await.unwrap();
+        let credits = get_credits(&db, 1, &roles).await.unwrap();
+        let composers = credits
+            .iter()
+            .find(|credit| credit["role"] == "composer")
+            .unwrap();
+        assert_eq!(composers["musicians"].as_array().unwrap().len(), 3);
+
+        let _ = db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_query_favorites() {
+        let db = init().await.unwrap();
+
+        let piece_id = add(
+            &db,
+            String::from("test favorite piece"),
+            None,
+            String::from("test path"),
+            None,
+            String::new(),
+        )
+        .await
+        .unwrap();
+
+        assert!(set_ratings(&db, piece_id, Some(6), None).await.is_err());
+        set_ratings(&db, piece_id, Some(5), Some(4)).await.unwrap();
+        set_favorite(&db, piece_id, true).await.unwrap();
+        crate::services::rehearsal_notes::add(&db, piece_id, String::from("watch bar 12"))
+            .await
+            .unwrap();
+
+        let favorites = query(
+            &db,
+            PieceQuery {
+                favorites_only: true,
+                min_rating: Some(5),
+                sort_by: Some(String::from("audience_rating")),
+                descending: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let piece = favorites
+            .iter()
+            .find(|piece| piece["id"] == piece_id)
+            .unwrap();
+        assert_eq!(piece["audience_rating"], 4);
+        assert_eq!(piece["rehearsal_notes"][0]["note"], "watch bar 12");
+
+        pieces::Entity::delete_by_id(piece_id)
+            .exec(&db)
+            .await
+            .unwrap();
+
+        let _ = db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_rename_folder() {
+        let db = init().await.unwrap();
+
+        let library = std::env::temp_dir().join("blackbird test rename folder");
+        let templates = Templates {
+            folder: String::from("{title}"),
+            ..Default::default()
+        };
+
+        let mut piece_ids = vec![];
+        for title in ["test renamed piece", "test other piece"] {
+            let piece_id = add(
+                &db,
+                String::from(title),
+                None,
+                String::new(),
+                None,
+                String::new(),
+            )
+            .await
+            .unwrap();
+            let folder = move_to_library_folder(&db, &library, &templates, piece_id)
+                .await
+                .unwrap();
+            fs::write(folder.join("part.pdf"), b"part").unwrap();
+            piece_ids.push(piece_id);
+        }
+        let part_path = library.join("test renamed piece").join("part.pdf");
+        let part_id = crate::services::parts::add(
+            &db,
+            String::from("Tuba"),
+            part_path.to_str().map(|path| path.to_string()),
+            piece_ids[0],
+        )
+        .await
+        .unwrap();
 
-        assert!(get_composers_result.is_ok());
+        // the other piece already has the folder, so this one is numbered
+        update(
+            &db,
+            piece_ids[0],
+            String::from("test other piece"),
+            None,
+            library
+                .join("test renamed piece")
+                .to_string_lossy()
+                .to_string(),
+            None,
+            String::new(),
+            &library,
+            &templates,
+        )
+        .await
+        .unwrap();
+
+        let folder = library.join("test other piece (2)");
+        assert!(folder.join("part.pdf").exists());
+        assert!(!library.join("test renamed piece").exists());
+        let part = parts::Entity::find_by_id(part_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            part.path,
+            folder
+                .join("part.pdf")
+                .to_str()
+                .map(|path| path.to_string())
+        );
+
+        for piece_id in piece_ids {
+            delete(&db, piece_id).await.unwrap();
+        }
+        let _ = fs::remove_dir_all(&library);
 
         let _ = db.close().await;
     }
+
+    #[tokio::test]
+    async fn test_move_folder_paths() {
+        let db = init().await.unwrap();
+
+        let library = std::env::temp_dir().join("blackbird test move folder paths");
+        let folder = library.join("Piece");
+        let sibling = library.join("Piece 2");
+        fs::create_dir_all(&folder).unwrap();
+        fs::create_dir_all(&sibling).unwrap();
+        let path = |path: PathBuf| path.to_str().map(|path| path.to_string());
+
+        // a new piece has no folder yet, so nothing it points to is moved
+        let new_piece_id = add(
+            &db,
+            String::from("test new piece"),
+            None,
+            String::new(),
+            None,
+            String::new(),
+        )
+        .await
+        .unwrap();
+        let elsewhere = crate::services::scores::add(
+            &db,
+            String::from("Full Score"),
+            path(sibling.join("score.pdf")),
+            new_piece_id,
+        )
+        .await
+        .unwrap();
+        move_folder(&db, new_piece_id, library.join("New"))
+            .await
+            .unwrap();
+        let score = scores::Entity::find_by_id(elsewhere)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(score.path, path(sibling.join("score.pdf")));
+
+        let piece_id = add(
+            &db,
+            String::from("test moved piece"),
+            None,
+            folder.to_string_lossy().to_string(),
+            None,
+            String::new(),
+        )
+        .await
+        .unwrap();
+        let inside = crate::services::parts::add(
+            &db,
+            String::from("Tuba"),
+            path(folder.join("Tuba.pdf")),
+            piece_id,
+        )
+        .await
+        .unwrap();
+        let in_sibling = crate::services::parts::add(
+            &db,
+            String::from("Horn"),
+            path(sibling.join("Horn.pdf")),
+            piece_id,
+        )
+        .await
+        .unwrap();
+
+        let moved = library.join("Moved");
+        move_folder(&db, piece_id, moved.clone()).await.unwrap();
+        assert!(moved.exists());
+        let part_path = |id| {
+            let db = &db;
+            async move {
+                parts::Entity::find_by_id(id)
+                    .one(db)
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .path
+            }
+        };
+        assert_eq!(part_path(inside).await, path(moved.join("Tuba.pdf")));
+        assert_eq!(part_path(in_sibling).await, path(sibling.join("Horn.pdf")));
+
+        delete(&db, piece_id).await.unwrap();
+        delete(&db, new_piece_id).await.unwrap();
+        let _ = fs::remove_dir_all(&library);
+
+        let _ = db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_parts_in_score_order() {
+        let db = init().await.unwrap();
+
+        let piece_id = add(
+            &db,
+            String::from("test score order piece"),
+            None,
+            String::new(),
+            None,
+            String::new(),
+        )
+        .await
+        .unwrap();
+        for name in ["Tuba", "Flute"] {
+            let instrument = instruments::Entity::find()
+                .filter(instruments::Column::Name.eq(name))
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            let part_id = crate::services::parts::add(&db, String::from(name), None, piece_id)
+                .await
+                .unwrap();
+            crate::services::parts::set_instruments(&db, part_id, vec![instrument.id])
+                .await
+                .unwrap();
+        }
+
+        // the piece keeps the order the parts were added in
+        let piece = get_by_id(&db, piece_id).await.unwrap();
+        assert_eq!(piece["parts"][0]["name"], "Tuba");
+
+        let parts = get_parts_in_score_order(&db, piece_id).await.unwrap();
+        assert_eq!(parts[0]["name"], "Flute");
+        assert_eq!(parts[1]["name"], "Tuba");
+
+        delete(&db, piece_id).await.unwrap();
+
+        let _ = db.close().await;
+    }
+
+    #[test]
+    fn test_numbered() {
+        let taken = [PathBuf::from("Op. 5"), PathBuf::from("Op. 5 (2)")];
+        let is_free = |path: &Path| !taken.iter().any(|taken| taken == path);
+        assert_eq!(
+            numbered(Path::new("Op. 5"), is_free),
+            PathBuf::from("Op. 5 (3)")
+        );
+        assert_eq!(
+            numbered(Path::new("Tuba.pdf"), |path| path != Path::new("Tuba.pdf")),
+            PathBuf::from("Tuba (2).pdf")
+        );
+    }
 }
@@ -0,0 +1,133 @@
+use sea_orm::{
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, JoinType, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait,
+};
+
+use crate::entities::{ensemble_parts_players, ensembles, ensembles_parts, players};
+
+use serde_json::Value;
+
+pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<Value>, DbErr> {
+    let players = players::Entity::find()
+        .order_by_asc(players::Column::Name)
+        .into_json()
+        .all(db)
+        .await?;
+
+    Ok(players)
+}
+
+pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr> {
+    let player = players::Entity::find_by_id(id).one(db).await?;
+
+    match player {
+        Some(player) => {
+            let chairs = get_chairs(db, id).await?;
+
+            let player = serde_json::json!({
+                "id": player.id,
+                "name": player.name,
+                "email": player.email,
+                "phone": player.phone,
+                "notes": player.notes,
+                "created_at": player.created_at,
+                "updated_at": player.updated_at,
+                "chairs": chairs,
+            });
+
+            Ok(player)
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Player with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn add(
+    db: &DatabaseConnection,
+    name: String,
+    email: Option<String>,
+    phone: Option<String>,
+    notes: Option<String>,
+) -> Result<i32, DbErr> {
+    let active_player = players::ActiveModel {
+        name: ActiveValue::Set(name),
+        email: ActiveValue::Set(email),
+        phone: ActiveValue::Set(phone),
+        notes: ActiveValue::Set(notes),
+        ..Default::default()
+    };
+
+    let player = players::Entity::insert(active_player).exec(db).await?;
+    Ok(player.last_insert_id)
+}
+
+pub async fn update(
+    db: &DatabaseConnection,
+    id: i32,
+    name: String,
+    email: Option<String>,
+    phone: Option<String>,
+    notes: Option<String>,
+) -> Result<(), DbErr> {
+    let player = players::Entity::find_by_id(id).one(db).await?;
+    match player {
+        Some(player) => {
+            let mut player: players::ActiveModel = player.into();
+
+            player.name = ActiveValue::Set(name);
+            player.email = ActiveValue::Set(email);
+            player.phone = ActiveValue::Set(phone);
+            player.notes = ActiveValue::Set(notes);
+            player.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            players::Entity::update(player).exec(db).await?;
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Player with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    players::Entity::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
+/// Returns the ensemble chairs the player sits in.
+async fn get_chairs(db: &DatabaseConnection, id: i32) -> Result<Vec<Value>, DbErr> {
+    let chairs = ensembles_parts::Entity::find()
+        .join_rev(
+            JoinType::InnerJoin,
+            ensemble_parts_players::Relation::EnsemblesParts.def(),
+        )
+        .filter(ensemble_parts_players::Column::PlayerId.eq(id))
+        .all(db)
+        .await?;
+
+    let ensembles = ensembles::Entity::find()
+        .filter(ensembles::Column::Id.is_in(chairs.iter().map(|chair| chair.ensemble_id)))
+        .all(db)
+        .await?;
+
+    let chairs = chairs
+        .iter()
+        .map(|chair| {
+            let ensemble = ensembles
+                .iter()
+                .find(|ensemble| ensemble.id == chair.ensemble_id);
+
+            serde_json::json!({
+                "id": chair.id,
+                "name": chair.name,
+                "ensemble_id": chair.ensemble_id,
+                "ensemble_name": ensemble.map(|ensemble| ensemble.name.clone()),
+            })
+        })
+        .collect();
+
+    Ok(chairs)
+}
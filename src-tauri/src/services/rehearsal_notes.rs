@@ -0,0 +1,72 @@
+use sea_orm::{
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::entities::{pieces, rehearsal_notes};
+
+use serde_json::Value;
+
+/// Returns the notes taken on a piece, newest first.
+pub async fn get_by_piece(db: &DatabaseConnection, piece_id: i32) -> Result<Vec<Value>, DbErr> {
+    let notes = rehearsal_notes::Entity::find()
+        .filter(rehearsal_notes::Column::PieceId.eq(piece_id))
+        .order_by_desc(rehearsal_notes::Column::CreatedAt)
+        .order_by_desc(rehearsal_notes::Column::Id)
+        .into_json()
+        .all(db)
+        .await?;
+
+    Ok(notes)
+}
+
+pub async fn add(db: &DatabaseConnection, piece_id: i32, note: String) -> Result<i32, DbErr> {
+    let piece = pieces::Entity::find_by_id(piece_id).one(db).await?;
+    if piece.is_none() {
+        return Err(DbErr::RecordNotFound(format!(
+            "Piece with id {} not found",
+            piece_id
+        )));
+    }
+
+    let now = chrono::Local::now().naive_local().to_string();
+    let active_note = rehearsal_notes::ActiveModel {
+        piece_id: ActiveValue::Set(piece_id),
+        note: ActiveValue::Set(note),
+        // the column default is fixed when the table is created, so stamp each note
+        created_at: ActiveValue::Set(now.clone()),
+        updated_at: ActiveValue::Set(now),
+        ..Default::default()
+    };
+
+    let note = rehearsal_notes::Entity::insert(active_note)
+        .exec(db)
+        .await?;
+    Ok(note.last_insert_id)
+}
+
+pub async fn update(db: &DatabaseConnection, id: i32, note: String) -> Result<(), DbErr> {
+    let rehearsal_note = rehearsal_notes::Entity::find_by_id(id).one(db).await?;
+    match rehearsal_note {
+        Some(rehearsal_note) => {
+            let mut rehearsal_note: rehearsal_notes::ActiveModel = rehearsal_note.into();
+
+            rehearsal_note.note = ActiveValue::Set(note);
+            rehearsal_note.updated_at =
+                ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            rehearsal_notes::Entity::update(rehearsal_note)
+                .exec(db)
+                .await?;
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Rehearsal note with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    rehearsal_notes::Entity::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
@@ -0,0 +1,203 @@
+use sea_orm::{
+    sea_query::Expr, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+
+use crate::entities::{pieces_musicians, roles};
+
+use serde_json::Value;
+
+pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<Value>, DbErr> {
+    let roles = roles::Entity::find()
+        .order_by_asc(roles::Column::Order)
+        .into_json()
+        .all(db)
+        .await?;
+
+    Ok(roles)
+}
+
+pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr> {
+    let role = roles::Entity::find_by_id(id).into_json().one(db).await?;
+    match role {
+        Some(role) => Ok(role),
+        None => Err(DbErr::RecordNotFound(format!(
+            "Role with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn add(db: &DatabaseConnection, name: String) -> Result<i32, DbErr> {
+    let name = normalize_name(&name);
+
+    let role = roles::Entity::find()
+        .filter(roles::Column::Name.eq(&name))
+        .one(db)
+        .await?;
+
+    if role.is_some() {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Role with name {} already exists",
+            name
+        ))));
+    }
+
+    let last_role = roles::Entity::find()
+        .order_by_desc(roles::Column::Order)
+        .one(db)
+        .await?;
+    let order = last_role.map_or(1, |role| role.order + 1);
+
+    let active_role = roles::ActiveModel {
+        name: ActiveValue::Set(name),
+        order: ActiveValue::Set(order),
+        is_default: ActiveValue::Set(false),
+        ..Default::default()
+    };
+
+    let role = roles::Entity::insert(active_role).exec(db).await?;
+    Ok(role.last_insert_id)
+}
+
+pub async fn update(db: &DatabaseConnection, id: i32, name: String) -> Result<(), DbErr> {
+    let role = roles::Entity::find_by_id(id).one(db).await?;
+    match role {
+        Some(role) => {
+            if role.is_default {
+                return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                    "Cannot update role with id {} because it is a default",
+                    id
+                ))));
+            }
+
+            let name = normalize_name(&name);
+            let old_name = role.name.clone();
+
+            let existing = roles::Entity::find()
+                .filter(roles::Column::Name.eq(&name))
+                .filter(roles::Column::Id.ne(id))
+                .one(db)
+                .await?;
+            if existing.is_some() {
+                return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                    "Role with name {} already exists",
+                    name
+                ))));
+            }
+
+            let txn = db.begin().await?;
+
+            // credits reference roles by name
+            pieces_musicians::Entity::update_many()
+                .col_expr(pieces_musicians::Column::Role, Expr::value(name.clone()))
+                .filter(pieces_musicians::Column::Role.eq(old_name))
+                .exec(&txn)
+                .await?;
+
+            let mut role: roles::ActiveModel = role.into();
+            role.name = ActiveValue::Set(name);
+            role.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+            roles::Entity::update(role).exec(&txn).await?;
+
+            txn.commit().await
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Role with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    let role = roles::Entity::find_by_id(id).one(db).await?;
+    if let Some(role) = role {
+        if role.is_default {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "Cannot delete role with id {} because it is a default",
+                id
+            ))));
+        }
+
+        let credits = pieces_musicians::Entity::find()
+            .filter(pieces_musicians::Column::Role.eq(role.name))
+            .count(db)
+            .await?;
+        if credits > 0 {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "Cannot delete role with id {} because {} credits still use it",
+                id, credits
+            ))));
+        }
+    }
+
+    roles::Entity::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
+/// Fails unless a role with the name exists.
+pub async fn validate(db: &DatabaseConnection, name: &str) -> Result<(), DbErr> {
+    let role = roles::Entity::find()
+        .filter(roles::Column::Name.eq(name))
+        .one(db)
+        .await?;
+
+    match role {
+        Some(_) => Ok(()),
+        None => Err(DbErr::RecordNotFound(format!(
+            "Role with name {} not found",
+            name
+        ))),
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+
+    #[tokio::test]
+    async fn test_default_roles() {
+        let db = init().await.unwrap();
+
+        assert!(validate(&db, "composer").await.is_ok());
+        assert!(validate(&db, "not a role").await.is_err());
+
+        let id = add(&db, String::from(" Dedicatee ")).await.unwrap();
+        let role = get_by_id(&db, id).await.unwrap();
+        assert_eq!(role["name"], "dedicatee");
+        assert!(add(&db, String::from("dedicatee")).await.is_err());
+
+        delete(&db, id).await.unwrap();
+
+        let close_result = db.close().await;
+        assert!(close_result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_update_onto_existing_name() {
+        let db = init().await.unwrap();
+
+        let id = add(&db, String::from("test editor")).await.unwrap();
+        let other_id = add(&db, String::from("test engraver")).await.unwrap();
+        assert!(update(&db, id, String::from("Test Engraver"))
+            .await
+            .is_err());
+
+        let role = get_by_id(&db, id).await.unwrap();
+        assert_eq!(role["name"], "test editor");
+
+        update(&db, id, String::from("test copyist")).await.unwrap();
+        let role = get_by_id(&db, id).await.unwrap();
+        assert_eq!(role["name"], "test copyist");
+
+        delete(&db, id).await.unwrap();
+        delete(&db, other_id).await.unwrap();
+
+        let _ = db.close().await;
+    }
+}
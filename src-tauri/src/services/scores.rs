@@ -1,9 +1,13 @@
 use sea_orm::{ActiveValue, DatabaseConnection, DbErr, EntityTrait};
 
 use crate::entities::scores;
+use crate::{pdf, utils};
 
 use serde_json::Value;
 
+/// Words that make a file a score rather than a part, e.g. "Full Score".
+pub const SCORE_WORDS: [&str; 4] = ["score", "conductor", "condensed", "full"];
+
 pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<Value>, DbErr> {
     let scores = scores::Entity::find().into_json().all(db).await?;
 
@@ -30,12 +34,12 @@ pub async fn add(
     path: Option<String>,
     piece_id: i32,
 ) -> Result<i32, DbErr> {
-    let active_score = scores::ActiveModel {
+    let mut active_score = scores::ActiveModel {
         name: ActiveValue::Set(name),
-        path: ActiveValue::Set(path),
         piece_id: ActiveValue::Set(piece_id),
         ..Default::default()
     };
+    set_path(&mut active_score, path);
 
     let score = scores::Entity::insert(active_score).exec(db).await?;
 
@@ -54,7 +58,7 @@ pub async fn update(
             let mut score: scores::ActiveModel = score.into();
 
             score.name = ActiveValue::Set(name);
-            score.path = ActiveValue::Set(path);
+            set_path(&mut score, path);
             score.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
 
             let _result = scores::Entity::update(score).exec(db).await?;
@@ -72,3 +76,48 @@ pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
     scores::Entity::delete_by_id(id).exec(db).await?;
     Ok(())
 }
+
+/// Reads the PDF again, e.g. after the file was replaced outside the library.
+pub async fn refresh_pdf_info(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    let score = scores::Entity::find_by_id(id).one(db).await?;
+    match score {
+        Some(score) => {
+            let path = score.path.clone();
+            let mut score: scores::ActiveModel = score.into();
+
+            set_path(&mut score, path);
+            score.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            scores::Entity::update(score).exec(db).await?;
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Score with id {} not found",
+            id
+        ))),
+    }
+}
+
+/// Whether a file name or part name is for a score, e.g. "Conductor" or "Full Score".
+pub fn is_score_name(name: &str) -> bool {
+    crate::services::instruments::name_words(name)
+        .iter()
+        .any(|word| SCORE_WORDS.contains(&word.as_str()))
+}
+
+/// Stores the path along with the file's hash and what can be read from it when it is a PDF.
+fn set_path(score: &mut scores::ActiveModel, path: Option<String>) {
+    let info = pdf::try_read_info(&path);
+    let sha256 = path
+        .as_deref()
+        .and_then(|path| utils::sha256_file(path).ok());
+
+    score.path = ActiveValue::Set(path);
+    score.page_count = ActiveValue::Set(info.as_ref().map(|info| info.page_count));
+    score.page_size = ActiveValue::Set(info.as_ref().and_then(|info| info.page_size.clone()));
+    score.pdf_title = ActiveValue::Set(info.as_ref().and_then(|info| info.title.clone()));
+    score.pdf_author = ActiveValue::Set(info.as_ref().and_then(|info| info.author.clone()));
+    score.pdf_producer = ActiveValue::Set(info.as_ref().and_then(|info| info.producer.clone()));
+    score.file_size = ActiveValue::Set(info.as_ref().map(|info| info.file_size));
+    score.sha256 = ActiveValue::Set(sha256);
+}
@@ -1,7 +1,7 @@
 use crate::entities::{pieces, pieces_setlists, setlists};
 use sea_orm::{
-    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, JoinType, QueryFilter,
-    QuerySelect, RelationTrait,
+    ActiveValue, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, JoinType,
+    QueryFilter, QueryOrder, QuerySelect, RelationTrait,
 };
 use serde_json::Value;
 
@@ -18,6 +18,7 @@ pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr>
             let pieces = pieces::Entity::find()
                 .join_rev(JoinType::InnerJoin, pieces_setlists::Relation::Pieces.def())
                 .filter(pieces_setlists::Column::SetlistId.eq(id))
+                .order_by_asc(pieces_setlists::Column::Order)
                 .into_json()
                 .all(db)
                 .await?;
@@ -81,10 +82,17 @@ pub async fn add_piece(
     setlist_id: i32,
     piece_id: i32,
 ) -> Result<(), DbErr> {
+    // new pieces go to the end of the setlist
+    let last = pieces_setlists::Entity::find()
+        .filter(pieces_setlists::Column::SetlistId.eq(setlist_id))
+        .order_by_desc(pieces_setlists::Column::Order)
+        .one(db)
+        .await?;
+
     let active_piece_setlist = pieces_setlists::ActiveModel {
         piece_id: ActiveValue::Set(piece_id),
         setlist_id: ActiveValue::Set(setlist_id),
-        ..Default::default()
+        order: ActiveValue::Set(last.map_or(1, |last| last.order + 1)),
     };
 
     pieces_setlists::Entity::insert(active_piece_setlist)
@@ -94,6 +102,30 @@ pub async fn add_piece(
     Ok(())
 }
 
+/// Puts the pieces of a setlist in the order of `piece_ids`.
+pub async fn set_order(
+    db: &DatabaseConnection,
+    setlist_id: i32,
+    piece_ids: Vec<i32>,
+) -> Result<(), DbErr> {
+    for (index, piece_id) in piece_ids.iter().enumerate() {
+        pieces_setlists::Entity::update_many()
+            .col_expr(
+                pieces_setlists::Column::Order,
+                sea_orm::sea_query::Expr::value(index as i32 + 1),
+            )
+            .filter(
+                Condition::all()
+                    .add(pieces_setlists::Column::SetlistId.eq(setlist_id))
+                    .add(pieces_setlists::Column::PieceId.eq(*piece_id)),
+            )
+            .exec(db)
+            .await?;
+    }
+
+    Ok(())
+}
+
 pub async fn remove_piece(
     db: &DatabaseConnection,
     setlist_id: i32,
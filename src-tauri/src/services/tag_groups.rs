@@ -0,0 +1,138 @@
+use sea_orm::{
+    sea_query::Expr, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+};
+
+use crate::entities::{tag_groups, tags};
+use crate::services::tags::to_tree;
+
+use serde_json::Value;
+
+pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<Value>, DbErr> {
+    let groups = tag_groups::Entity::find().all(db).await?;
+
+    let mut groups_with_tags: Vec<Value> = vec![];
+
+    for group in groups {
+        let tags = get_tags(db, group.id).await?;
+
+        let group = serde_json::json!({
+            "id": group.id,
+            "name": group.name,
+            "color": group.color,
+            "created_at": group.created_at,
+            "updated_at": group.updated_at,
+            "tags": tags,
+        });
+
+        groups_with_tags.push(group);
+    }
+
+    Ok(groups_with_tags)
+}
+
+pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr> {
+    let group = tag_groups::Entity::find_by_id(id).one(db).await?;
+
+    match group {
+        Some(group) => {
+            let tags = get_tags(db, id).await?;
+
+            let group = serde_json::json!({
+                "id": group.id,
+                "name": group.name,
+                "color": group.color,
+                "created_at": group.created_at,
+                "updated_at": group.updated_at,
+                "tags": tags,
+            });
+
+            Ok(group)
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Tag group with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn add(
+    db: &DatabaseConnection,
+    name: String,
+    color: Option<String>,
+) -> Result<i32, DbErr> {
+    let active_group = tag_groups::ActiveModel {
+        name: ActiveValue::Set(name),
+        color: ActiveValue::Set(color),
+        ..Default::default()
+    };
+
+    let group = tag_groups::Entity::insert(active_group).exec(db).await?;
+    Ok(group.last_insert_id)
+}
+
+/// Renames the group and sets its color. Tags still on the group's old color
+/// change with it; tags given their own color keep it.
+pub async fn update(
+    db: &DatabaseConnection,
+    id: i32,
+    name: String,
+    color: Option<String>,
+) -> Result<(), DbErr> {
+    let group = tag_groups::Entity::find_by_id(id).one(db).await?;
+    match group {
+        Some(group) => {
+            if let (Some(old_color), Some(color)) = (&group.color, &color) {
+                tags::Entity::update_many()
+                    .col_expr(tags::Column::Color, Expr::value(color.clone()))
+                    .filter(tags::Column::GroupId.eq(id))
+                    .filter(tags::Column::Color.eq(old_color.clone()))
+                    .exec(db)
+                    .await?;
+            }
+
+            let mut group: tag_groups::ActiveModel = group.into();
+
+            group.name = ActiveValue::Set(name);
+            group.color = ActiveValue::Set(color);
+            group.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+
+            tag_groups::Entity::update(group).exec(db).await?;
+            Ok(())
+        }
+        None => Err(DbErr::RecordNotFound(format!(
+            "Tag group with id {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    // tags outlive their group
+    tags::Entity::update_many()
+        .col_expr(tags::Column::GroupId, Expr::value(Option::<i32>::None))
+        .filter(tags::Column::GroupId.eq(id))
+        .exec(db)
+        .await?;
+
+    tag_groups::Entity::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
+async fn get_tags(db: &DatabaseConnection, id: i32) -> Result<Vec<Value>, DbErr> {
+    let tags = tags::Entity::find()
+        .filter(tags::Column::GroupId.eq(id))
+        .all(db)
+        .await?;
+
+    // a tag is a root within the group when its parent sits outside of it
+    let tags_tree = tags
+        .iter()
+        .filter(|tag| match tag.parent_id {
+            Some(parent_id) => !tags.iter().any(|other| other.id == parent_id),
+            None => true,
+        })
+        .map(|tag| to_tree(&tags, tag))
+        .collect();
+
+    Ok(tags_tree)
+}
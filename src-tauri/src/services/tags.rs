@@ -1,12 +1,18 @@
-use sea_orm::{ActiveValue, DatabaseConnection, DbErr, EntityTrait};
+use sea_orm::{
+    sea_query::Expr, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    TransactionTrait,
+};
 
-use crate::entities::tags;
+use crate::entities::{pieces_tags, tag_groups, tags};
 
 use serde_json::Value;
 
+const DEFAULT_COLOR: &str = "#ffffff";
+
+/// Returns the top-level tags, each with its `children` nested below it.
 pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<Value>, DbErr> {
-    let tags = tags::Entity::find().into_json().all(db).await?;
-    Ok(tags)
+    let tags = tags::Entity::find().all(db).await?;
+    Ok(build_tree(&tags, None))
 }
 
 pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr> {
@@ -20,10 +26,35 @@ pub async fn get_by_id(db: &DatabaseConnection, id: i32) -> Result<Value, DbErr>
     }
 }
 
-pub async fn add(db: &DatabaseConnection, name: String, color: String) -> Result<i32, DbErr> {
+pub async fn add(
+    db: &DatabaseConnection,
+    name: String,
+    color: Option<String>,
+    parent_id: Option<i32>,
+    group_id: Option<i32>,
+) -> Result<i32, DbErr> {
+    let parent = match parent_id {
+        Some(parent_id) => Some(find(db, parent_id).await?),
+        None => None,
+    };
+
+    // a child tag lives in its parent's group unless told otherwise
+    let group_id = group_id.or_else(|| parent.as_ref().and_then(|parent| parent.group_id));
+    let group = match group_id {
+        Some(group_id) => Some(find_group(db, group_id).await?),
+        None => None,
+    };
+
+    let color = color
+        .or_else(|| group.and_then(|group| group.color))
+        .or_else(|| parent.map(|parent| parent.color))
+        .unwrap_or_else(|| String::from(DEFAULT_COLOR));
+
     let active_tag = tags::ActiveModel {
         name: ActiveValue::Set(name),
         color: ActiveValue::Set(color),
+        parent_id: ActiveValue::Set(parent_id),
+        group_id: ActiveValue::Set(group_id),
         ..Default::default()
     };
 
@@ -56,7 +87,296 @@ pub async fn update(
     }
 }
 
+pub async fn set_parent(
+    db: &DatabaseConnection,
+    id: i32,
+    parent_id: Option<i32>,
+) -> Result<(), DbErr> {
+    let tag = find(db, id).await?;
+
+    if let Some(parent_id) = parent_id {
+        find(db, parent_id).await?;
+
+        if get_descendant_ids(db, id).await?.contains(&parent_id) {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "Tag with id {} cannot be nested under its own descendant {}",
+                id, parent_id
+            ))));
+        }
+    }
+
+    let mut tag: tags::ActiveModel = tag.into();
+    tag.parent_id = ActiveValue::Set(parent_id);
+    tag.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+    tags::Entity::update(tag).exec(db).await?;
+
+    Ok(())
+}
+
+/// Moves the tag into a group, or out of any. A tag joining a group with a
+/// color takes that color, like tags added to the group do.
+pub async fn set_group(
+    db: &DatabaseConnection,
+    id: i32,
+    group_id: Option<i32>,
+) -> Result<(), DbErr> {
+    let tag = find(db, id).await?;
+
+    let group = match group_id {
+        Some(group_id) => Some(find_group(db, group_id).await?),
+        None => None,
+    };
+
+    let mut tag: tags::ActiveModel = tag.into();
+    tag.group_id = ActiveValue::Set(group_id);
+    if let Some(color) = group.and_then(|group| group.color) {
+        tag.color = ActiveValue::Set(color);
+    }
+    tag.updated_at = ActiveValue::Set(chrono::Local::now().naive_local().to_string());
+    tags::Entity::update(tag).exec(db).await?;
+
+    Ok(())
+}
+
 pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
+    let tag = tags::Entity::find_by_id(id).one(db).await?;
+
+    // hand any children over to the deleted tag's parent
+    if let Some(tag) = tag {
+        tags::Entity::update_many()
+            .col_expr(tags::Column::ParentId, Expr::value(tag.parent_id))
+            .filter(tags::Column::ParentId.eq(id))
+            .exec(db)
+            .await?;
+    }
+
     tags::Entity::delete_by_id(id).exec(db).await?;
     Ok(())
 }
+
+/// Folds the duplicates into the survivor, re-pointing their pieces and child tags.
+pub async fn merge(
+    db: &DatabaseConnection,
+    survivor_id: i32,
+    duplicate_ids: Vec<i32>,
+) -> Result<(), DbErr> {
+    find(db, survivor_id).await?;
+
+    // merging a tag into its own descendant would orphan the subtree
+    let survivor_ancestors: Vec<i32> = {
+        let tags = tags::Entity::find().all(db).await?;
+        let mut ancestors = vec![];
+        let mut current = tags.iter().find(|tag| tag.id == survivor_id);
+        while let Some(parent_id) = current.and_then(|tag| tag.parent_id) {
+            if ancestors.contains(&parent_id) {
+                break;
+            }
+            ancestors.push(parent_id);
+            current = tags.iter().find(|tag| tag.id == parent_id);
+        }
+        ancestors
+    };
+
+    let duplicate_ids: Vec<i32> = duplicate_ids
+        .into_iter()
+        .filter(|id| *id != survivor_id)
+        .collect();
+
+    if let Some(id) = duplicate_ids
+        .iter()
+        .find(|id| survivor_ancestors.contains(id))
+    {
+        return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+            "Tag with id {} cannot be merged into its descendant {}",
+            id, survivor_id
+        ))));
+    }
+
+    let txn = db.begin().await?;
+
+    let pieces_tags = pieces_tags::Entity::find()
+        .filter(pieces_tags::Column::TagId.is_in(duplicate_ids.clone()))
+        .all(&txn)
+        .await?;
+
+    for piece_tag in pieces_tags {
+        let existing = pieces_tags::Entity::find_by_id((piece_tag.piece_id, survivor_id))
+            .one(&txn)
+            .await?;
+
+        pieces_tags::Entity::delete_by_id((piece_tag.piece_id, piece_tag.tag_id))
+            .exec(&txn)
+            .await?;
+
+        if existing.is_none() {
+            let active_piece_tag = pieces_tags::ActiveModel {
+                piece_id: ActiveValue::Set(piece_tag.piece_id),
+                tag_id: ActiveValue::Set(survivor_id),
+            };
+
+            pieces_tags::Entity::insert(active_piece_tag)
+                .exec(&txn)
+                .await?;
+        }
+    }
+
+    tags::Entity::update_many()
+        .col_expr(tags::Column::ParentId, Expr::value(survivor_id))
+        .filter(tags::Column::ParentId.is_in(duplicate_ids.clone()))
+        .exec(&txn)
+        .await?;
+
+    tags::Entity::delete_many()
+        .filter(tags::Column::Id.is_in(duplicate_ids))
+        .exec(&txn)
+        .await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Returns the id of the tag and of every tag nested below it.
+pub async fn get_descendant_ids(db: &DatabaseConnection, id: i32) -> Result<Vec<i32>, DbErr> {
+    let tags = tags::Entity::find().all(db).await?;
+
+    let mut ids = vec![id];
+    let mut index = 0;
+
+    while index < ids.len() {
+        let parent_id = ids[index];
+        for tag in &tags {
+            if tag.parent_id == Some(parent_id) && !ids.contains(&tag.id) {
+                ids.push(tag.id);
+            }
+        }
+        index += 1;
+    }
+
+    Ok(ids)
+}
+
+pub(crate) fn build_tree(tags: &[tags::Model], parent_id: Option<i32>) -> Vec<Value> {
+    tags.iter()
+        .filter(|tag| tag.parent_id == parent_id)
+        .map(|tag| to_tree(tags, tag))
+        .collect()
+}
+
+pub(crate) fn to_tree(tags: &[tags::Model], tag: &tags::Model) -> Value {
+    serde_json::json!({
+        "id": tag.id,
+        "name": tag.name,
+        "color": tag.color,
+        "parent_id": tag.parent_id,
+        "group_id": tag.group_id,
+        "created_at": tag.created_at,
+        "updated_at": tag.updated_at,
+        "children": build_tree(tags, Some(tag.id)),
+    })
+}
+
+async fn find(db: &DatabaseConnection, id: i32) -> Result<tags::Model, DbErr> {
+    let tag = tags::Entity::find_by_id(id).one(db).await?;
+    match tag {
+        Some(tag) => Ok(tag),
+        None => Err(DbErr::RecordNotFound(format!(
+            "Tag with id {} not found",
+            id
+        ))),
+    }
+}
+
+async fn find_group(db: &DatabaseConnection, id: i32) -> Result<tag_groups::Model, DbErr> {
+    let group = tag_groups::Entity::find_by_id(id).one(db).await?;
+    match group {
+        Some(group) => Ok(group),
+        None => Err(DbErr::RecordNotFound(format!(
+            "Tag group with id {} not found",
+            id
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+
+    #[tokio::test]
+    async fn test_descendants() {
+        let db = init().await.unwrap();
+
+        let holiday_id = add(&db, String::from("Holiday"), None, None, None)
+            .await
+            .unwrap();
+        let christmas_id = add(&db, String::from("Christmas"), None, Some(holiday_id), None)
+            .await
+            .unwrap();
+        let carols_id = add(&db, String::from("Carols"), None, Some(christmas_id), None)
+            .await
+            .unwrap();
+
+        let tree = get_all(&db).await.unwrap();
+        let holiday = tree.iter().find(|tag| tag["id"] == holiday_id).unwrap();
+        assert_eq!(holiday["children"][0]["id"], christmas_id);
+        assert_eq!(holiday["children"][0]["children"][0]["id"], carols_id);
+
+        let descendant_ids = get_descendant_ids(&db, holiday_id).await.unwrap();
+        assert!(descendant_ids.contains(&christmas_id));
+        assert!(descendant_ids.contains(&carols_id));
+
+        // nesting a tag under its own descendant would create a cycle
+        let set_parent_result = set_parent(&db, holiday_id, Some(carols_id)).await;
+        assert!(set_parent_result.is_err());
+
+        // deleting the middle tag moves its children up a level
+        delete(&db, christmas_id).await.unwrap();
+        let carols = get_by_id(&db, carols_id).await.unwrap();
+        assert_eq!(carols["parent_id"], holiday_id);
+
+        delete(&db, carols_id).await.unwrap();
+        delete(&db, holiday_id).await.unwrap();
+
+        let _ = db.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_group_color() {
+        let db = init().await.unwrap();
+
+        let group_id = crate::services::tag_groups::add(
+            &db,
+            String::from("test seasons"),
+            Some(String::from("#00ff00")),
+        )
+        .await
+        .unwrap();
+        let tag_id = add(&db, String::from("test spring"), None, None, None)
+            .await
+            .unwrap();
+
+        set_group(&db, tag_id, Some(group_id)).await.unwrap();
+        let tag = get_by_id(&db, tag_id).await.unwrap();
+        assert_eq!(tag["color"], "#00ff00");
+
+        // tags still on the group's color follow it
+        crate::services::tag_groups::update(
+            &db,
+            group_id,
+            String::from("test seasons"),
+            Some(String::from("#0000ff")),
+        )
+        .await
+        .unwrap();
+        let tag = get_by_id(&db, tag_id).await.unwrap();
+        assert_eq!(tag["color"], "#0000ff");
+
+        delete(&db, tag_id).await.unwrap();
+        crate::services::tag_groups::delete(&db, group_id)
+            .await
+            .unwrap();
+
+        let _ = db.close().await;
+    }
+}
@@ -0,0 +1,167 @@
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait};
+
+use crate::entities::{part_files, parts, scores};
+use crate::{render, utils};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Where thumbnails are kept, inside the library so they move with it.
+pub fn cache_dir(working_directory: &str) -> PathBuf {
+    Path::new(working_directory)
+        .join(".cache")
+        .join("thumbnails")
+}
+
+pub async fn get_by_score(
+    db: &DatabaseConnection,
+    cache_dir: &Path,
+    score_id: i32,
+    include_data: bool,
+) -> Result<Value, DbErr> {
+    let score = scores::Entity::find_by_id(score_id).one(db).await?;
+    match score {
+        Some(score) => get(db, cache_dir, "score", &score.path, include_data).await,
+        None => Err(DbErr::RecordNotFound(format!(
+            "Score with id {} not found",
+            score_id
+        ))),
+    }
+}
+
+pub async fn get_by_part(
+    db: &DatabaseConnection,
+    cache_dir: &Path,
+    part_id: i32,
+    include_data: bool,
+) -> Result<Value, DbErr> {
+    let part = parts::Entity::find_by_id(part_id).one(db).await?;
+    match part {
+        Some(part) => get(db, cache_dir, "part", &part.path, include_data).await,
+        None => Err(DbErr::RecordNotFound(format!(
+            "Part with id {} not found",
+            part_id
+        ))),
+    }
+}
+
+/// Deletes every cached thumbnail. They are rendered again when next asked for.
+pub fn clear(cache_dir: &Path) -> Result<(), DbErr> {
+    if cache_dir.exists() {
+        fs::remove_dir_all(cache_dir)
+            .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string())))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the thumbnail of a file, rendering it when there is none for what's
+/// in the file now. Thumbnails are named after the file's hash, so an edited
+/// file gets a new one, and files with the same contents share one.
+async fn get(
+    db: &DatabaseConnection,
+    cache_dir: &Path,
+    kind: &str,
+    path: &Option<String>,
+    include_data: bool,
+) -> Result<Value, DbErr> {
+    let path = match path {
+        Some(path) => path,
+        None => {
+            return Err(DbErr::Query(sea_orm::RuntimeErr::Internal(format!(
+                "There is no file to preview for this {}",
+                kind
+            ))))
+        }
+    };
+
+    let hash = utils::sha256_file(path)
+        .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string())))?;
+    let thumbnail = cache_dir.join(format!("{}.png", hash));
+
+    if !thumbnail.exists() {
+        render::first_page(path, &thumbnail)
+            .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string())))?;
+        remove_unused(db, cache_dir, &hash).await?;
+    }
+
+    let data = match include_data {
+        true => {
+            let bytes = fs::read(&thumbnail)
+                .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(e.to_string())))?;
+            Some(STANDARD.encode(bytes))
+        }
+        false => None,
+    };
+
+    Ok(serde_json::json!({
+        "path": thumbnail,
+        "hash": hash,
+        "data": data,
+    }))
+}
+
+/// Removes the thumbnails of files no score, part or earlier part version has
+/// anymore, such as ones that were edited, replaced or deleted.
+async fn remove_unused(db: &DatabaseConnection, cache_dir: &Path, hash: &str) -> Result<(), DbErr> {
+    let entries = match fs::read_dir(cache_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(()),
+    };
+
+    let mut hashes: HashSet<String> = HashSet::from([hash.to_string()]);
+    hashes.extend(
+        scores::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|score| score.sha256),
+    );
+    hashes.extend(
+        parts::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|part| part.sha256),
+    );
+    hashes.extend(
+        part_files::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|part_file| part_file.sha256),
+    );
+
+    for entry in entries.flatten() {
+        if is_unused(&entry.file_name().to_string_lossy(), &hashes) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+
+    Ok(())
+}
+
+fn is_unused(file_name: &str, hashes: &HashSet<String>) -> bool {
+    file_name
+        .strip_suffix(".png")
+        .is_some_and(|hash| !hashes.contains(hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_unused() {
+        let hashes = HashSet::from([String::from("abc")]);
+        assert!(is_unused("def.png", &hashes));
+        assert!(!is_unused("abc.png", &hashes));
+        // anything else in the folder is left alone
+        assert!(!is_unused(".DS_Store", &hashes));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::utils;
+use crate::{naming, utils};
 use std::{collections::BTreeMap, path::PathBuf};
 
 use serde_json::Value;
@@ -10,6 +10,8 @@ const SETTINGS_FILE: &str = "settings.json";
 macro_rules! pub_struct {
   ($name:ident {$($field:ident : $t:ty,)*}) => {
     #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+    // settings files written before a field existed fall back to its default
+    #[serde(default)]
     pub struct $name {
       $(pub $field: $t,)*
     }
@@ -23,6 +25,15 @@ pub_struct!(AppSettings {
     stay_on_top: bool,
 
     working_directory: String,
+    // life_plus_50 / life_plus_70 / us
+    copyright_rule: String,
+    // clone identical score and part files from one copy in the library's store
+    store_files_by_hash: bool,
+    // downloads dropped here are queued as drafts, empty when there is no inbox
+    inbox_directory: String,
+    // where pieces and their files go in the library, see naming.rs for the placeholders
+    folder_template: String,
+    file_template: String,
 });
 
 impl AppSettings {
@@ -34,6 +45,18 @@ impl AppSettings {
             auto_update: "prompt".into(),
             stay_on_top: false,
             working_directory: default_path.to_str().unwrap().into(),
+            copyright_rule: "life_plus_70".into(),
+            store_files_by_hash: false,
+            inbox_directory: String::new(),
+            folder_template: naming::DEFAULT_FOLDER_TEMPLATE.into(),
+            file_template: naming::DEFAULT_FILE_TEMPLATE.into(),
+        }
+    }
+
+    pub fn templates(&self) -> naming::Templates {
+        naming::Templates {
+            folder: self.folder_template.clone(),
+            file: self.file_template.clone(),
         }
     }
 
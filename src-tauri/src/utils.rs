@@ -1,7 +1,8 @@
 use directories::ProjectDirs;
 use eyre::Result;
+use sha2::{Digest, Sha256};
 use std::{
-    fs,
+    fs, io,
     path::{Path, PathBuf},
 };
 pub fn data_dir() -> PathBuf {
@@ -30,3 +31,10 @@ pub fn create_file<P: AsRef<Path>>(filename: P) -> Result<()> {
     fs::File::create(filename)?;
     Ok(())
 }
+
+pub fn sha256_file<P: AsRef<Path>>(filename: P) -> Result<String> {
+    let mut file = fs::File::open(filename)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
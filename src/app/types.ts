@@ -5,23 +5,41 @@ export interface Piece {
   path: string;
   difficulty?: number;
   notes: string;
+  location_cabinet?: string;
+  location_drawer?: string;
+  location_box?: string;
+  copyright_status?: "public_domain" | "protected" | "unknown";
+  copyright_reason?: string;
+  copyright_rule?: string;
+  rating?: number;
+  audience_rating?: number;
+  is_favorite?: boolean;
   created_at: string;
   updated_at: string;
-  composers: Musician[];
-  arrangers: Musician[];
-  orchestrators: Musician[];
-  transcribers: Musician[];
-  lyricists: Musician[];
+  roles: { role: string; musicians: Musician[] }[];
   scores: Score[];
   parts: Part[];
   setlists: Setlist[];
   tags: Tag[];
+  acquisitions?: Acquisition[];
+  performance_count?: number;
+  last_performed?: string;
+  rehearsal_notes?: RehearsalNote[];
 }
 
 export interface Musician {
   id: number;
   first_name: string;
   last_name?: string;
+  birth_year?: number;
+  death_year?: number;
+  nationality?: string;
+  sort_name?: string;
+  display_name?: string;
+  is_anonymous?: boolean;
+  full_name?: string;
+  sort_key?: string;
+  aliases?: string[];
   created_at: string;
   updated_at: string;
 }
@@ -30,6 +48,13 @@ export interface Score {
   id: number;
   name: string;
   path?: string;
+  page_count?: number;
+  page_size?: string;
+  pdf_title?: string;
+  pdf_author?: string;
+  pdf_producer?: string;
+  file_size?: number;
+  sha256?: string;
   created_at: string;
   updated_at: string;
 }
@@ -37,10 +62,33 @@ export interface Part {
   id: number;
   name: string;
   path?: string;
+  copies?: number;
+  page_count?: number;
+  page_size?: string;
+  pdf_title?: string;
+  pdf_author?: string;
+  pdf_producer?: string;
+  file_size?: number;
+  sha256?: string;
   created_at: string;
   updated_at: string;
   piece_id: number;
   instruments: Instrument[];
+  files?: PartFile[];
+}
+
+export interface PartFile {
+  id: number;
+  part_id: number;
+  path: string;
+  label: "original" | "marked" | "large print" | "transposed";
+  is_primary: boolean;
+  version: number;
+  replaced_at?: string;
+  sha256?: string;
+  file_size?: number;
+  created_at: string;
+  updated_at: string;
 }
 
 export interface EditPiece {
@@ -66,6 +114,9 @@ export interface EditPart {
   name: string;
   instruments: Instrument[];
   file: ByteFile | null;
+  // set on parts loaded from the library, which keep their file unless replaced
+  saved?: boolean;
+  path?: string;
 }
 
 export interface EditScore {
@@ -73,12 +124,20 @@ export interface EditScore {
   renaming: boolean;
   name: string;
   file: ByteFile | null;
+  saved?: boolean;
+  path?: string;
 }
 export interface Instrument {
   id: number;
   name: string;
   category?: string;
   is_default: boolean;
+  transposition?: number;
+  clefs?: string;
+  range_low?: string;
+  range_high?: string;
+  family?: string;
+  score_order?: number;
   created_at: Date;
   updated_at: Date;
 }
@@ -86,6 +145,9 @@ export interface Tag {
   id: number;
   name: string;
   color: string;
+  parent_id?: number;
+  group_id?: number;
+  children?: Tag[];
   created_at: Date;
   updated_at: Date;
 }
@@ -111,3 +173,169 @@ export interface Setlist {
   created_at: string;
   updated_at: string;
 }
+
+export interface Player {
+  id: number;
+  name: string;
+  email?: string;
+  phone?: string;
+  notes?: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface Acquisition {
+  id: number;
+  piece_id: number;
+  acquisition_type:
+    | "purchased"
+    | "rental"
+    | "borrowed"
+    | "public_domain"
+    | "commissioned";
+  vendor?: string;
+  cost?: number;
+  currency?: string;
+  acquired_date?: string;
+  rental_start?: string;
+  rental_end?: string;
+  returned_date?: string;
+  performance_rights?: string;
+  recording_rights?: string;
+  notes?: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface Performance {
+  id: number;
+  date: string;
+  venue?: string;
+  ensemble_id?: number;
+  ensemble_name?: string;
+  conductor?: string;
+  setlist_id?: number;
+  notes?: string;
+  created_at: string;
+  updated_at: string;
+  pieces: { id: number; title: string }[];
+}
+
+export interface CalendarEvent {
+  id: number;
+  event_type: "concert" | "rehearsal" | "sectional";
+  title: string;
+  starts_at: string;
+  ends_at?: string;
+  call_time?: string;
+  location?: string;
+  setlist_id?: number;
+  setlist_name?: string;
+  notes?: string;
+  uid: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface RehearsalNote {
+  id: number;
+  piece_id: number;
+  note: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface Binder {
+  path: string;
+  name: string;
+  pieces: {
+    id: number;
+    title: string;
+    first_page: number;
+    parts: { id: number; name: string }[];
+  }[];
+  missing: { id: number; title: string }[];
+}
+
+export interface Thumbnail {
+  path: string;
+  hash: string;
+  data?: string;
+}
+
+export interface DraftFile {
+  id: number;
+  path: string;
+  entry?: string;
+  name: string;
+  kind: "score" | "part";
+  instrument_id?: number;
+  instrument_name?: string;
+}
+
+export interface Draft {
+  id: number;
+  title: string;
+  source?: string;
+  status: "pending" | "dismissed";
+  files: DraftFile[];
+  created_at: string;
+  updated_at: string;
+}
+
+export interface ImportedFile {
+  name: string;
+  path: string;
+  instrument_id?: number;
+  instrument_name?: string;
+}
+
+export interface ImportedPiece {
+  title: string;
+  composer?: string;
+  composer_id?: number;
+  arranger?: string;
+  arranger_id?: number;
+  year_published?: number;
+  exists: boolean;
+  piece_id?: number;
+  scores: ImportedFile[];
+  parts: ImportedFile[];
+}
+
+export interface ImportReport {
+  dry_run: boolean;
+  pieces: ImportedPiece[];
+  created: number;
+  new_musicians: string[];
+  unmatched: string[];
+  failed: { title: string; error: string }[];
+}
+
+export interface NamingTemplates {
+  folder: string;
+  file: string;
+}
+
+export interface ReorganizedPiece {
+  id: number;
+  title: string;
+  from: string;
+  to: string;
+  files: { from: string; to: string }[];
+}
+
+export interface ReorganizeReport {
+  dry_run: boolean;
+  pieces: ReorganizedPiece[];
+  moved: number;
+  renamed: number;
+  failed: { id: number; title: string; error: string }[];
+}
+
+export interface DuplicateMatch {
+  id: number;
+  title: string;
+  score: number;
+  reasons: string[];
+}
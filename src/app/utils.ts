@@ -1,4 +1,5 @@
 import { type } from "@tauri-apps/api/os";
+import { Musician, Piece, Tag } from "./types";
 
 export async function isWindows() {
   return (await type()) === "Windows_NT";
@@ -37,3 +38,13 @@ export function getContrast(backgroundColor: string, foregroundColor: string) {
   const contrast = (bg.luminance + 0.05) / (fg.luminance + 0.05);
   return contrast;
 }
+
+// the musicians credited on a piece with the role, e.g. "composer"
+export function credited(piece: Piece, role: string): Musician[] {
+  return piece.roles.find((credit) => credit.role === role)?.musicians ?? [];
+}
+
+// tags_get_all returns the tag tree; this lists every tag, each before its children
+export function flattenTags(tags: Tag[]): Tag[] {
+  return tags.flatMap((tag) => [tag, ...flattenTags(tag.children ?? [])]);
+}
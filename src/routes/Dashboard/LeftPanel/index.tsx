@@ -1,5 +1,6 @@
 import { useAppDispatch, useAppSelector } from "@/app/hooks";
 import { Setlist, Tag } from "@/app/types";
+import { flattenTags } from "@/app/utils";
 import { EditTagDialog } from "@/components/EditTagDialog";
 import { Button } from "@/components/ui/button";
 import { Collapsible, CollapsibleContent, CollapsibleTrigger } from "@/components/ui/collapsible";
@@ -61,7 +62,7 @@ export function LeftPanel() {
   }, []);
 
   async function fetchTags() {
-    const tags = (await invoke("tags_get_all")) as Tag[];
+    const tags = flattenTags((await invoke("tags_get_all")) as Tag[]);
     dispatch(setTags({ tags }));
   }
 
@@ -1,6 +1,6 @@
 import { useAppDispatch, useAppSelector } from "@/app/hooks";
 import { Piece, Tag } from "@/app/types";
-import { isWindows } from "@/app/utils";
+import { credited, isWindows } from "@/app/utils";
 import { Modal } from "@/components/Modal";
 import { Badge } from "@/components/ui/badge";
 import { Button } from "@/components/ui/button";
@@ -173,7 +173,7 @@ export function Table() {
     if (filter.composers.length > 0) {
       filteringPieces = filteringPieces.filter((piece) => {
         return filter.composers.every((composer) => {
-          return piece.composers.some(
+          return credited(piece, "composer").some(
             (pieceComposer) => pieceComposer.id === composer.id
           );
         });
@@ -183,7 +183,7 @@ export function Table() {
     if (filter.arrangers.length > 0) {
       filteringPieces = filteringPieces.filter((piece) => {
         return filter.arrangers.every((arranger) => {
-          return piece.arrangers.some(
+          return credited(piece, "arranger").some(
             (pieceArranger) => pieceArranger.id === arranger.id
           );
         });
@@ -193,7 +193,7 @@ export function Table() {
     if (filter.orchestrators.length > 0) {
       filteringPieces = filteringPieces.filter((piece) => {
         return filter.orchestrators.every((orchestrator) => {
-          return piece.orchestrators.some(
+          return credited(piece, "orchestrator").some(
             (pieceOrchestrator) => pieceOrchestrator.id === orchestrator.id
           );
         });
@@ -203,7 +203,7 @@ export function Table() {
     if (filter.transcribers.length > 0) {
       filteringPieces = filteringPieces.filter((piece) => {
         return filter.transcribers.every((transcriber) => {
-          return piece.transcribers.some(
+          return credited(piece, "transcriber").some(
             (pieceTranscriber) => pieceTranscriber.id === transcriber.id
           );
         });
@@ -213,7 +213,7 @@ export function Table() {
     if (filter.lyricists.length > 0) {
       filteringPieces = filteringPieces.filter((piece) => {
         return filter.lyricists.every((lyricist) => {
-          return piece.lyricists.some(
+          return credited(piece, "lyricist").some(
             (pieceLyricist) => pieceLyricist.id === lyricist.id
           );
         });
@@ -250,7 +250,7 @@ export function Table() {
                     {info.row.original.title}
                   </span>
                   <span className="text-body-small-default">
-                    {info.row.original.composers
+                    {credited(info.row.original, "composer")
                       .map((composer) =>
                         composer.last_name
                           ? `${composer.first_name} ${composer.last_name}`
@@ -362,8 +362,7 @@ export function Table() {
       {
         id: "composers",
         accessorFn: (row) => {
-          const { composers } = row;
-          return composers
+          return credited(row, "composer")
             .map((composer) =>
               composer.last_name
                 ? `${composer.last_name}, ${composer.first_name}`
@@ -647,7 +646,7 @@ export function Table() {
       {
         name: "composers",
         getFn: (piece) => {
-          return piece.composers
+          return credited(piece, "composer")
             .map((composer) => `${composer.first_name} ${composer.last_name}`)
             .join(" ");
         },
@@ -4,6 +4,7 @@ import { mdiClose } from "@mdi/js";
 import Icon from "@mdi/react";
 import { clearPiece } from "../reducers/previewSlice";
 import { Piece } from "@/app/types";
+import { credited } from "@/app/utils";
 
 export function Header(props: { piece: Piece }) {
 
@@ -18,7 +19,7 @@ export function Header(props: { piece: Piece }) {
   return (
     <span className="flex justify-between items-start py-[14px] gap-[8px] px-[14px]">
       <span className="flex flex-wrap text-body-bold text-fg.0">
-        {piece.composers
+        {credited(piece, "composer")
           .map((composer) =>
             [composer.first_name, composer.last_name].join(" ")
           )
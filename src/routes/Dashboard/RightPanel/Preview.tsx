@@ -1,5 +1,6 @@
 import { useAppDispatch } from "@/app/hooks";
 import { Piece, Tag } from "@/app/types";
+import { credited } from "@/app/utils";
 import { Badge } from "@/components/ui/badge";
 import { Button } from "@/components/ui/button";
 import { Collapsible, CollapsibleContent, CollapsibleTrigger } from "@/components/ui/collapsible";
@@ -28,23 +29,23 @@ export function Preview(props: { piece: Piece }) {
   const roles = [
     {
       name: "Composed By",
-      musicians: piece.composers,
+      musicians: credited(piece, "composer"),
     },
     {
       name: "Arranged By",
-      musicians: piece.arrangers,
+      musicians: credited(piece, "arranger"),
     },
     {
       name: "Orchestrated By",
-      musicians: piece.orchestrators,
+      musicians: credited(piece, "orchestrator"),
     },
     {
       name: "Transcribed By",
-      musicians: piece.transcribers,
+      musicians: credited(piece, "transcriber"),
     },
     {
       name: "Lyrics By",
-      musicians: piece.lyricists,
+      musicians: credited(piece, "lyricist"),
     }
   ]
 
@@ -1,5 +1,6 @@
 import { useAppDispatch, useAppSelector } from "@/app/hooks";
 import { ByteFile, Piece } from "@/app/types";
+import { credited } from "@/app/utils";
 import { Button } from "@/components/ui/button";
 import { Progress } from "@/components/ui/progress";
 import { toast } from "@/components/ui/use-toast";
@@ -43,36 +44,24 @@ export function DragUpload() {
       if (state.piece) {
         const { piece: uploadedPiece }: { piece: Piece } = state;
 
-        const {
-          id,
-          arrangers,
-          composers,
-          lyricists,
-          notes,
-          orchestrators,
-          parts,
-          scores,
-          tags,
-          title,
-          transcribers,
-          difficulty,
-          year_published,
-        } = uploadedPiece;
+        const { id, notes, parts, scores, tags, title, difficulty, year_published } =
+          uploadedPiece;
 
         dispatch(
           setPiece({
             id,
-            arrangers,
-            composers,
-            lyricists,
+            arrangers: credited(uploadedPiece, "arranger"),
+            composers: credited(uploadedPiece, "composer"),
+            lyricists: credited(uploadedPiece, "lyricist"),
             notes,
-            orchestrators,
+            orchestrators: credited(uploadedPiece, "orchestrator"),
             parts: parts
               ? parts.map((part) => ({
                 ...part,
                 show: false,
                 renaming: false,
                 file: null,
+                saved: true,
               }))
               : [],
             scores: scores
@@ -81,11 +70,12 @@ export function DragUpload() {
                 show: false,
                 renaming: false,
                 file: null,
+                saved: true,
               }))
               : [],
             tags,
             title,
-            transcribers,
+            transcribers: credited(uploadedPiece, "transcriber"),
             difficulty,
             yearPublished: year_published,
           })
@@ -1,3 +1,4 @@
+import { confirm } from "@tauri-apps/api/dialog";
 import classNames from "classnames";
 import { useCallback } from "react";
 import { useLocation, useNavigate } from "react-router-dom";
@@ -5,7 +6,7 @@ import { useAppDispatch, useAppSelector } from "@/app/hooks";
 import { clearFiles } from "../filesSlice";
 import { setLoading } from "../loadingSlice";
 import { clearPiece } from "../pieceSlice";
-import { createPiece } from "./createPiece";
+import { createPiece, findDuplicates } from "./createPiece";
 import { StepEvent, StepState } from "./stepMachine";
 import { updatePiece } from "./updatePiece";
 
@@ -18,6 +19,20 @@ export function Navbar(props: { stepState: StepState; sendStep: StepEvent }) {
   const { state } = useLocation();
 
   async function handleClickFinish() {
+    if (!piece.id) {
+      const duplicates = await findDuplicates(piece);
+      if (duplicates.length > 0) {
+        const titles = duplicates
+          .map((duplicate) => `"${duplicate.title}"`)
+          .join(", ");
+        const confirmed = await confirm(
+          `This looks like ${titles}, already in the library. Create it anyway?`,
+          { title: "Possible duplicate", type: "warning" }
+        );
+        if (!confirmed) return;
+      }
+    }
+
     dispatch(setLoading(true));
     if (piece.id) {
       await updatePiece(piece, state?.piece?.path);
@@ -1,5 +1,6 @@
 import { useAppDispatch, useAppSelector } from "@/app/hooks";
 import { Tag } from "@/app/types";
+import { flattenTags } from "@/app/utils";
 import { EditTagDialog } from "@/components/EditTagDialog";
 import { SelectTags } from "@/components/SelectTags";
 import { Dialog, DialogContent, DialogTrigger } from "@/components/ui/dialog";
@@ -34,7 +35,7 @@ export function GeneralInformation() {
   }, []);
 
   async function fetchTags() {
-    const tags = (await invoke("tags_get_all")) as Tag[];
+    const tags = flattenTags((await invoke("tags_get_all")) as Tag[]);
     setAllTags(tags);
   }
 
@@ -1,38 +1,35 @@
 import { invoke } from "@tauri-apps/api";
-import { createDir, writeBinaryFile } from "@tauri-apps/api/fs";
-import { EditPiece } from "@/app/types";
+import { writeBinaryFile } from "@tauri-apps/api/fs";
+import { DuplicateMatch, EditPiece } from "@/app/types";
+
+// pieces in the library the new one may be a copy of, so the user can be warned
+export async function findDuplicates(piece: EditPiece) {
+  const files = [...piece.scores, ...piece.parts].flatMap((item) =>
+    item.file ? [item.file.name] : []
+  );
+
+  return (await invoke("duplicates_find_candidates", {
+    candidate: {
+      title: piece.title,
+      composer_ids: piece.composers.map((composer) => composer.id),
+      arranger_ids: piece.arrangers.map((arranger) => arranger.id),
+      file_paths: files,
+    },
+  })) as DuplicateMatch[];
+}
 
 export async function createPiece(piece: EditPiece) {
-  const principalComposer = piece.composers[0];
-
-  const composerName = principalComposer.last_name
-    ? `${principalComposer.last_name}, ${principalComposer.first_name}`
-    : `${principalComposer.first_name}`;
-
-  const workingDir = await invoke("get_working_directory");
-
+  // the user was already warned about likely duplicates
   const pieceId = (await invoke("pieces_add", {
     title: piece.title,
     yearPublished: piece.yearPublished,
     path: "",
     difficulty: piece.difficulty,
     notes: piece.notes,
+    composerIds: piece.composers.map((composer) => composer.id),
+    force: true,
   })) as number;
 
-  const pathSlash = window.navigator.userAgent.includes("Windows") ? "\\" : "/";
-
-  const path = `${workingDir}${pathSlash}${principalComposer.id}_${composerName}${pathSlash}${pieceId}_${piece.title}`;
-  await createDir(path, { recursive: true });
-
-  await invoke("pieces_update", {
-    id: pieceId,
-    title: piece.title,
-    yearPublished: piece.yearPublished,
-    path,
-    difficulty: piece.difficulty,
-    notes: piece.notes,
-  });
-
   // tags
   await invoke("pieces_set_tags", {
     pieceId,
@@ -73,27 +70,52 @@ export async function createPiece(piece: EditPiece) {
     role: "lyricist",
   });
 
+  // the folder is named after the credits, so it comes once they are set
+  const path = (await invoke("pieces_move_to_library_folder", {
+    pieceId,
+  })) as string;
+
+  const pathSlash = window.navigator.userAgent.includes("Windows") ? "\\" : "/";
+
   // scores
   for (const [index, score] of piece.scores.entries()) {
     const scorePath =
-      score.file && `${path}${pathSlash}${0}.${index + 1}_${score.name}.pdf`;
+      score.file &&
+      `${path}${pathSlash}${await invoke("pieces_get_library_file_name", {
+        pieceId,
+        isScore: true,
+        order: index + 1,
+        name: score.name,
+      })}`;
+
+    // create file first so the backend can read the PDF
+    if (score.file && scorePath) {
+      await writeBinaryFile(scorePath, score.file.bytearray);
+    }
 
     await invoke("scores_add", {
       name: score.name,
       path: scorePath,
       pieceId,
     });
-
-    // create file
-    if (score.file && scorePath) {
-      await writeBinaryFile(scorePath, score.file.bytearray);
-    }
   }
 
   // parts
   for (const [index, part] of piece.parts.entries()) {
     const partPath =
-      part.file && `${path}${pathSlash}${1}.${index + 1}_${part.name}.pdf`;
+      part.file &&
+      `${path}${pathSlash}${await invoke("pieces_get_library_file_name", {
+        pieceId,
+        isScore: false,
+        order: index + 1,
+        name: part.name,
+        instrument: part.instruments[0]?.name,
+      })}`;
+
+    // create file first so the backend can read the PDF
+    if (part.file && partPath) {
+      await writeBinaryFile(partPath, part.file.bytearray);
+    }
 
     const partId = (await invoke("parts_add", {
       name: part.name,
@@ -106,10 +128,5 @@ export async function createPiece(piece: EditPiece) {
       partId,
       instrumentIds: part.instruments.map((instrument) => instrument.id),
     });
-
-    // create file
-    if (part.file && partPath) {
-      await writeBinaryFile(partPath, part.file.bytearray);
-    }
   }
 }
@@ -1,28 +1,13 @@
 import { invoke } from "@tauri-apps/api";
-import { createDir, writeBinaryFile } from "@tauri-apps/api/fs";
-import { EditPiece } from "@/app/types";
+import { exists, removeFile, writeBinaryFile } from "@tauri-apps/api/fs";
+import { ByteFile, EditPiece, PartFile, Piece } from "@/app/types";
 
 export async function updatePiece(piece: EditPiece, path: string) {
-  await invoke("delete_dir", { path });
-
-  const principalComposer = piece.composers[0];
-
-  const composerName = principalComposer.last_name
-    ? `${principalComposer.last_name}, ${principalComposer.first_name}`
-    : `${principalComposer.first_name}`;
-
-  const workingDir = await invoke("get_working_directory");
-
-  const pathSlash = window.navigator.userAgent.includes("Windows") ? "\\" : "/";
-
-  const newPath = `${workingDir}${pathSlash}${principalComposer.id}_${composerName}${pathSlash}${piece.id}_${piece.title}`;
-  await createDir(newPath, { recursive: true });
-
   await invoke("pieces_update", {
     id: piece.id,
     title: piece.title,
     yearPublished: piece.yearPublished,
-    path: newPath,
+    path,
     difficulty: piece.difficulty,
     notes: piece.notes,
   });
@@ -62,44 +47,127 @@ export async function updatePiece(piece: EditPiece, path: string) {
     role: "lyricist",
   });
 
-  await invoke("pieces_drop_scores", { pieceId: piece.id });
-  await invoke("pieces_drop_parts", { pieceId: piece.id });
+  // the folder is named after the credits, so it comes once they are set
+  const newPath = (await invoke("pieces_move_to_library_folder", {
+    pieceId: piece.id,
+  })) as string;
+
+  const pathSlash = window.navigator.userAgent.includes("Windows") ? "\\" : "/";
+
+  // scores and parts are updated in place so their loans, assignments and
+  // other files stay with them
+  const saved = (await invoke("pieces_get_by_id", { id: piece.id })) as Piece;
+
+  for (const score of saved.scores) {
+    if (piece.scores.some((editScore) => editScore.saved && editScore.id === score.id)) {
+      continue;
+    }
+
+    await invoke("scores_delete", { id: score.id });
+    await removeIfExists(score.path);
+  }
+
+  for (const part of saved.parts) {
+    if (piece.parts.some((editPart) => editPart.saved && editPart.id === part.id)) {
+      continue;
+    }
+
+    await invoke("parts_delete", { id: part.id });
+    await removeIfExists(part.path);
+  }
 
   for (const [index, score] of piece.scores.entries()) {
-    const scorePath =
-      score.file && `${path}${pathSlash}${0}.${index + 1}_${score.name}.pdf`;
+    const savedScore = score.saved
+      ? saved.scores.find((savedScore) => savedScore.id === score.id)
+      : undefined;
+    const scorePath = await writeIfPicked(
+      score.file,
+      score.path,
+      `${newPath}${pathSlash}.editing score ${index + 1}.pdf`
+    );
+
+    if (!savedScore) {
+      await invoke("scores_add", {
+        name: score.name,
+        path: scorePath,
+        pieceId: piece.id,
+      });
+      continue;
+    }
 
-    await invoke("scores_add", {
+    await invoke("scores_update", {
+      id: savedScore.id,
       name: score.name,
-      path: scorePath,
-      pieceId: piece.id,
+      path: scorePath ?? savedScore.path,
     });
-
-    // create file
-    if (score.file && scorePath) {
-      await writeBinaryFile(scorePath, score.file.bytearray);
+    if (scorePath) {
+      await removeIfExists(savedScore.path);
     }
   }
 
   for (const [index, part] of piece.parts.entries()) {
-    const partPath =
-      part.file && `${path}${pathSlash}${1}.${index + 1}_${part.name}.pdf`;
-
-    const partId = (await invoke("parts_add", {
-      name: part.name,
-      path: partPath,
-      pieceId: piece.id,
-    })) as number;
+    const savedPart = part.saved
+      ? saved.parts.find((savedPart) => savedPart.id === part.id)
+      : undefined;
+    const partPath = await writeIfPicked(
+      part.file,
+      part.path,
+      `${newPath}${pathSlash}.editing part ${index + 1}.pdf`
+    );
+
+    let partId = savedPart?.id;
+    if (!savedPart) {
+      partId = (await invoke("parts_add", {
+        name: part.name,
+        path: partPath,
+        pieceId: piece.id,
+      })) as number;
+    } else {
+      let newPartPath = partPath ?? savedPart.path;
+      if (partPath && savedPart.path) {
+        const [primary] = (await invoke("part_files_get_by_part", {
+          partId,
+        })) as PartFile[];
+        if (primary?.is_primary) {
+          // the replaced file is kept as an earlier version of the part
+          await invoke("part_files_replace", { id: primary.id, source: partPath });
+          await removeIfExists(partPath);
+          newPartPath = savedPart.path;
+        }
+      }
+
+      await invoke("parts_update", {
+        id: partId,
+        name: part.name,
+        path: newPartPath,
+      });
+    }
 
     // instruments
     await invoke("parts_set_instruments", {
       partId,
       instrumentIds: part.instruments.map((instrument) => instrument.id),
     });
+  }
 
-    // create file
-    if (part.file && partPath) {
-      await writeBinaryFile(partPath, part.file.bytearray);
-    }
+  // new and renamed files get their names from the library templates
+  await invoke("library_rename_files", { pieceId: piece.id });
+}
+
+// writes a file picked in the wizard, unless it is the one the item already has
+async function writeIfPicked(
+  file: ByteFile | null,
+  currentPath: string | undefined,
+  temporaryPath: string
+) {
+  if (!file || file.name === currentPath) return undefined;
+
+  await writeBinaryFile(temporaryPath, file.bytearray);
+  return temporaryPath;
+}
+
+async function removeIfExists(path: string | undefined) {
+  if (path && (await exists(path))) {
+    await removeFile(path);
   }
 }
@@ -9,6 +9,7 @@ import {
   EditPiece,
 } from "../../app/types";
 import { invoke } from "@tauri-apps/api";
+import { credited } from "../../app/utils";
 import undoable from "redux-undo";
 
 const initialState: EditPiece = {
@@ -376,11 +377,11 @@ export const pieceSlice = createSlice({
         difficulty: piece.difficulty,
         notes: piece.notes,
         tags: piece.tags,
-        composers: piece.composers,
-        arrangers: piece.arrangers,
-        transcribers: piece.transcribers,
-        orchestrators: piece.orchestrators,
-        lyricists: piece.lyricists,
+        composers: credited(piece, "composer"),
+        arrangers: credited(piece, "arranger"),
+        transcribers: credited(piece, "transcriber"),
+        orchestrators: credited(piece, "orchestrator"),
+        lyricists: credited(piece, "lyricist"),
         parts: piece.parts,
         scores: piece.scores,
       };
@@ -0,0 +1,36 @@
+import { invoke } from "@tauri-apps/api/tauri";
+import { useEffect, useState } from "react";
+import { SettingsEntry } from "./components/SettingsEntry";
+
+export function Previews() {
+  const [renderer, setRenderer] = useState<string | null>();
+
+  useEffect(() => {
+    async function initSetting() {
+      const invokeGetRenderer = await invoke("thumbnails_get_renderer");
+      setRenderer(invokeGetRenderer as string | null);
+    }
+
+    initSetting();
+  }, []);
+
+  async function handleClickClearPreviews() {
+    await invoke("thumbnails_clear");
+  }
+
+  function description() {
+    if (renderer === undefined) return "Loading...";
+    if (renderer === null) {
+      return "PDF previews need poppler installed (pdftoppm must be on your PATH).";
+    }
+    return `PDF previews are rendered with ${renderer}.`;
+  }
+
+  return (
+    <SettingsEntry name="Previews" description={description()}>
+      <button className="button-default" onClick={handleClickClearPreviews}>
+        Clear Previews
+      </button>
+    </SettingsEntry>
+  );
+}
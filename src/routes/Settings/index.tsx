@@ -3,6 +3,7 @@ import { mdiHomeOutline } from "@mdi/js";
 import Icon from "@mdi/react";
 import { Link, useNavigate } from "react-router-dom";
 import { OpenOnStartup } from "./OpenOnStartup";
+import { Previews } from "./Previews";
 import { WorkingDirectory } from "./WorkingDirectory";
 export function Settings() {
   const navigate = useNavigate();
@@ -23,6 +24,7 @@ export function Settings() {
           <div className="flex flex-col gap-[14px]">
             <WorkingDirectory />
             <OpenOnStartup />
+            <Previews />
           </div>
         </div>
       </div>